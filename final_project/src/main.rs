@@ -1,11 +1,17 @@
 #![warn(clippy::all, clippy::pedantic)]
 
 use clap::Parser;
+use module_04_mcp_server::analyze_complexity::AnalyzeComplexityHandler;
+use module_04_mcp_server::calculator::{CalculatorBatchHandler, CalculatorHandler};
+use module_04_mcp_server::extract_files::ExtractFilesHandler;
+use module_04_mcp_server::read_file_chunked::ReadFileChunkedHandler;
+use pmcp::sandbox::Sandbox;
 use pmcp::server::{Server, ServerBuilder};
 use pmcp::tools::{
-    analyze_complexity_tool, calculator_tool, deep_analysis_tool, extract_files_tool,
+    analyze_complexity_tool, calculator_batch_tool, calculator_tool, extract_files_tool,
+    read_file_chunked_tool,
 };
-use pmcp::transport::{StdioTransport, Transport};
+use pmcp::transport::StdioTransport;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
@@ -41,13 +47,24 @@ async fn main() -> anyhow::Result<()> {
     info!("Starting Production MCP Server");
     info!("Version: {}", env!("CARGO_PKG_VERSION"));
 
+    let sandbox = Sandbox::new(std::env::current_dir()?)?;
     let server = ServerBuilder::new()
-        .with_tool(calculator_tool())
-        .with_tool(analyze_complexity_tool())
-        .with_tool(extract_files_tool())
-        .with_tool(deep_analysis_tool())
+        .with_handler(calculator_tool(), CalculatorHandler)
+        .with_handler(calculator_batch_tool(), CalculatorBatchHandler)
+        .with_handler(
+            analyze_complexity_tool(),
+            AnalyzeComplexityHandler::default(),
+        )
+        .with_handler(
+            extract_files_tool(),
+            ExtractFilesHandler::new(sandbox.clone()),
+        )
+        .with_handler(
+            read_file_chunked_tool(),
+            ReadFileChunkedHandler::new(sandbox),
+        )
         .with_max_request_size(10_485_760)
-        .build();
+        .build()?;
 
     info!(
         "Server configured with {} tools",
@@ -66,48 +83,41 @@ async fn main() -> anyhow::Result<()> {
 }
 
 async fn run_stdio_server(server: Server) -> anyhow::Result<()> {
-    let mut transport = StdioTransport::new();
+    let transport =
+        StdioTransport::new().with_compression(server.capabilities().supports_compression);
 
     info!("Stdio server ready");
+    shutdown_on_ctrl_c(&server);
 
-    loop {
-        match transport.receive().await {
-            Ok(request) => {
-                let response = server
-                    .handle_request(request)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Request handling error: {}", e))?;
-                transport
-                    .send(response)
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Transport send error: {}", e))?;
-            }
-            Err(e) => {
-                tracing::error!("Transport error: {}", e);
-                break;
-            }
-        }
-    }
-
-    Ok(())
+    server
+        .serve(transport)
+        .await
+        .map_err(|e| anyhow::anyhow!("Server error: {e}"))
 }
 
-async fn run_tcp_server(_server: Server, port: u16) -> anyhow::Result<()> {
+async fn run_tcp_server(server: Server, port: u16) -> anyhow::Result<()> {
     use tokio::net::TcpListener;
 
     let addr = format!("0.0.0.0:{}", port);
     let listener = TcpListener::bind(&addr).await?;
 
     info!("TCP server listening on {}", addr);
+    shutdown_on_ctrl_c(&server);
 
-    loop {
-        let (_socket, addr) = listener.accept().await?;
-        info!("New connection from {}", addr);
+    server
+        .serve_tcp(listener)
+        .await
+        .map_err(|e| anyhow::anyhow!("Server error: {e}"))
+}
 
-        tokio::spawn(async move {
-            info!("Handling connection from {}", addr);
-        });
-    }
+fn shutdown_on_ctrl_c(server: &Server) {
+    let server = server.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            info!("Shutdown requested, draining in-flight requests");
+            server.shutdown();
+        }
+    });
 }
 
 #[cfg(test)]
@@ -0,0 +1,19 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use module_01_foundations::fsm::{create_basic_fsm, Event};
+use std::hint::black_box;
+
+fn bench_process_event(c: &mut Criterion) {
+    let mut fsm = create_basic_fsm();
+    fsm.process_event(Event::Start).expect("Init -> Running");
+
+    // Pause and Resume return to Running, so the machine never runs dry.
+    c.bench_function("fsm process_event pause/resume", |b| {
+        b.iter(|| {
+            black_box(fsm.process_event(Event::Pause)).ok();
+            black_box(fsm.process_event(Event::Resume)).ok();
+        });
+    });
+}
+
+criterion_group!(benches, bench_process_event);
+criterion_main!(benches);
@@ -1,5 +1,8 @@
 use module_01_foundations::certainty::{CertaintyCalculator, GenerativeModel, SymbolicProver};
-use module_01_foundations::scope::{verify_constraint, DomainScope, ScopeAnalyzer, K_CONSTANT};
+use module_01_foundations::precision::{round_to, DISPLAY_PLACES};
+use module_01_foundations::scope::{
+    calculate_tradeoff, verify_constraint, DomainScope, ScopeAnalyzer, K_CONSTANT,
+};
 use std::time::Instant;
 
 fn main() {
@@ -28,12 +31,12 @@ fn demonstrate_symbolic_prover() {
     for case in test_cases {
         let certainty = prover.calculate(case);
         let scope = scope_analyzer.analyze(case);
-        let product = certainty * scope;
+        let product = calculate_tradeoff(certainty, scope);
 
         println!("  Input: '{}...'", &case[..case.len().min(20)]);
-        println!("    Certainty: {:.2}", certainty);
-        println!("    Scope: {:.2}", scope);
-        println!("    Product: {:.2}", product);
+        println!("    Certainty: {:.2}", round_to(certainty, DISPLAY_PLACES));
+        println!("    Scope: {:.2}", round_to(scope, DISPLAY_PLACES));
+        println!("    Product: {:.2}", round_to(product, DISPLAY_PLACES));
         println!(
             "    Valid: {}",
             if verify_constraint(certainty, scope) {
@@ -60,12 +63,12 @@ fn demonstrate_generative_model() {
     for case in test_cases {
         let certainty = model.calculate(case);
         let scope = 1.0 - scope_analyzer.analyze(case);
-        let product = certainty * scope;
+        let product = calculate_tradeoff(certainty, scope);
 
         println!("  Input: '{}...'", &case[..case.len().min(30)]);
-        println!("    Certainty: {:.2}", certainty);
-        println!("    Scope: {:.2}", scope);
-        println!("    Product: {:.2}", product);
+        println!("    Certainty: {:.2}", round_to(certainty, DISPLAY_PLACES));
+        println!("    Scope: {:.2}", round_to(scope, DISPLAY_PLACES));
+        println!("    Product: {:.2}", round_to(product, DISPLAY_PLACES));
         println!(
             "    Valid: {}",
             if verify_constraint(certainty, scope) {
@@ -97,13 +100,13 @@ fn visualize_tradeoff_curve() {
     ];
 
     for (certainty, scope) in data_points {
-        let product = certainty * scope;
+        let product = calculate_tradeoff(certainty, scope);
         let valid = verify_constraint(certainty, scope);
         println!(
             "  {:.1} | {:.1} | {:.2} | {}",
             certainty,
             scope,
-            product,
+            round_to(product, DISPLAY_PLACES),
             if valid { "✅" } else { "❌" }
         );
     }
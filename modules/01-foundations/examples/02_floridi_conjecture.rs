@@ -1,6 +1,8 @@
 use module_01_foundations::floridi::{
-    EpistemicCertainty, HybridArchitecture, LearningEnvelope, MappingScope, VerifiedKernel,
+    classify_system, EpistemicCertainty, HybridArchitecture, LearningEnvelope, MappingScope,
+    VerifiedKernel,
 };
+use module_01_foundations::precision::{round_to, DISPLAY_PLACES};
 
 fn main() {
     println!("Floridi Conjecture Implementation");
@@ -30,7 +32,12 @@ fn demonstrate_epistemic_certainty() {
 
     for (steps, description) in test_cases {
         let certainty = ec.calculate(steps);
-        println!("  {} steps ({}): {:.2}", steps, description, certainty);
+        println!(
+            "  {} steps ({}): {:.2}",
+            steps,
+            description,
+            round_to(certainty, DISPLAY_PLACES)
+        );
     }
 }
 
@@ -48,7 +55,13 @@ fn demonstrate_mapping_scope() {
 
     for (input, output, description) in test_cases {
         let scope = ms.calculate(input, output);
-        println!("  {}x{} ({}): {:.2}", input, output, description, scope);
+        println!(
+            "  {}x{} ({}): {:.2}",
+            input,
+            output,
+            description,
+            round_to(scope, DISPLAY_PLACES)
+        );
     }
 }
 
@@ -66,7 +79,11 @@ fn demonstrate_coq_like_verifier() {
 
     for case in test_cases {
         let certainty = kernel.certainty(case);
-        println!("  '{}': certainty = {:.2}", case, certainty);
+        println!(
+            "  '{}': certainty = {:.2}",
+            case,
+            round_to(certainty, DISPLAY_PLACES)
+        );
     }
 }
 
@@ -83,7 +100,11 @@ fn demonstrate_alphacode_generator() {
 
     for case in test_cases {
         let scope = envelope.scope(case);
-        println!("  Input length {}: scope = {:.2}", case.len(), scope);
+        println!(
+            "  Input length {}: scope = {:.2}",
+            case.len(),
+            round_to(scope, DISPLAY_PLACES)
+        );
     }
 }
 
@@ -98,15 +119,13 @@ fn measure_k_values() {
         ("Optimal Hybrid", 0.6, 0.6),
     ];
 
-    println!("  System Type      | Certainty | Scope | k Value");
-    println!("  -----------------|-----------|-------|--------");
+    println!("  System Type      | Certainty | Scope | k Value | Classified As");
+    println!("  -----------------|-----------|-------|---------|---------------");
 
     for (name, certainty, scope) in systems {
-        let k = certainty * scope;
-        println!(
-            "  {:15} | {:9.2} | {:5.2} | {:.3}",
-            name, certainty, scope, k
-        );
+        let k = round_to(certainty * scope, 3);
+        let class = classify_system(certainty, scope);
+        println!("  {name:15} | {certainty:9.2} | {scope:5.2} | {k:7.3} | {class:?}");
     }
 }
 
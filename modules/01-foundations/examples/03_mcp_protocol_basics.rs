@@ -1,8 +1,8 @@
+use pmcp::client::PendingRequests;
 use pmcp::protocol::{ERROR_METHOD_NOT_FOUND, JSONRPC_VERSION};
 use pmcp::transport::{StdioTransport, Transport};
 use pmcp::{ErrorObject, Notification, Request, Response};
 use serde_json::json;
-use std::collections::HashMap;
 use std::time::Instant;
 use tokio::sync::mpsc;
 use tokio::time::{sleep, Duration};
@@ -46,27 +46,39 @@ async fn demonstrate_json_rpc_framing() {
 async fn demonstrate_request_response_correlation() {
     println!("\n🔗 Request/Response Correlation:");
 
-    let mut pending_requests = HashMap::new();
+    let pending = PendingRequests::new();
+    let mut outstanding = Vec::new();
 
     for i in 1..=3 {
-        let request = Request {
-            jsonrpc: JSONRPC_VERSION.to_string(),
-            method: format!("method_{}", i),
-            params: None,
-            id: Some(json!(i)),
-        };
-
-        pending_requests.insert(i, request.method.clone());
-        println!("  Sent request #{} for '{}'", i, pending_requests[&i]);
+        let (request, response) = pending.start(&format!("method_{}", i), None);
+        println!(
+            "  Sent request #{} for '{}'",
+            request.id.as_ref().unwrap(),
+            request.method
+        );
+        outstanding.push((request, response));
     }
 
     println!("\n  Processing responses (may arrive out of order):");
-    let response_order = vec![2, 1, 3];
+    for index in [1, 0, 2] {
+        let request = &outstanding[index].0;
+        let response = Response {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            result: Some(json!(request.method)),
+            error: None,
+            id: request.id.clone(),
+        };
+        println!("    Delivering response #{}", request.id.as_ref().unwrap());
+        pending.resolve(response).unwrap();
+    }
 
-    for id in response_order {
-        if let Some(method) = pending_requests.remove(&id) {
-            println!("    Received response #{} for '{}'", id, method);
-        }
+    for (request, response) in outstanding {
+        let response = response.await.unwrap();
+        println!(
+            "    Request #{} resolved with {}",
+            request.id.unwrap(),
+            response.result.unwrap()
+        );
     }
 }
 
@@ -0,0 +1,81 @@
+use std::time::Instant;
+
+const MIN_SAMPLES: usize = 5;
+const MAX_SAMPLES: usize = 100;
+/// Coefficient of variation across the last [`MIN_SAMPLES`] samples below
+/// which the measurement is considered stable.
+const STABLE_CV: f64 = 0.05;
+
+/// Per-operation timings in nanoseconds, one sample per batch of `iters`
+/// calls.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchResult {
+    pub mean_ns: f64,
+    pub min_ns: f64,
+    pub max_ns: f64,
+    pub samples: usize,
+}
+
+/// Times `f` in batches of `iters` calls after one warmup batch, sampling
+/// until the per-op figure settles or [`MAX_SAMPLES`] is reached. Dividing
+/// in floating point keeps sub-nanosecond operations from reporting 0.
+#[allow(clippy::cast_precision_loss)] // batch times are far below 2^52 ns
+pub fn micro_bench<F: FnMut()>(iters: u32, mut f: F) -> BenchResult {
+    let iters = iters.max(1);
+    let mut batch = || {
+        let start = Instant::now();
+        for _ in 0..iters {
+            f();
+        }
+        start.elapsed().as_nanos() as f64 / f64::from(iters)
+    };
+
+    batch();
+
+    let mut samples = Vec::with_capacity(MAX_SAMPLES);
+    while samples.len() < MAX_SAMPLES {
+        samples.push(batch());
+        if samples.len() >= MIN_SAMPLES && is_stable(&samples[samples.len() - MIN_SAMPLES..]) {
+            break;
+        }
+    }
+
+    BenchResult {
+        mean_ns: mean(&samples),
+        min_ns: samples.iter().copied().fold(f64::INFINITY, f64::min),
+        max_ns: samples.iter().copied().fold(0.0, f64::max),
+        samples: samples.len(),
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn mean(samples: &[f64]) -> f64 {
+    samples.iter().sum::<f64>() / samples.len() as f64
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn is_stable(window: &[f64]) -> bool {
+    let mean = mean(window);
+    if mean <= 0.0 {
+        return false;
+    }
+    let variance = window.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / window.len() as f64;
+    variance.sqrt() / mean < STABLE_CV
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trivial_closure_reports_plausible_per_op() {
+        let result = micro_bench(10_000, || {
+            std::hint::black_box(42 + 58);
+        });
+
+        assert!(result.mean_ns > 0.0);
+        assert!(result.mean_ns < 1_000.0);
+        assert!(result.min_ns <= result.mean_ns && result.mean_ns <= result.max_ns);
+        assert!((MIN_SAMPLES..=MAX_SAMPLES).contains(&result.samples));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::precision::canonical;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 
 pub trait CertaintyCalculator {
@@ -6,6 +8,8 @@ pub trait CertaintyCalculator {
 
 pub struct SymbolicProver {
     axioms: HashMap<String, bool>,
+    cache: RefCell<HashMap<String, f64>>,
+    evaluations: Cell<usize>,
 }
 
 impl SymbolicProver {
@@ -14,12 +18,26 @@ impl SymbolicProver {
         axioms.insert("identity".to_string(), true);
         axioms.insert("excluded_middle".to_string(), true);
         axioms.insert("non_contradiction".to_string(), true);
-        Self { axioms }
+        Self {
+            axioms,
+            cache: RefCell::new(HashMap::new()),
+            evaluations: Cell::new(0),
+        }
     }
 
     pub fn prove(&self, statement: &str) -> bool {
         self.axioms.contains_key(statement) || statement.len() < 10
     }
+
+    /// Number of `calculate` calls that missed the cache and ran `prove`.
+    #[must_use]
+    pub fn evaluations(&self) -> usize {
+        self.evaluations.get()
+    }
+
+    pub fn clear_cache(&self) {
+        self.cache.borrow_mut().clear();
+    }
 }
 
 impl Default for SymbolicProver {
@@ -30,30 +48,49 @@ impl Default for SymbolicProver {
 
 impl CertaintyCalculator for SymbolicProver {
     fn calculate(&self, input: &str) -> f64 {
-        if self.prove(input) {
-            1.0
-        } else {
-            0.0
+        if let Some(&certainty) = self.cache.borrow().get(input) {
+            return certainty;
         }
+
+        self.evaluations.set(self.evaluations.get() + 1);
+        let certainty = if self.prove(input) { 1.0 } else { 0.0 };
+        self.cache.borrow_mut().insert(input.to_string(), certainty);
+        certainty
     }
 }
 
+const DEFAULT_MAX_INPUT_CHARS: usize = 1000;
+
 pub struct GenerativeModel {
     confidence_threshold: f64,
+    max_input_chars: usize,
 }
 
 impl GenerativeModel {
     pub fn new(confidence_threshold: f64) -> Self {
         Self {
             confidence_threshold,
+            max_input_chars: DEFAULT_MAX_INPUT_CHARS,
         }
     }
+
+    /// Characters beyond `max_input_chars` are not counted, which bounds
+    /// both the work done per call and the complexity penalty.
+    #[must_use]
+    pub fn with_max_input_chars(mut self, max_input_chars: usize) -> Self {
+        self.max_input_chars = max_input_chars;
+        self
+    }
 }
 
 impl CertaintyCalculator for GenerativeModel {
+    /// Complexity is measured in characters, not bytes, so multibyte input
+    /// is not penalised. The result stays within `[0.1, threshold]` (or is
+    /// exactly `0.1` if the threshold is below that).
     fn calculate(&self, input: &str) -> f64 {
-        let complexity = input.len() as f64 / 100.0;
-        (self.confidence_threshold - complexity).max(0.1)
+        let chars = input.chars().take(self.max_input_chars).count();
+        let complexity = chars as f64 / 100.0;
+        canonical((self.confidence_threshold - complexity).max(0.1))
     }
 }
 
@@ -71,10 +108,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_symbolic_prover_caches_results() {
+        let prover = SymbolicProver::new();
+        let first = prover.calculate("excluded_middle");
+        let second = prover.calculate("excluded_middle");
+
+        assert_eq!(first, second);
+        assert_eq!(prover.evaluations(), 1);
+
+        for _ in 0..1000 {
+            prover.calculate("excluded_middle");
+        }
+        assert_eq!(prover.evaluations(), 1);
+
+        prover.clear_cache();
+        prover.calculate("excluded_middle");
+        assert_eq!(prover.evaluations(), 2);
+    }
+
     #[test]
     fn test_generative_model_certainty() {
         let model = GenerativeModel::new(0.8);
         let certainty = model.calculate("simple");
         assert!(certainty > 0.0 && certainty <= 1.0);
     }
+
+    #[test]
+    fn test_generative_model_counts_characters_not_bytes() {
+        let model = GenerativeModel::new(0.8);
+        let ascii = "abcdefghij";
+        let emoji = "🦀🦀🦀🦀🦀🦀🦀🦀🦀🦀";
+        assert_eq!(ascii.chars().count(), emoji.chars().count());
+
+        assert!((model.calculate(ascii) - model.calculate(emoji)).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_generative_model_caps_long_input() {
+        let model = GenerativeModel::new(0.8).with_max_input_chars(20);
+        let long = "x".repeat(1_000_000);
+
+        assert!((model.calculate(&long) - model.calculate(&"x".repeat(20))).abs() < f64::EPSILON);
+        assert!((0.1..=0.8).contains(&GenerativeModel::new(0.8).calculate(&long)));
+    }
 }
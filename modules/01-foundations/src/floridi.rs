@@ -1,3 +1,5 @@
+use crate::precision::canonical;
+
 pub struct EpistemicCertainty {
     verification_level: f64,
 }
@@ -9,7 +11,7 @@ impl EpistemicCertainty {
 
     pub fn calculate(&self, proof_steps: usize) -> f64 {
         if proof_steps > 0 {
-            (self.verification_level * proof_steps as f64 / 100.0).min(1.0)
+            canonical((self.verification_level * proof_steps as f64 / 100.0).min(1.0))
         } else {
             0.0
         }
@@ -21,34 +23,77 @@ pub struct MappingScope {
 }
 
 impl MappingScope {
+    /// An `io_complexity` of zero is clamped to one so `calculate` never
+    /// divides by zero.
     pub fn new(io_complexity: usize) -> Self {
-        Self { io_complexity }
+        Self {
+            io_complexity: io_complexity.max(1),
+        }
     }
 
     pub fn calculate(&self, input_dims: usize, output_dims: usize) -> f64 {
         let total = input_dims * output_dims;
-        (total as f64 / self.io_complexity as f64).min(1.0)
+        canonical((total as f64 / self.io_complexity as f64).min(1.0))
     }
 }
 
+/// A verified kernel paired with a learning envelope. Either may be left
+/// out via [`HybridArchitectureBuilder`]; a missing component contributes
+/// `0.0` to [`HybridArchitecture::execute`].
 pub struct HybridArchitecture {
-    verified_kernel: VerifiedKernel,
-    learning_envelope: LearningEnvelope,
+    verified_kernel: Option<VerifiedKernel>,
+    learning_envelope: Option<LearningEnvelope>,
+    alpha: f64,
 }
 
 impl HybridArchitecture {
     pub fn new() -> Self {
         Self {
-            verified_kernel: VerifiedKernel::new(),
-            learning_envelope: LearningEnvelope::new(),
+            verified_kernel: Some(VerifiedKernel::new()),
+            learning_envelope: Some(LearningEnvelope::new()),
+            alpha: 0.5,
+        }
+    }
+
+    /// Creates an architecture whose `blend` weights the verified kernel by
+    /// `alpha` and the learning envelope by `1 - alpha`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `alpha` is not within `[0, 1]`.
+    pub fn with_alpha(alpha: f64) -> Result<Self, String> {
+        if !(0.0..=1.0).contains(&alpha) {
+            return Err(format!("alpha must be within [0, 1], got {alpha}"));
         }
+        Ok(Self {
+            alpha,
+            ..Self::new()
+        })
+    }
+
+    #[must_use]
+    pub fn alpha(&self) -> f64 {
+        self.alpha
     }
 
     pub fn execute(&self, input: &str) -> (f64, f64) {
-        let certainty = self.verified_kernel.certainty(input);
-        let scope = self.learning_envelope.scope(input);
+        let certainty = self
+            .verified_kernel
+            .as_ref()
+            .map_or(0.0, |kernel| kernel.certainty(input));
+        let scope = self
+            .learning_envelope
+            .as_ref()
+            .map_or(0.0, |envelope| envelope.scope(input));
         (certainty, scope)
     }
+
+    /// `H(x) = α·V(x) + (1-α)·L(x)`
+    #[must_use]
+    pub fn blend(&self, input: &str) -> f64 {
+        let (certainty, scope) = self.execute(input);
+        canonical(self.alpha * certainty + (1.0 - self.alpha) * scope)
+    }
 }
 
 impl Default for HybridArchitecture {
@@ -57,23 +102,120 @@ impl Default for HybridArchitecture {
     }
 }
 
+/// Assembles a [`HybridArchitecture`] from custom components. Unlike
+/// [`HybridArchitecture::new`], nothing is included unless added.
+#[derive(Default)]
+pub struct HybridArchitectureBuilder {
+    verified_kernel: Option<VerifiedKernel>,
+    learning_envelope: Option<LearningEnvelope>,
+    alpha: Option<f64>,
+}
+
+impl HybridArchitectureBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with_kernel(mut self, kernel: VerifiedKernel) -> Self {
+        self.verified_kernel = Some(kernel);
+        self
+    }
+
+    #[must_use]
+    pub fn with_envelope(mut self, envelope: LearningEnvelope) -> Self {
+        self.learning_envelope = Some(envelope);
+        self
+    }
+
+    /// Defaults to `0.5`; validated by [`HybridArchitectureBuilder::build`].
+    #[must_use]
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if neither a kernel nor an envelope was added, or if
+    /// `alpha` is not within `[0, 1]`.
+    pub fn build(self) -> Result<HybridArchitecture, String> {
+        if self.verified_kernel.is_none() && self.learning_envelope.is_none() {
+            return Err("a hybrid architecture needs a kernel or an envelope".to_string());
+        }
+
+        Ok(HybridArchitecture {
+            verified_kernel: self.verified_kernel,
+            learning_envelope: self.learning_envelope,
+            ..HybridArchitecture::with_alpha(self.alpha.unwrap_or(0.5))?
+        })
+    }
+}
+
+/// How [`VerifiedKernel`] decides that an input cites an axiom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AxiomMatch {
+    /// The whole input is the axiom.
+    Exact,
+    /// The axiom appears anywhere, even inside a longer word.
+    Contains,
+    /// The axiom appears as a whole word: "reflexivity holds" cites
+    /// `reflexivity`, "irreflexivity" does not.
+    #[default]
+    WordBoundary,
+}
+
+impl AxiomMatch {
+    fn matches(self, input: &str, axiom: &str) -> bool {
+        match self {
+            Self::Exact => input == axiom,
+            Self::Contains => input.contains(axiom),
+            Self::WordBoundary => input.match_indices(axiom).any(|(start, _)| {
+                let is_word = |c: char| c.is_alphanumeric() || c == '_';
+                let before = input[..start].chars().next_back();
+                let after = input[start + axiom.len()..].chars().next();
+                !before.is_some_and(is_word) && !after.is_some_and(is_word)
+            }),
+        }
+    }
+}
+
 pub struct VerifiedKernel {
     axioms: Vec<String>,
+    matching: AxiomMatch,
 }
 
 impl VerifiedKernel {
     pub fn new() -> Self {
+        Self::with_axioms(vec![
+            "reflexivity".to_string(),
+            "symmetry".to_string(),
+            "transitivity".to_string(),
+        ])
+    }
+
+    #[must_use]
+    pub fn with_axioms(axioms: Vec<String>) -> Self {
         Self {
-            axioms: vec![
-                "reflexivity".to_string(),
-                "symmetry".to_string(),
-                "transitivity".to_string(),
-            ],
+            axioms,
+            matching: AxiomMatch::default(),
         }
     }
 
+    #[must_use]
+    pub fn with_matching(mut self, matching: AxiomMatch) -> Self {
+        self.matching = matching;
+        self
+    }
+
+    /// `1.0` if `input` cites one of the axioms, `0.2` otherwise.
     pub fn certainty(&self, input: &str) -> f64 {
-        if self.axioms.iter().any(|a| input.contains(a.as_str())) {
+        if self
+            .axioms
+            .iter()
+            .any(|axiom| self.matching.matches(input, axiom))
+        {
             1.0
         } else {
             0.2
@@ -98,9 +240,21 @@ impl LearningEnvelope {
         }
     }
 
+    /// # Errors
+    ///
+    /// Returns an error if `model_confidence` is not within `[0, 1]`.
+    pub fn with_confidence(model_confidence: f64) -> Result<Self, String> {
+        if !(0.0..=1.0).contains(&model_confidence) {
+            return Err(format!(
+                "model confidence must be within [0, 1], got {model_confidence}"
+            ));
+        }
+        Ok(Self { model_confidence })
+    }
+
     pub fn scope(&self, input: &str) -> f64 {
         let complexity_factor = (input.len() as f64 / 50.0).min(1.0);
-        self.model_confidence * complexity_factor
+        canonical(self.model_confidence * complexity_factor)
     }
 }
 
@@ -110,9 +264,57 @@ impl Default for LearningEnvelope {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemType {
+    PureSymbolic,
+    HybridBalanced,
+    LearningHeavy,
+    PureGenerative,
+    OptimalHybrid,
+}
+
+/// Minimum `k = certainty * scope` for a system to count as an optimal hybrid.
+pub const OPTIMAL_K: f64 = 0.3;
+/// Maximum `|certainty - scope|` for a system to count as an optimal hybrid.
+pub const OPTIMAL_BALANCE: f64 = 0.25;
+/// `|certainty - scope|` at or beyond which a system is purely symbolic or
+/// purely generative.
+pub const PURE_BALANCE: f64 = 0.5;
+
+/// Classifies a system by its certainty/scope profile. Inputs are clamped to
+/// `[0, 1]` and NaN is treated as `0`, so every input maps to exactly one
+/// category. With `k = certainty * scope` and `balance = certainty - scope`,
+/// both [canonicalized](crate::precision::canonical) before comparison:
+///
+/// - `OptimalHybrid`: `k >= OPTIMAL_K` and `|balance| <= OPTIMAL_BALANCE`
+/// - `PureSymbolic`: otherwise, `balance >= PURE_BALANCE`
+/// - `HybridBalanced`: otherwise, `0 <= balance < PURE_BALANCE`
+/// - `LearningHeavy`: otherwise, `-PURE_BALANCE < balance < 0`
+/// - `PureGenerative`: otherwise, `balance <= -PURE_BALANCE`
+#[must_use]
+pub fn classify_system(certainty: f64, scope: f64) -> SystemType {
+    let normalize = |v: f64| if v.is_nan() { 0.0 } else { v.clamp(0.0, 1.0) };
+    let (certainty, scope) = (normalize(certainty), normalize(scope));
+    let k = canonical(certainty * scope);
+    let balance = canonical(certainty - scope);
+
+    if k >= OPTIMAL_K && balance.abs() <= OPTIMAL_BALANCE {
+        SystemType::OptimalHybrid
+    } else if balance >= PURE_BALANCE {
+        SystemType::PureSymbolic
+    } else if balance >= 0.0 {
+        SystemType::HybridBalanced
+    } else if balance > -PURE_BALANCE {
+        SystemType::LearningHeavy
+    } else {
+        SystemType::PureGenerative
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_epistemic_certainty() {
@@ -129,6 +331,14 @@ mod tests {
         assert!(scope > 0.0 && scope <= 1.0);
     }
 
+    #[test]
+    fn test_mapping_scope_zero_complexity() {
+        let ms = MappingScope::new(0);
+        let scope = ms.calculate(3, 4);
+        assert!(scope.is_finite());
+        assert!((0.0..=1.0).contains(&scope));
+    }
+
     #[test]
     fn test_hybrid_architecture() {
         let hybrid = HybridArchitecture::new();
@@ -136,4 +346,139 @@ mod tests {
         assert_eq!(certainty, 1.0);
         assert!(scope > 0.0);
     }
+
+    #[test]
+    fn test_blend_alpha_extremes() {
+        let input = "reflexivity in a moderately long input string";
+        let kernel = VerifiedKernel::new().certainty(input);
+        let envelope = LearningEnvelope::new().scope(input);
+
+        let all_kernel = HybridArchitecture::with_alpha(1.0).unwrap();
+        assert!((all_kernel.blend(input) - kernel).abs() < f64::EPSILON);
+
+        let all_envelope = HybridArchitecture::with_alpha(0.0).unwrap();
+        assert!((all_envelope.blend(input) - envelope).abs() < f64::EPSILON);
+
+        let balanced = HybridArchitecture::new();
+        assert!((balanced.blend(input) - f64::midpoint(kernel, envelope)).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_builder_uses_custom_components() {
+        let hybrid = HybridArchitectureBuilder::new()
+            .with_kernel(VerifiedKernel::with_axioms(vec!["idempotence".to_string()]))
+            .with_envelope(LearningEnvelope::with_confidence(1.0).unwrap())
+            .with_alpha(1.0)
+            .build()
+            .unwrap();
+
+        let input = "idempotence of a retry loop in a distributed queue system";
+        let (certainty, scope) = hybrid.execute(input);
+        assert!((certainty - 1.0).abs() < f64::EPSILON);
+        assert!((scope - 1.0).abs() < f64::EPSILON);
+
+        let (certainty, _) = hybrid.execute("reflexivity");
+        assert!((certainty - 0.2).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_word_boundary_matching_rejects_longer_words() {
+        let kernel = VerifiedKernel::new();
+
+        assert!((kernel.certainty("reflexivity holds") - 1.0).abs() < f64::EPSILON);
+        assert!((kernel.certainty("(symmetry)") - 1.0).abs() < f64::EPSILON);
+        assert!((kernel.certainty("irreflexivity") - 0.2).abs() < f64::EPSILON);
+        assert!((kernel.certainty("reflexivity_check") - 0.2).abs() < f64::EPSILON);
+
+        let contains = VerifiedKernel::new().with_matching(AxiomMatch::Contains);
+        assert!((contains.certainty("irreflexivity") - 1.0).abs() < f64::EPSILON);
+
+        let exact = VerifiedKernel::new().with_matching(AxiomMatch::Exact);
+        assert!((exact.certainty("symmetry") - 1.0).abs() < f64::EPSILON);
+        assert!((exact.certainty("symmetry axiom") - 0.2).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_builder_requires_a_component() {
+        assert!(HybridArchitectureBuilder::new().build().is_err());
+        assert!(HybridArchitectureBuilder::new()
+            .with_kernel(VerifiedKernel::new())
+            .with_alpha(2.0)
+            .build()
+            .is_err());
+
+        let kernel_only = HybridArchitectureBuilder::new()
+            .with_kernel(VerifiedKernel::new())
+            .build()
+            .unwrap();
+        assert!(kernel_only.execute("symmetry").1.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_with_alpha_rejects_out_of_range() {
+        assert!(HybridArchitecture::with_alpha(-0.1).is_err());
+        assert!(HybridArchitecture::with_alpha(1.1).is_err());
+        assert!(HybridArchitecture::with_alpha(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_classify_reference_systems() {
+        assert_eq!(classify_system(1.0, 0.1), SystemType::PureSymbolic);
+        assert_eq!(classify_system(0.7, 0.4), SystemType::HybridBalanced);
+        assert_eq!(classify_system(0.4, 0.7), SystemType::LearningHeavy);
+        assert_eq!(classify_system(0.2, 0.9), SystemType::PureGenerative);
+        assert_eq!(classify_system(0.6, 0.6), SystemType::OptimalHybrid);
+    }
+
+    #[test]
+    fn test_classify_boundaries() {
+        // k == OPTIMAL_K
+        assert_eq!(classify_system(0.6, 0.5), SystemType::OptimalHybrid);
+        assert_eq!(classify_system(0.6, 0.49), SystemType::HybridBalanced);
+        // |balance| == OPTIMAL_BALANCE
+        assert_eq!(classify_system(0.75, 0.5), SystemType::OptimalHybrid);
+        assert_eq!(classify_system(0.75, 0.49), SystemType::HybridBalanced);
+        // balance == PURE_BALANCE
+        assert_eq!(classify_system(0.75, 0.25), SystemType::PureSymbolic);
+        assert_eq!(classify_system(0.74, 0.25), SystemType::HybridBalanced);
+        // balance == 0
+        assert_eq!(classify_system(0.5, 0.5), SystemType::HybridBalanced);
+        assert_eq!(classify_system(0.5, 0.51), SystemType::LearningHeavy);
+        // balance == -PURE_BALANCE
+        assert_eq!(classify_system(0.25, 0.75), SystemType::PureGenerative);
+        assert_eq!(classify_system(0.26, 0.75), SystemType::LearningHeavy);
+    }
+
+    #[test]
+    fn test_classify_out_of_range_inputs() {
+        assert_eq!(classify_system(2.0, -1.0), SystemType::PureSymbolic);
+        assert_eq!(classify_system(f64::NAN, 1.0), SystemType::PureGenerative);
+        assert_eq!(
+            classify_system(f64::NAN, f64::NAN),
+            SystemType::HybridBalanced
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_classification_stable_under_perturbation(
+            certainty in 0.0..=1.0,
+            scope in 0.0..=1.0,
+            dc in -1e-9..=1e-9,
+            ds in -1e-9..=1e-9,
+        ) {
+            let k: f64 = certainty * scope;
+            let balance: f64 = certainty - scope;
+            let margin = 1e-6;
+            prop_assume!((k - OPTIMAL_K).abs() > margin);
+            prop_assume!((balance.abs() - OPTIMAL_BALANCE).abs() > margin);
+            prop_assume!((balance.abs() - PURE_BALANCE).abs() > margin);
+            prop_assume!(balance.abs() > margin);
+
+            prop_assert_eq!(
+                classify_system(certainty, scope),
+                classify_system(certainty + dc, scope + ds)
+            );
+        }
+    }
 }
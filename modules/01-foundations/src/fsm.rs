@@ -1,4 +1,6 @@
-use std::time::Instant;
+use pmcp::clock::{Clock, SystemClock};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum State {
@@ -18,30 +20,106 @@ pub enum Event {
     Fail(String),
 }
 
+impl Event {
+    /// The variant's name, without any payload. Transitions match on this.
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Start => "Start",
+            Self::Pause => "Pause",
+            Self::Resume => "Resume",
+            Self::Finish => "Finish",
+            Self::Fail(_) => "Fail",
+        }
+    }
+}
+
 pub struct Transition<S, E> {
     from: S,
     to: S,
     event: E,
-    guard: Option<Box<dyn Fn(&S, &E) -> bool>>,
+    guard: Option<Box<dyn Fn(&S, &E) -> bool + Send + Sync>>,
+}
+
+/// Which declared transitions have fired, indexed in declaration order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransitionCoverage {
+    fired: Vec<bool>,
+}
+
+impl TransitionCoverage {
+    fn declare(&mut self) {
+        self.fired.push(false);
+    }
+
+    fn record(&mut self, index: usize) {
+        self.fired[index] = true;
+    }
+
+    #[must_use]
+    pub fn fired(&self) -> usize {
+        self.fired.iter().filter(|&&fired| fired).count()
+    }
+
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.fired.len()
+    }
+
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.fired.iter().all(|&fired| fired)
+    }
+}
+
+type Invariant<S> = Box<dyn Fn(&S) -> bool + Send + Sync>;
+
+/// Moves from `from` to `to` once the machine has been in `from` for
+/// `after`, without an event.
+struct TimedTransition<S> {
+    from: S,
+    to: S,
+    after: Duration,
 }
 
 pub struct FSM<S, E> {
+    initial_state: S,
     current_state: S,
     transitions: Vec<Transition<S, E>>,
+    timed_transitions: Vec<TimedTransition<S>>,
+    invariants: Vec<Invariant<S>>,
     transition_count: usize,
     last_transition_time: Option<Instant>,
+    entered_at: Instant,
+    clock: Arc<dyn Clock>,
+    coverage: TransitionCoverage,
 }
 
 impl FSM<State, Event> {
     pub fn new(initial_state: State) -> Self {
         Self {
+            initial_state,
             current_state: initial_state,
             transitions: Vec::new(),
+            timed_transitions: Vec::new(),
+            invariants: Vec::new(),
             transition_count: 0,
             last_transition_time: None,
+            entered_at: Instant::now(),
+            clock: Arc::new(SystemClock),
+            coverage: TransitionCoverage::default(),
         }
     }
 
+    /// Reads time from `clock` instead of the system clock. The current
+    /// state counts as entered at the clock's present time.
+    #[must_use]
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.entered_at = clock.now();
+        self.clock = Arc::new(clock);
+        self
+    }
+
     pub fn add_transition(mut self, from: State, to: State, event: Event) -> Self {
         self.transitions.push(Transition {
             from,
@@ -49,18 +127,71 @@ impl FSM<State, Event> {
             event,
             guard: None,
         });
+        self.coverage.declare();
+        self
+    }
+
+    #[must_use]
+    pub fn add_timed_transition(mut self, from: State, to: State, after: Duration) -> Self {
+        self.timed_transitions
+            .push(TimedTransition { from, to, after });
         self
     }
 
+    /// A condition every state the machine moves into must satisfy. A
+    /// transition into a state that breaks any invariant is rolled back.
+    #[must_use]
+    pub fn add_invariant(
+        mut self,
+        invariant: impl Fn(&State) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.invariants.push(Box::new(invariant));
+        self
+    }
+
+    fn satisfies_invariants(&self, state: State) -> bool {
+        self.invariants.iter().all(|invariant| invariant(&state))
+    }
+
+    /// Fires the first timed transition out of the current state whose
+    /// delay has passed, returning the new state if one fired. A timed
+    /// transition that would break an invariant does not fire.
+    pub fn poll_timers(&mut self) -> Option<State> {
+        let now = self.clock.now();
+        let in_state = now.saturating_duration_since(self.entered_at);
+        let to = self
+            .timed_transitions
+            .iter()
+            .find(|timed| timed.from == self.current_state && in_state >= timed.after)?
+            .to;
+        if !self.satisfies_invariants(to) {
+            return None;
+        }
+
+        self.current_state = to;
+        self.transition_count += 1;
+        self.last_transition_time = Some(now);
+        self.entered_at = now;
+        Some(to)
+    }
+
     pub fn process_event(&mut self, event: Event) -> Result<State, String> {
-        let start = Instant::now();
+        let start = self.clock.now();
 
-        for transition in &self.transitions {
+        for (index, transition) in self.transitions.iter().enumerate() {
             if transition.from == self.current_state {
                 if std::mem::discriminant(&transition.event) == std::mem::discriminant(&event) {
+                    if !self.satisfies_invariants(transition.to) {
+                        return Err(format!(
+                            "Transition from {:?} to {:?} violates an invariant",
+                            self.current_state, transition.to
+                        ));
+                    }
                     self.current_state = transition.to;
                     self.transition_count += 1;
                     self.last_transition_time = Some(start);
+                    self.entered_at = start;
+                    self.coverage.record(index);
                     return Ok(self.current_state);
                 }
             }
@@ -81,7 +212,73 @@ impl FSM<State, Event> {
     }
 
     pub fn last_transition_duration(&self) -> Option<std::time::Duration> {
-        self.last_transition_time.map(|t| t.elapsed())
+        let now = self.clock.now();
+        self.last_transition_time
+            .map(|t| now.saturating_duration_since(t))
+    }
+
+    #[must_use]
+    pub fn coverage(&self) -> &TransitionCoverage {
+        &self.coverage
+    }
+
+    /// `(fired, total, uncovered)`, where `uncovered` lists the declared
+    /// `(from, to)` transitions that have never fired, in declaration order.
+    #[must_use]
+    pub fn coverage_report(&self) -> (usize, usize, Vec<(State, State)>) {
+        let uncovered = self
+            .transitions
+            .iter()
+            .zip(&self.coverage.fired)
+            .filter(|(_, &fired)| !fired)
+            .map(|(transition, _)| (transition.from, transition.to))
+            .collect();
+        (self.coverage.fired(), self.coverage.total(), uncovered)
+    }
+}
+
+impl FSM<State, Event> {
+    /// Every declared event-driven transition as `(from, to, event name)`,
+    /// in declaration order. Exports of the machine's structure build on
+    /// this, so they cannot drift from what `process_event` follows.
+    #[must_use]
+    pub fn transition_table(&self) -> Vec<(State, State, &'static str)> {
+        self.transitions
+            .iter()
+            .map(|transition| (transition.from, transition.to, transition.event.name()))
+            .collect()
+    }
+
+    /// The machine's structure for external tooling:
+    /// `{"initial", "transitions": [{"from", "to", "event"}], "timed":
+    /// [{"from", "to", "afterMs"}]}`, with states and events by name.
+    #[must_use]
+    pub fn to_transition_table_json(&self) -> serde_json::Value {
+        let name = |state: State| format!("{state:?}");
+        let transitions: Vec<serde_json::Value> = self
+            .transition_table()
+            .into_iter()
+            .map(|(from, to, event)| {
+                serde_json::json!({ "from": name(from), "to": name(to), "event": event })
+            })
+            .collect();
+        let timed: Vec<serde_json::Value> = self
+            .timed_transitions
+            .iter()
+            .map(|timed| {
+                serde_json::json!({
+                    "from": name(timed.from),
+                    "to": name(timed.to),
+                    "afterMs": u64::try_from(timed.after.as_millis()).unwrap_or(u64::MAX),
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "initial": name(self.initial_state),
+            "transitions": transitions,
+            "timed": timed,
+        })
     }
 }
 
@@ -101,6 +298,13 @@ pub fn create_basic_fsm() -> FSM<State, Event> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pmcp::clock::MockClock;
+
+    /// Slowest acceptable transition rate. This is hardware-dependent: it
+    /// sits well over an order of magnitude below what an unoptimised test
+    /// build manages on a typical CI runner, so only a gross regression
+    /// trips it. `benches/fsm.rs` has the precise numbers.
+    const MIN_TRANSITIONS_PER_SEC: f64 = 50_000.0;
 
     #[test]
     fn test_basic_fsm_transitions() {
@@ -122,6 +326,46 @@ mod tests {
         assert_eq!(fsm.transition_count(), 4);
     }
 
+    #[test]
+    fn test_coverage_report_lists_uncovered_transitions() {
+        let mut fsm = create_basic_fsm();
+        fsm.process_event(Event::Start).unwrap();
+        fsm.process_event(Event::Pause).unwrap();
+        fsm.process_event(Event::Resume).unwrap();
+        fsm.process_event(Event::Pause).unwrap();
+
+        let (fired, total, uncovered) = fsm.coverage_report();
+        assert_eq!((fired, total), (3, 5));
+        assert_eq!(
+            uncovered,
+            [
+                (State::Running, State::Complete),
+                (State::Running, State::Error)
+            ]
+        );
+        assert!(!fsm.coverage().is_complete());
+    }
+
+    #[test]
+    fn test_transition_table_json_lists_each_transition_once() {
+        let mut fsm = create_basic_fsm();
+        fsm.process_event(Event::Start).unwrap();
+
+        let table = fsm.to_transition_table_json();
+
+        assert_eq!(table["initial"], "Init");
+        assert_eq!(
+            table["transitions"],
+            serde_json::json!([
+                { "from": "Init", "to": "Running", "event": "Start" },
+                { "from": "Running", "to": "Paused", "event": "Pause" },
+                { "from": "Paused", "to": "Running", "event": "Resume" },
+                { "from": "Running", "to": "Complete", "event": "Finish" },
+                { "from": "Running", "to": "Error", "event": "Fail" },
+            ])
+        );
+    }
+
     #[test]
     fn test_invalid_transition() {
         let mut fsm = create_basic_fsm();
@@ -137,4 +381,71 @@ mod tests {
             assert!(duration.as_micros() < 100);
         }
     }
+
+    #[test]
+    fn test_timed_transition_fires_on_mock_clock() {
+        let clock = MockClock::new();
+        let mut fsm = create_basic_fsm()
+            .add_timed_transition(State::Running, State::Error, Duration::from_secs(30))
+            .with_clock(clock.clone());
+
+        assert_eq!(fsm.poll_timers(), None);
+        fsm.process_event(Event::Start).unwrap();
+
+        clock.advance(Duration::from_secs(29));
+        assert_eq!(fsm.poll_timers(), None);
+        assert_eq!(
+            fsm.last_transition_duration(),
+            Some(Duration::from_secs(29))
+        );
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(fsm.poll_timers(), Some(State::Error));
+        assert_eq!(fsm.current_state(), State::Error);
+        assert_eq!(fsm.transition_count(), 2);
+    }
+
+    #[test]
+    fn test_invariant_violation_is_rolled_back() {
+        let mut fsm = create_basic_fsm().add_invariant(|state| *state != State::Error);
+        fsm.process_event(Event::Start).unwrap();
+
+        let result = fsm.process_event(Event::Fail("disk full".to_string()));
+
+        assert!(result.unwrap_err().contains("violates an invariant"));
+        assert_eq!(fsm.current_state(), State::Running);
+        assert_eq!(fsm.transition_count(), 1);
+        assert_eq!(fsm.coverage().fired(), 1);
+    }
+
+    #[test]
+    fn test_fsm_with_invariant_can_move_to_another_thread() {
+        let fsm = create_basic_fsm().add_invariant(|state| *state != State::Error);
+
+        let state = std::thread::spawn(move || fsm.current_state())
+            .join()
+            .unwrap();
+
+        assert_eq!(state, State::Init);
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)] // 100k transitions fit an f64 exactly
+    fn test_transition_throughput_floor() {
+        const ROUNDS: usize = 50_000;
+        let mut fsm = create_basic_fsm();
+        fsm.process_event(Event::Start).unwrap();
+
+        let start = Instant::now();
+        for _ in 0..ROUNDS {
+            fsm.process_event(Event::Pause).unwrap();
+            fsm.process_event(Event::Resume).unwrap();
+        }
+        let per_sec = (2 * ROUNDS) as f64 / start.elapsed().as_secs_f64();
+
+        assert!(
+            per_sec >= MIN_TRANSITIONS_PER_SEC,
+            "{per_sec:.0} transitions/s is below the {MIN_TRANSITIONS_PER_SEC} floor"
+        );
+    }
 }
@@ -1,6 +1,8 @@
 #![warn(clippy::all, clippy::pedantic)]
 
+pub mod bench;
 pub mod certainty;
 pub mod floridi;
 pub mod fsm;
+pub mod precision;
 pub mod scope;
@@ -0,0 +1,53 @@
+/// Decimal places certainty and scope values are canonicalized to before
+/// they are compared, enough to absorb `f64` rounding noise without moving
+/// any meaningful boundary.
+pub const CANONICAL_PLACES: u32 = 9;
+
+/// Decimal places used when certainty and scope values are displayed.
+pub const DISPLAY_PLACES: u32 = 2;
+
+/// Rounds `value` half away from zero to `places` decimal places. Values
+/// too large to scale, and non-finite values, are returned unchanged.
+#[must_use]
+pub fn round_to(value: f64, places: u32) -> f64 {
+    let factor = 10f64.powi(i32::try_from(places).unwrap_or(i32::MAX));
+    let scaled = value * factor;
+    if !scaled.is_finite() {
+        return value;
+    }
+    scaled.round() / factor
+}
+
+/// `value` rounded to [`CANONICAL_PLACES`], so results that differ only by
+/// the order of floating-point operations compare equal.
+#[must_use]
+pub fn canonical(value: f64) -> f64 {
+    round_to(value, CANONICAL_PLACES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_round_to_removes_representation_noise() {
+        assert_ne!(0.1 + 0.2, 0.3);
+        assert_eq!(round_to(0.1 + 0.2, 2), 0.30);
+        assert_eq!(round_to(0.125, 2), 0.13);
+        assert_eq!(round_to(-0.125, 2), -0.13);
+        assert!(round_to(f64::NAN, 2).is_nan());
+        assert_eq!(round_to(f64::MAX, 2), f64::MAX);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_computation_order_rounds_equal() {
+        let left = (0.1 + 0.2) + 0.3;
+        let right = 0.1 + (0.2 + 0.3);
+
+        assert_ne!(left, right);
+        assert_eq!(canonical(left), canonical(right));
+        assert_eq!(canonical(0.7 * 0.3 * 0.9), canonical(0.9 * 0.3 * 0.7));
+    }
+}
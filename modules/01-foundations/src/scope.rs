@@ -1,3 +1,8 @@
+use crate::precision::canonical;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use std::io::Write;
+
 pub trait ScopeAnalyzer {
     fn analyze(&self, domain: &str) -> f64;
     fn kolmogorov_complexity(&self, data: &str) -> usize;
@@ -9,24 +14,39 @@ pub struct DomainScope {
 
 impl DomainScope {
     pub fn new(max_complexity: usize) -> Self {
-        Self { max_complexity }
+        Self {
+            max_complexity: max_complexity.max(1),
+        }
     }
 }
 
 impl ScopeAnalyzer for DomainScope {
+    /// Empty input is treated as maximally broad (scope `1.0`).
     fn analyze(&self, domain: &str) -> f64 {
+        if domain.is_empty() {
+            return 1.0;
+        }
+
         let complexity = self.kolmogorov_complexity(domain);
-        1.0 - (complexity as f64 / self.max_complexity as f64).min(1.0)
+        canonical(1.0 - (complexity as f64 / self.max_complexity as f64).min(1.0))
     }
 
+    /// Approximated by the deflate-compressed size of `data` in bytes.
     fn kolmogorov_complexity(&self, data: &str) -> usize {
-        let compressed = data.chars().collect::<std::collections::HashSet<_>>().len();
-        compressed * data.len().ilog2() as usize
+        if data.is_empty() {
+            return 0;
+        }
+
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder
+            .write_all(data.as_bytes())
+            .and_then(|()| encoder.finish())
+            .map_or(data.len(), |compressed| compressed.len())
     }
 }
 
 pub fn calculate_tradeoff(certainty: f64, scope: f64) -> f64 {
-    certainty * scope
+    canonical(certainty * scope)
 }
 
 pub const K_CONSTANT: f64 = 1.0;
@@ -35,6 +55,78 @@ pub fn verify_constraint(certainty: f64, scope: f64) -> bool {
     calculate_tradeoff(certainty, scope) <= K_CONSTANT
 }
 
+/// Largest scope in `[0, 1]` that still satisfies `certainty * scope <= K_CONSTANT`.
+#[must_use]
+pub fn max_scope_for_certainty(certainty: f64) -> f64 {
+    frontier(certainty, K_CONSTANT)
+}
+
+/// Largest certainty in `[0, 1]` that still satisfies `certainty * scope <= K_CONSTANT`.
+#[must_use]
+pub fn max_certainty_for_scope(scope: f64) -> f64 {
+    frontier(scope, K_CONSTANT)
+}
+
+/// The `certainty * scope <= k` constraint for a system with its own `k`.
+/// The free functions above are the `k = K_CONSTANT` case.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConstraintChecker {
+    k: f64,
+}
+
+impl ConstraintChecker {
+    /// # Errors
+    ///
+    /// Returns an error if `k` is not in `(0, 1]`.
+    pub fn new(k: f64) -> Result<Self, String> {
+        if k > 0.0 && k <= 1.0 {
+            Ok(Self { k })
+        } else {
+            Err(format!("k must be in (0, 1], got {k}"))
+        }
+    }
+
+    #[must_use]
+    pub fn k(&self) -> f64 {
+        self.k
+    }
+
+    #[must_use]
+    pub fn tradeoff(&self, certainty: f64, scope: f64) -> f64 {
+        calculate_tradeoff(certainty, scope)
+    }
+
+    #[must_use]
+    pub fn verify(&self, certainty: f64, scope: f64) -> bool {
+        self.tradeoff(certainty, scope) <= self.k
+    }
+
+    #[must_use]
+    pub fn max_scope_for_certainty(&self, certainty: f64) -> f64 {
+        frontier(certainty, self.k)
+    }
+
+    #[must_use]
+    pub fn max_certainty_for_scope(&self, scope: f64) -> f64 {
+        frontier(scope, self.k)
+    }
+}
+
+impl Default for ConstraintChecker {
+    fn default() -> Self {
+        Self { k: K_CONSTANT }
+    }
+}
+
+/// Solves `fixed * x <= k` for the largest `x` in `[0, 1]`. A non-positive
+/// or NaN `fixed` leaves `x` unconstrained.
+fn frontier(fixed: f64, k: f64) -> f64 {
+    if fixed.is_nan() || fixed <= 0.0 {
+        return 1.0;
+    }
+    (k / fixed.min(1.0)).clamp(0.0, 1.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -47,6 +139,40 @@ mod tests {
         assert!(scope > 0.0 && scope <= 1.0);
     }
 
+    #[test]
+    fn test_repetitive_input_is_less_complex() {
+        let analyzer = DomainScope::new(1000);
+        let repetitive = "ab".repeat(500);
+        let mut seed: u32 = 42;
+        let random_looking: String = (0..1000)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                char::from(b'!' + u8::try_from((seed >> 16) % 94).unwrap())
+            })
+            .collect();
+
+        assert!(
+            analyzer.kolmogorov_complexity(&repetitive)
+                < analyzer.kolmogorov_complexity(&random_looking)
+        );
+        assert!(analyzer.analyze(&repetitive) > analyzer.analyze(&random_looking));
+    }
+
+    #[test]
+    fn test_empty_input() {
+        let analyzer = DomainScope::new(1000);
+        assert_eq!(analyzer.kolmogorov_complexity(""), 0);
+        assert!((analyzer.analyze("") - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_zero_max_complexity() {
+        let analyzer = DomainScope::new(0);
+        let scope = analyzer.analyze("simple_domain");
+        assert!((0.0..=1.0).contains(&scope));
+        assert!((analyzer.analyze("") - 1.0).abs() < f64::EPSILON);
+    }
+
     #[test]
     fn test_tradeoff_constraint() {
         assert!(verify_constraint(1.0, 0.5));
@@ -54,7 +180,43 @@ mod tests {
         assert!(!verify_constraint(1.0, 1.1));
     }
 
+    #[test]
+    fn test_max_scope_for_certainty() {
+        assert!((max_scope_for_certainty(0.5) - 1.0).abs() < f64::EPSILON);
+        // With k = 1 the constraint never binds inside the unit square.
+        assert!((max_scope_for_certainty(1.0) - 1.0).abs() < f64::EPSILON);
+        assert!((max_certainty_for_scope(0.0) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_constraint_checker_uses_its_own_k() {
+        let checker = ConstraintChecker::new(0.5).unwrap();
+        assert!(!checker.verify(0.75, 0.8));
+        assert!(checker.verify(0.5, 0.8));
+        assert!((checker.max_scope_for_certainty(1.0) - 0.5).abs() < f64::EPSILON);
+
+        assert!(ConstraintChecker::new(0.0).is_err());
+        assert!(ConstraintChecker::new(1.5).is_err());
+        assert!(ConstraintChecker::new(f64::NAN).is_err());
+        assert!((ConstraintChecker::default().k() - K_CONSTANT).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_frontier_with_tighter_k() {
+        assert!((frontier(0.6, 0.3) - 0.5).abs() < 1e-12);
+        assert!((frontier(1.0, 0.3) - 0.3).abs() < 1e-12);
+        assert!((frontier(0.2, 0.3) - 1.0).abs() < f64::EPSILON);
+        assert!((frontier(f64::NAN, 0.3) - 1.0).abs() < f64::EPSILON);
+    }
+
     proptest! {
+        #[test]
+        fn prop_max_scope_satisfies_constraint(certainty in 0.0..=1.0f64) {
+            let scope = max_scope_for_certainty(certainty);
+            prop_assert!((0.0..=1.0).contains(&scope));
+            prop_assert!(verify_constraint(certainty, scope));
+        }
+
         #[test]
         fn prop_tradeoff_constraint(certainty in 0.0..=1.0, scope in 0.0..=1.0) {
             let product = calculate_tradeoff(certainty, scope);
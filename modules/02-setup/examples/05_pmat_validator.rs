@@ -1,4 +1,5 @@
 use module_02_setup::pmat::{BaselineMetrics, PmatInfo, PmatValidator};
+use std::path::PathBuf;
 
 fn main() {
     println!("PMAT Installation Validator");
@@ -54,6 +55,10 @@ fn verify_mcp_feature(validator: &PmatValidator) {
             println!("  ❌ Error: {}", e);
         }
     }
+
+    if let Ok(features) = validator.list_features() {
+        println!("  Enabled features: {}", features.join(", "));
+    }
 }
 
 fn check_docker_availability(validator: &PmatValidator) {
@@ -70,21 +75,23 @@ fn check_docker_availability(validator: &PmatValidator) {
     }
 }
 
-fn validate_ci_cd_templates(_validator: &PmatValidator) {
+fn validate_ci_cd_templates(validator: &PmatValidator) {
     println!("\n📋 CI/CD Templates:");
 
-    let templates = vec![
-        ("GitHub Actions", ".github/workflows/quality-gate.yml"),
-        ("GitLab CI", ".gitlab-ci.yml"),
-        ("Jenkins", "Jenkinsfile"),
-        ("Azure DevOps", "azure-pipelines.yml"),
-    ];
-
-    for (name, file) in templates {
-        if std::path::Path::new(file).exists() {
-            println!("  ✅ {} template found", name);
+    let root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    for template in validator.detect_templates(&root) {
+        if template.exists {
+            println!(
+                "  ✅ {} template found ({})",
+                template.name,
+                template.path.display()
+            );
         } else {
-            println!("  ⚠️  {} template not found ({})", name, file);
+            println!(
+                "  ⚠️  {} template not found ({})",
+                template.name,
+                template.path.display()
+            );
         }
     }
 }
@@ -164,8 +171,13 @@ fn generate_installation_report(validator: &PmatValidator) {
         coverage: 96.8,
     };
 
-    let report = validator.generate_report(&info, &metrics);
-    println!("{}", report);
+    if std::env::args().any(|arg| arg == "--json") {
+        let report = validator.generate_report_json(&info, &metrics);
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+    } else {
+        let report = validator.generate_report(&info, &metrics);
+        println!("{}", report);
+    }
 
     println!("\n🎯 Next Steps:");
     println!("  1. Configure project-specific thresholds");
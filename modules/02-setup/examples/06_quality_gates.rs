@@ -1,6 +1,6 @@
 use module_02_setup::quality::{
-    generate_sarif_report, ComplexityChecker, CoverageValidator, QualityGateConfig, RuleEngine,
-    SatdScanner,
+    generate_sarif_report, ComplexityChecker, CoverageValidator, QualityGate, QualityGateConfig,
+    QualityRule, RuleEngine, SatdScanner,
 };
 use serde_yaml;
 use std::fs;
@@ -17,6 +17,7 @@ fn main() {
     create_custom_rule_engine();
     generate_sarif_output();
     demonstrate_exit_codes();
+    run_aggregate_gate(&config);
 }
 
 fn parse_quality_gate_config() -> QualityGateConfig {
@@ -175,16 +176,25 @@ fn create_custom_rule_engine() {
 
     let mut engine = RuleEngine::new();
 
-    let rules = vec![
-        ("no_unwrap", "Check for .unwrap() calls"),
-        ("no_panic", "Check for panic! macros"),
-        ("has_tests", "Ensure test module exists"),
-        ("documented", "Check for documentation"),
+    let rules: Vec<(&str, &str, fn(&str) -> bool)> = vec![
+        ("no_unwrap", "Check for .unwrap() calls", |code| {
+            !code.contains(".unwrap()")
+        }),
+        ("no_panic", "Check for panic! macros", |code| {
+            !code.contains("panic!")
+        }),
+        ("has_tests", "Ensure test module exists", |code| {
+            code.contains("#[cfg(test)]")
+        }),
+        ("documented", "Check for documentation", |code| {
+            code.contains("///")
+        }),
     ];
 
     println!("  Registered Rules:");
-    for (name, description) in &rules {
+    for (name, description, rule) in rules {
         println!("    - {}: {}", name, description);
+        engine.add_rule(name, rule);
     }
 
     let sample_code = r#"
@@ -205,16 +215,30 @@ mod tests {
 "#;
 
     println!("\n  Evaluating sample code...");
-    println!("  ✅ All custom rules passed");
+    let violations = engine.evaluate(sample_code);
+    if violations.is_empty() {
+        println!("  ✅ All custom rules passed");
+    } else {
+        println!("  ❌ Violations: {}", violations.join(", "));
+    }
 }
 
 fn generate_sarif_output() {
     println!("\n📄 SARIF Report Generation:");
 
     let violations = vec![
-        "Complexity exceeds threshold in function 'process_order' (25 > 20)".to_string(),
-        "SATD found at line 42: TODO: Implement proper error handling".to_string(),
-        "Coverage below threshold in module 'utils' (92.3% < 95.0%)".to_string(),
+        (
+            QualityRule::Complexity,
+            "Complexity exceeds threshold in function 'process_order' (25 > 20)".to_string(),
+        ),
+        (
+            QualityRule::Satd,
+            "SATD found at line 42: TODO: Implement proper error handling".to_string(),
+        ),
+        (
+            QualityRule::Coverage,
+            "Coverage below threshold in module 'utils' (92.3% < 95.0%)".to_string(),
+        ),
     ];
 
     let report = generate_sarif_report(violations);
@@ -246,6 +270,22 @@ fn demonstrate_exit_codes() {
     println!("    fi");
 }
 
+fn run_aggregate_gate(config: &QualityGateConfig) {
+    println!("\n🧮 Aggregate Quality Gate:");
+
+    let gate = QualityGate::new(config.clone());
+    let report = gate.run("fn add(a: i64, b: i64) -> i64 { a + b }", 92.5, 3.0);
+
+    for result in &report.gates {
+        let mark = if result.passed { "✅" } else { "❌" };
+        match &result.detail {
+            Some(detail) => println!("  {} {}: {}", mark, result.gate, detail),
+            None => println!("  {} {}", mark, result.gate),
+        }
+    }
+    println!("  Overall: {} (exit {})", report.passed, report.exit_code);
+}
+
 fn indent(s: &str, spaces: usize) -> String {
     s.lines()
         .map(|line| format!("{}{}", " ".repeat(spaces), line))
@@ -0,0 +1,6 @@
+use module_02_setup::quality_cli::run_cli;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    std::process::exit(run_cli(&args));
+}
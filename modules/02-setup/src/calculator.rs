@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use thiserror::Error;
 
@@ -11,9 +12,12 @@ pub enum CalculatorError {
 
     #[error("Invalid operation")]
     InvalidOperation,
+
+    #[error("Snapshot holds {history} operations but max_history is {max_history}")]
+    HistoryExceedsCapacity { history: usize, max_history: usize },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Operation {
     Add(i64, i64),
     Subtract(i64, i64),
@@ -21,7 +25,25 @@ pub enum Operation {
     Divide(i64, i64),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperationKind {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
 impl Operation {
+    #[must_use]
+    pub fn kind(&self) -> OperationKind {
+        match self {
+            Operation::Add(..) => OperationKind::Add,
+            Operation::Subtract(..) => OperationKind::Subtract,
+            Operation::Multiply(..) => OperationKind::Multiply,
+            Operation::Divide(..) => OperationKind::Divide,
+        }
+    }
+
     pub fn execute(&self) -> Result<i64, CalculatorError> {
         match self {
             Operation::Add(a, b) => a.checked_add(*b).ok_or(CalculatorError::Overflow),
@@ -38,18 +60,39 @@ impl Operation {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum CalculatorState {
     Ready,
     Computing,
     Error(String),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "CalculatorSnapshot")]
 pub struct Calculator {
     state: CalculatorState,
     history: VecDeque<Operation>,
     max_history: usize,
+    total_operations: usize,
+}
+
+/// Unvalidated serialized form of a [`Calculator`]; see
+/// [`Calculator::from_snapshot`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct CalculatorSnapshot {
+    pub state: CalculatorState,
+    pub history: VecDeque<Operation>,
+    pub max_history: usize,
+    #[serde(default)]
+    pub total_operations: usize,
+}
+
+impl TryFrom<CalculatorSnapshot> for Calculator {
+    type Error = CalculatorError;
+
+    fn try_from(snapshot: CalculatorSnapshot) -> Result<Self, Self::Error> {
+        Self::from_snapshot(snapshot)
+    }
 }
 
 impl Calculator {
@@ -58,17 +101,44 @@ impl Calculator {
             state: CalculatorState::Ready,
             history: VecDeque::with_capacity(100),
             max_history: 100,
+            total_operations: 0,
         }
     }
 
+    /// A `max_history` of 0 keeps no history at all; operations are still
+    /// counted by [`Calculator::total_operations`].
     pub fn with_max_history(max_history: usize) -> Self {
         Self {
             state: CalculatorState::Ready,
             history: VecDeque::with_capacity(max_history),
             max_history,
+            total_operations: 0,
         }
     }
 
+    /// Restores a checkpointed calculator. Every deserialized `Calculator`
+    /// goes through this check.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CalculatorError::HistoryExceedsCapacity`] if the snapshot
+    /// holds more operations than its `max_history` allows.
+    pub fn from_snapshot(snapshot: CalculatorSnapshot) -> Result<Self, CalculatorError> {
+        if snapshot.history.len() > snapshot.max_history {
+            return Err(CalculatorError::HistoryExceedsCapacity {
+                history: snapshot.history.len(),
+                max_history: snapshot.max_history,
+            });
+        }
+
+        Ok(Self {
+            total_operations: snapshot.total_operations.max(snapshot.history.len()),
+            state: snapshot.state,
+            history: snapshot.history,
+            max_history: snapshot.max_history,
+        })
+    }
+
     pub fn add(&mut self, a: i64, b: i64) -> Result<i64, CalculatorError> {
         self.execute_operation(Operation::Add(a, b))
     }
@@ -102,6 +172,10 @@ impl Calculator {
     }
 
     fn add_to_history(&mut self, op: Operation) {
+        self.total_operations += 1;
+        if self.max_history == 0 {
+            return;
+        }
         if self.history.len() >= self.max_history {
             self.history.pop_front();
         }
@@ -112,6 +186,41 @@ impl Calculator {
         &self.history
     }
 
+    #[must_use]
+    pub fn max_history(&self) -> usize {
+        self.max_history
+    }
+
+    /// How many more operations fit before the oldest entry is evicted.
+    #[must_use]
+    pub fn remaining_capacity(&self) -> usize {
+        self.max_history.saturating_sub(self.history.len())
+    }
+
+    /// Counts retained history entries matching `pred`; evicted entries
+    /// are not included.
+    #[must_use]
+    pub fn history_count_by<P: Fn(&Operation) -> bool>(&self, pred: P) -> usize {
+        self.history.iter().filter(|op| pred(op)).count()
+    }
+
+    /// The most recent retained operation of `kind`.
+    #[must_use]
+    pub fn last_of_kind(&self, kind: OperationKind) -> Option<Operation> {
+        self.history
+            .iter()
+            .rev()
+            .find(|op| op.kind() == kind)
+            .copied()
+    }
+
+    /// Successful operations since creation or the last [`Calculator::reset`],
+    /// including those evicted from the bounded history.
+    #[must_use]
+    pub fn total_operations(&self) -> usize {
+        self.total_operations
+    }
+
     pub fn state(&self) -> &CalculatorState {
         &self.state
     }
@@ -123,6 +232,7 @@ impl Calculator {
     pub fn reset(&mut self) {
         self.state = CalculatorState::Ready;
         self.history.clear();
+        self.total_operations = 0;
     }
 }
 
@@ -187,6 +297,97 @@ mod tests {
         assert_eq!(calc.history().len(), 3);
     }
 
+    #[test]
+    fn test_zero_max_history_stores_nothing() {
+        let mut calc = Calculator::with_max_history(0);
+        calc.add(1, 2).unwrap();
+        calc.multiply(3, 4).unwrap();
+
+        assert!(calc.history().is_empty());
+        assert_eq!(calc.remaining_capacity(), 0);
+        assert_eq!(calc.total_operations(), 2);
+    }
+
+    #[test]
+    fn test_history_at_capacity_boundary() {
+        let mut calc = Calculator::with_max_history(2);
+        assert_eq!(calc.max_history(), 2);
+        assert_eq!(calc.remaining_capacity(), 2);
+
+        calc.add(1, 1).unwrap();
+        calc.add(2, 2).unwrap();
+        assert_eq!(calc.remaining_capacity(), 0);
+        assert_eq!(calc.history().len(), 2);
+
+        calc.add(3, 3).unwrap();
+        assert_eq!(calc.history().len(), 2);
+        assert_eq!(calc.history().front(), Some(&Operation::Add(2, 2)));
+    }
+
+    #[test]
+    fn test_history_queries_after_eviction() {
+        let mut calc = Calculator::with_max_history(3);
+        calc.divide(10, 2).unwrap();
+        calc.multiply(2, 3).unwrap();
+        calc.divide(9, 3).unwrap();
+        calc.add(1, 1).unwrap();
+        calc.multiply(4, 5).unwrap();
+        assert!(calc.divide(1, 0).is_err());
+
+        assert_eq!(calc.total_operations(), 5);
+        assert_eq!(calc.history().len(), 3);
+        assert_eq!(
+            calc.history_count_by(|op| op.kind() == OperationKind::Divide),
+            1
+        );
+        assert_eq!(
+            calc.last_of_kind(OperationKind::Multiply),
+            Some(Operation::Multiply(4, 5))
+        );
+        assert_eq!(calc.last_of_kind(OperationKind::Subtract), None);
+
+        calc.reset();
+        assert_eq!(calc.total_operations(), 0);
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_with_error_state() {
+        let mut calc = Calculator::with_max_history(5);
+        calc.add(1, 2).unwrap();
+        calc.multiply(3, 4).unwrap();
+        calc.subtract(9, 5).unwrap();
+        assert!(calc.divide(1, 0).is_err());
+
+        let json = serde_json::to_string(&calc).unwrap();
+        let restored: Calculator = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored, calc);
+        assert_eq!(
+            restored.state(),
+            &CalculatorState::Error("Division by zero".to_string())
+        );
+    }
+
+    #[test]
+    fn test_snapshot_over_capacity_is_rejected() {
+        let json = r#"{
+            "state": "Ready",
+            "history": [{ "Add": [1, 2] }, { "Add": [3, 4] }],
+            "max_history": 1
+        }"#;
+
+        assert!(serde_json::from_str::<Calculator>(json).is_err());
+
+        let snapshot: CalculatorSnapshot = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Calculator::from_snapshot(snapshot),
+            Err(CalculatorError::HistoryExceedsCapacity {
+                history: 2,
+                max_history: 1
+            })
+        );
+    }
+
     #[quickcheck]
     fn prop_add_commutative(a: i64, b: i64) -> bool {
         let mut calc1 = Calculator::new();
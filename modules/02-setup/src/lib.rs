@@ -3,3 +3,4 @@
 pub mod calculator;
 pub mod pmat;
 pub mod quality;
+pub mod quality_cli;
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use thiserror::Error;
 
@@ -67,6 +68,36 @@ impl PmatValidator {
         Ok(features.contains("mcp"))
     }
 
+    /// Every feature reported by `pmat features`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PmatError::NotInstalled`] if `pmat` cannot be run, or
+    /// [`PmatError::CommandFailed`] if it exits unsuccessfully.
+    pub fn list_features(&self) -> Result<Vec<String>, PmatError> {
+        let output = Command::new("pmat")
+            .arg("features")
+            .output()
+            .map_err(|_| PmatError::NotInstalled)?;
+
+        if !output.status.success() {
+            return Err(PmatError::CommandFailed(
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            ));
+        }
+
+        Ok(parse_features(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Whether `name` is one of the features `pmat` reports, matched exactly.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`PmatValidator::list_features`].
+    pub fn has_feature(&self, name: &str) -> Result<bool, PmatError> {
+        Ok(self.list_features()?.iter().any(|feature| feature == name))
+    }
+
     pub fn check_docker(&self) -> bool {
         Command::new("docker")
             .arg("--version")
@@ -119,6 +150,7 @@ impl PmatValidator {
     }
 
     pub fn generate_report(&self, info: &PmatInfo, metrics: &BaselineMetrics) -> String {
+        let report = self.installation_report(info, metrics);
         format!(
             r"PMAT Installation Report
 ========================
@@ -136,25 +168,79 @@ Baseline Metrics:
 - Coverage: {}%
 
 Status: {}",
-            info.version,
-            if info.mcp_enabled { "✅" } else { "❌" },
-            if self.check_docker() { "✅" } else { "❌" },
-            info.features
+            report.version,
+            if report.mcp_enabled { "✅" } else { "❌" },
+            if report.docker_available {
+                "✅"
+            } else {
+                "❌"
+            },
+            report
+                .features
                 .iter()
                 .map(|f| format!("  - {}", f))
                 .collect::<Vec<_>>()
                 .join("\n"),
-            metrics.complexity,
-            metrics.satd_count,
-            metrics.dead_code_percentage,
-            metrics.coverage,
-            if info.mcp_enabled && self.check_docker() {
+            report.metrics.complexity,
+            report.metrics.satd_count,
+            report.metrics.dead_code_percentage,
+            report.metrics.coverage,
+            if report.ready {
                 "✅ Ready"
             } else {
                 "⚠️  Incomplete"
             }
         )
     }
+
+    /// The same data as [`PmatValidator::generate_report`], for CI systems
+    /// and dashboards.
+    #[must_use]
+    pub fn generate_report_json(
+        &self,
+        info: &PmatInfo,
+        metrics: &BaselineMetrics,
+    ) -> serde_json::Value {
+        serde_json::to_value(self.installation_report(info, metrics))
+            .unwrap_or(serde_json::Value::Null)
+    }
+
+    #[must_use]
+    pub fn installation_report(
+        &self,
+        info: &PmatInfo,
+        metrics: &BaselineMetrics,
+    ) -> InstallationReport {
+        InstallationReport::new(info, metrics, self.check_docker())
+    }
+
+    /// Looks for each known CI template under `root`, the project being
+    /// validated, rather than the process's working directory.
+    #[must_use]
+    pub fn detect_templates(&self, root: &Path) -> Vec<TemplateStatus> {
+        CI_TEMPLATES
+            .iter()
+            .map(|&(name, relative)| {
+                let path = root.join(relative);
+                TemplateStatus {
+                    name: name.to_string(),
+                    exists: path.is_file(),
+                    path,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Splits `pmat features` output, which may be comma- or newline-separated,
+/// optionally with list bullets.
+fn parse_features(output: &str) -> Vec<String> {
+    output
+        .split([',', '\n'])
+        .map(|feature| feature.trim().trim_start_matches(['-', '*']).trim())
+        .filter(|feature| !feature.is_empty())
+        .map(str::to_string)
+        .collect()
 }
 
 impl Default for PmatValidator {
@@ -163,7 +249,7 @@ impl Default for PmatValidator {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BaselineMetrics {
     pub complexity: u32,
     pub satd_count: u32,
@@ -171,6 +257,48 @@ pub struct BaselineMetrics {
     pub coverage: f64,
 }
 
+const CI_TEMPLATES: &[(&str, &str)] = &[
+    ("GitHub Actions", ".github/workflows/quality-gate.yml"),
+    ("GitLab CI", ".gitlab-ci.yml"),
+    ("Jenkins", "Jenkinsfile"),
+    ("Azure DevOps", "azure-pipelines.yml"),
+];
+
+/// Whether one known CI template is present in a project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateStatus {
+    pub name: String,
+    /// Where the template is, or would be, under the project root.
+    pub path: PathBuf,
+    pub exists: bool,
+}
+
+/// Machine-readable form of the installation report.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstallationReport {
+    pub version: String,
+    pub mcp_enabled: bool,
+    pub docker_available: bool,
+    pub features: Vec<String>,
+    pub metrics: BaselineMetrics,
+    /// Both MCP and Docker are available.
+    pub ready: bool,
+}
+
+impl InstallationReport {
+    #[must_use]
+    pub fn new(info: &PmatInfo, metrics: &BaselineMetrics, docker_available: bool) -> Self {
+        Self {
+            version: info.version.clone(),
+            mcp_enabled: info.mcp_enabled,
+            docker_available,
+            features: info.features.clone(),
+            metrics: metrics.clone(),
+            ready: info.mcp_enabled && docker_available,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -185,4 +313,58 @@ mod tests {
         assert!(metrics.dead_code_percentage < 5.0);
         assert!(metrics.coverage > 95.0);
     }
+
+    #[test]
+    fn test_json_report_fields() {
+        let validator = PmatValidator::new();
+        let info = PmatInfo {
+            version: "pmat 2.4.0".to_string(),
+            features: vec!["complexity".to_string()],
+            mcp_enabled: true,
+        };
+        let metrics = validator.measure_baseline_metrics().unwrap();
+
+        let json = validator.generate_report_json(&info, &metrics);
+
+        assert_eq!(json["version"], "pmat 2.4.0");
+        assert_eq!(json["mcp_enabled"], true);
+        assert!(json["docker_available"].is_boolean());
+        assert!((json["metrics"]["coverage"].as_f64().unwrap() - 96.3).abs() < f64::EPSILON);
+
+        let report: InstallationReport = serde_json::from_value(json).unwrap();
+        assert_eq!(report.ready, report.docker_available);
+    }
+
+    #[test]
+    fn test_detect_templates_under_root() {
+        let root = tempfile::tempdir().unwrap();
+        let workflows = root.path().join(".github/workflows");
+        std::fs::create_dir_all(&workflows).unwrap();
+        std::fs::write(workflows.join("quality-gate.yml"), "name: quality\n").unwrap();
+
+        let templates = PmatValidator::new().detect_templates(root.path());
+
+        let github = templates
+            .iter()
+            .find(|t| t.name == "GitHub Actions")
+            .unwrap();
+        assert!(github.exists);
+        assert_eq!(github.path, workflows.join("quality-gate.yml"));
+        assert_eq!(templates.iter().filter(|t| t.exists).count(), 1);
+    }
+
+    #[test]
+    fn test_parse_features_formats() {
+        let expected = vec!["mcp", "complexity", "satd", "dead-code"];
+
+        assert_eq!(
+            parse_features("mcp, complexity,satd , dead-code\n"),
+            expected
+        );
+        assert_eq!(
+            parse_features("mcp\ncomplexity\r\n- satd\n\n* dead-code\n"),
+            expected
+        );
+        assert!(parse_features("").is_empty());
+    }
 }
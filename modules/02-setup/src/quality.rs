@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -17,6 +19,92 @@ pub enum QualityError {
     DeadCodeExceeded(f64, f64),
 }
 
+impl QualityError {
+    #[must_use]
+    pub fn rule(&self) -> QualityRule {
+        match self {
+            QualityError::ComplexityExceeded(..) => QualityRule::Complexity,
+            QualityError::SatdViolation(_) => QualityRule::Satd,
+            QualityError::CoverageBelowThreshold(..) => QualityRule::Coverage,
+            QualityError::DeadCodeExceeded(..) => QualityRule::DeadCode,
+        }
+    }
+}
+
+const QUALITY_STANDARDS_URI: &str =
+    "https://github.com/paiml/deterministic-mcp-agents#quality-standards";
+
+/// The quality rules a SARIF report can reference by `ruleId`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityRule {
+    Complexity,
+    Satd,
+    Coverage,
+    DeadCode,
+}
+
+impl QualityRule {
+    pub const ALL: [QualityRule; 4] = [
+        QualityRule::Complexity,
+        QualityRule::Satd,
+        QualityRule::Coverage,
+        QualityRule::DeadCode,
+    ];
+
+    #[must_use]
+    pub fn id(self) -> &'static str {
+        match self {
+            QualityRule::Complexity => "QG001",
+            QualityRule::Satd => "QG002",
+            QualityRule::Coverage => "QG003",
+            QualityRule::DeadCode => "QG004",
+        }
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            QualityRule::Complexity => "ComplexityExceeded",
+            QualityRule::Satd => "SelfAdmittedTechnicalDebt",
+            QualityRule::Coverage => "CoverageBelowThreshold",
+            QualityRule::DeadCode => "DeadCodeExceeded",
+        }
+    }
+
+    #[must_use]
+    pub fn description(self) -> &'static str {
+        match self {
+            QualityRule::Complexity => "Cyclomatic complexity exceeds the configured maximum",
+            QualityRule::Satd => "Code contains a technical debt marker such as TODO or FIXME",
+            QualityRule::Coverage => "Test coverage is below the configured minimum",
+            QualityRule::DeadCode => "Dead code exceeds the configured percentage",
+        }
+    }
+
+    #[must_use]
+    pub fn default_level(self) -> &'static str {
+        match self {
+            QualityRule::Complexity | QualityRule::Coverage => "error",
+            QualityRule::Satd | QualityRule::DeadCode => "warning",
+        }
+    }
+
+    #[must_use]
+    pub fn sarif_rule(self) -> SarifRule {
+        SarifRule {
+            id: self.id().to_string(),
+            name: self.name().to_string(),
+            short_description: SarifMessage {
+                text: self.description().to_string(),
+            },
+            help_uri: QUALITY_STANDARDS_URI.to_string(),
+            default_configuration: SarifRuleConfiguration {
+                level: self.default_level().to_string(),
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QualityGateConfig {
     pub max_complexity: u32,
@@ -58,25 +146,123 @@ impl ComplexityChecker {
     }
 
     pub fn calculate_cyclomatic(&self, code: &str) -> u32 {
-        let mut complexity = 1;
+        cyclomatic_complexity(code)
+    }
+}
 
-        for line in code.lines() {
-            let trimmed = line.trim();
-            if trimmed.starts_with("if ") || trimmed.starts_with("else if ") {
-                complexity += 1;
-            }
-            if trimmed.starts_with("for ") || trimmed.starts_with("while ") {
-                complexity += 1;
-            }
-            if trimmed.starts_with("match ") {
-                complexity += 1;
-            }
-            if trimmed.contains(" && ") || trimmed.contains(" || ") {
-                complexity += 1;
-            }
+fn cyclomatic_complexity(code: &str) -> u32 {
+    let mut complexity = 1;
+
+    for line in code.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("if ") || trimmed.starts_with("else if ") {
+            complexity += 1;
+        }
+        if trimmed.starts_with("for ") || trimmed.starts_with("while ") {
+            complexity += 1;
+        }
+        if trimmed.starts_with("match ") {
+            complexity += 1;
+        }
+        if trimmed.contains(" && ") || trimmed.contains(" || ") {
+            complexity += 1;
+        }
+    }
+
+    complexity
+}
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher` it is stable across Rust releases,
+/// so cached hashes stay valid between CI runs.
+fn content_hash(content: &str) -> u64 {
+    content.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct CacheEntry {
+    hash: u64,
+    complexity: u32,
+}
+
+/// Cyclomatic complexity per file, keyed by path and content hash, so only
+/// changed files are re-analyzed. [`ComplexityCache::save`] and
+/// [`ComplexityCache::load`] persist it between runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ComplexityCache {
+    entries: HashMap<PathBuf, CacheEntry>,
+    #[serde(skip)]
+    recomputations: usize,
+}
+
+impl ComplexityCache {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if `path` cannot be read or does not hold a cache.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(std::io::Error::other)
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if `path` cannot be written.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+
+    /// Returns the file's cyclomatic complexity, recomputing it only when
+    /// the content changed since it was last cached.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` cannot be read.
+    pub fn analyze_path(&mut self, path: &Path) -> std::io::Result<u32> {
+        let content = std::fs::read_to_string(path)?;
+        let hash = content_hash(&content);
+
+        if let Some(entry) = self.entries.get(path).filter(|entry| entry.hash == hash) {
+            return Ok(entry.complexity);
         }
 
-        complexity
+        self.recomputations += 1;
+        let complexity = cyclomatic_complexity(&content);
+        self.entries
+            .insert(path.to_path_buf(), CacheEntry { hash, complexity });
+        Ok(complexity)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// One SATD marker found on a line; a line with several markers yields one
+/// hit per marker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SatdHit {
+    /// 1-based line number.
+    pub line: usize,
+    pub pattern: String,
+    pub text: String,
+}
+
+impl std::fmt::Display for SatdHit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.text)
     }
 }
 
@@ -100,17 +286,35 @@ impl SatdScanner {
     }
 
     pub fn scan(&self, code: &str) -> Vec<String> {
-        let mut violations = Vec::new();
-
-        for (line_num, line) in code.lines().enumerate() {
-            for pattern in &self.patterns {
-                if line.contains(pattern) {
-                    violations.push(format!("Line {}: {}", line_num + 1, line.trim()));
-                }
-            }
-        }
+        self.scan_reader(code.as_bytes())
+            .map(|hit| hit.to_string())
+            .collect()
+    }
 
-        violations
+    /// Scans `reader` one line at a time, yielding hits lazily so large files
+    /// never have to be held in memory. Invalid UTF-8 is decoded lossily so a
+    /// stray byte does not hide the lines after it; stops at the first I/O
+    /// error.
+    pub fn scan_reader<'a, R: BufRead + 'a>(
+        &'a self,
+        reader: R,
+    ) -> impl Iterator<Item = SatdHit> + 'a {
+        reader
+            .split(b'\n')
+            .map_while(Result::ok)
+            .enumerate()
+            .flat_map(move |(line_num, bytes)| {
+                let line = String::from_utf8_lossy(&bytes);
+                self.patterns
+                    .iter()
+                    .filter(|pattern| line.contains(pattern.as_str()))
+                    .map(|pattern| SatdHit {
+                        line: line_num + 1,
+                        pattern: pattern.clone(),
+                        text: line.trim().to_string(),
+                    })
+                    .collect::<Vec<_>>()
+            })
     }
 
     pub fn check(&self, code: &str) -> Result<(), QualityError> {
@@ -151,25 +355,279 @@ impl CoverageValidator {
     }
 }
 
+/// Outcome of one check within a [`QualityReport`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GateResult {
+    pub gate: String,
+    pub passed: bool,
+    pub detail: Option<String>,
+}
+
+impl GateResult {
+    /// The SARIF rule this gate reports under; `None` for configuration
+    /// failures.
+    #[must_use]
+    pub fn rule(&self) -> Option<QualityRule> {
+        match self.gate.as_str() {
+            "complexity" => Some(QualityRule::Complexity),
+            "satd" => Some(QualityRule::Satd),
+            "coverage" => Some(QualityRule::Coverage),
+            "dead_code" => Some(QualityRule::DeadCode),
+            _ => None,
+        }
+    }
+}
+
+/// Every gate's result plus the verdict CI acts on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityReport {
+    pub gates: Vec<GateResult>,
+    pub passed: bool,
+    /// 0 when every gate passes, 1 on a violation, 2 when the gates could
+    /// not run because the configuration or input was invalid.
+    pub exit_code: i32,
+}
+
+impl QualityReport {
+    fn from_gates(gates: Vec<GateResult>) -> Self {
+        let passed = gates.iter().all(|gate| gate.passed);
+        Self {
+            gates,
+            passed,
+            exit_code: i32::from(!passed),
+        }
+    }
+
+    fn invalid(detail: String) -> Self {
+        Self {
+            gates: vec![GateResult {
+                gate: "configuration".to_string(),
+                passed: false,
+                detail: Some(detail),
+            }],
+            passed: false,
+            exit_code: 2,
+        }
+    }
+}
+
+/// Runs the complexity, SATD, coverage and dead-code checks configured by a
+/// [`QualityGateConfig`] in one pass. The SATD gate is skipped when
+/// `allow_satd` is set. Coverage and dead code come from separate analyses,
+/// so their percentages are passed in.
+#[derive(Debug, Clone)]
+pub struct QualityGate {
+    config: QualityGateConfig,
+    complexity: ComplexityChecker,
+    satd: SatdScanner,
+    coverage: CoverageValidator,
+}
+
+impl QualityGate {
+    #[must_use]
+    pub fn new(config: QualityGateConfig) -> Self {
+        Self {
+            complexity: ComplexityChecker::new(config.max_complexity),
+            satd: SatdScanner::new(),
+            coverage: CoverageValidator::new(config.min_coverage),
+            config,
+        }
+    }
+
+    #[must_use]
+    pub fn run(&self, code: &str, coverage: f64, dead_code: f64) -> QualityReport {
+        let percentages = [
+            ("min_coverage", self.config.min_coverage),
+            ("max_dead_code", self.config.max_dead_code),
+            ("coverage", coverage),
+            ("dead_code", dead_code),
+        ];
+        for (name, value) in percentages {
+            if !(0.0..=100.0).contains(&value) {
+                return QualityReport::invalid(format!(
+                    "{name} must be within 0..=100, got {value}"
+                ));
+            }
+        }
+
+        let cyclomatic = self.complexity.calculate_cyclomatic(code);
+        let mut gates = vec![gate_result("complexity", self.complexity.check(cyclomatic))];
+        if !self.config.allow_satd {
+            gates.push(gate_result("satd", self.satd.check(code)));
+        }
+        gates.push(gate_result("coverage", self.coverage.validate(coverage)));
+        gates.push(gate_result("dead_code", self.check_dead_code(dead_code)));
+
+        QualityReport::from_gates(gates)
+    }
+
+    fn check_dead_code(&self, dead_code: f64) -> Result<(), QualityError> {
+        if dead_code > self.config.max_dead_code {
+            Err(QualityError::DeadCodeExceeded(
+                dead_code,
+                self.config.max_dead_code,
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn gate_result(gate: &str, outcome: Result<(), QualityError>) -> GateResult {
+    GateResult {
+        gate: gate.to_string(),
+        passed: outcome.is_ok(),
+        detail: outcome.err().map(|e| e.to_string()),
+    }
+}
+
+/// Per-file results from [`scan_paths`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileReport {
+    pub path: PathBuf,
+    pub complexity: u32,
+    pub satd: Vec<String>,
+    pub dead_code_percentage: f64,
+    pub passed: bool,
+    /// Set when the file could not be read; the metrics are then zero.
+    pub error: Option<String>,
+}
+
+impl FileReport {
+    fn analyze(path: &Path, config: &QualityGateConfig, scanner: &SatdScanner) -> Self {
+        let code = match std::fs::read_to_string(path) {
+            Ok(code) => code,
+            Err(e) => {
+                return Self {
+                    path: path.to_path_buf(),
+                    complexity: 0,
+                    satd: Vec::new(),
+                    dead_code_percentage: 0.0,
+                    passed: false,
+                    error: Some(e.to_string()),
+                }
+            }
+        };
+
+        let complexity = cyclomatic_complexity(&code);
+        let satd = scanner.scan(&code);
+        let dead_code_percentage = dead_code_percentage(&code);
+        Self {
+            path: path.to_path_buf(),
+            passed: complexity <= config.max_complexity
+                && (config.allow_satd || satd.is_empty())
+                && dead_code_percentage <= config.max_dead_code,
+            complexity,
+            satd,
+            dead_code_percentage,
+            error: None,
+        }
+    }
+}
+
+/// Reads and analyzes `paths` on a bounded pool of worker threads, one per
+/// available core at most. Reports come back sorted by path whatever order
+/// the workers finish in. A panic in a worker is re-raised on the caller
+/// rather than dropping that worker's reports.
+#[must_use]
+pub fn scan_paths(paths: &[PathBuf], config: &QualityGateConfig) -> Vec<FileReport> {
+    let workers = std::thread::available_parallelism()
+        .map_or(1, std::num::NonZeroUsize::get)
+        .min(paths.len());
+    let next = std::sync::atomic::AtomicUsize::new(0);
+    let scanner = SatdScanner::new();
+
+    let mut reports: Vec<FileReport> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        let Some(path) = paths.get(index) else {
+                            return done;
+                        };
+                        done.push(FileReport::analyze(path, config, &scanner));
+                    }
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+
+    reports.sort_by(|a, b| a.path.cmp(&b.path));
+    reports
+}
+
+/// Share of private functions never referenced elsewhere in the same file,
+/// as a percentage. A file-local heuristic: callers in other files are not
+/// seen, which is why only private functions are counted.
+fn dead_code_percentage(code: &str) -> f64 {
+    let private_fns: Vec<&str> = code
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("fn "))
+        .filter_map(|rest| rest.split(['(', '<']).next())
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && *name != "main")
+        .collect();
+    if private_fns.is_empty() {
+        return 0.0;
+    }
+
+    let unused = private_fns
+        .iter()
+        .filter(|name| code.matches(&format!("{name}(")).count() <= 1)
+        .count();
+    #[allow(clippy::cast_precision_loss)]
+    let percentage = unused as f64 / private_fns.len() as f64 * 100.0;
+    percentage
+}
+
+type Rule = Box<dyn Fn(&str) -> bool>;
+
+/// Named code rules, evaluated in registration order so reports are
+/// reproducible.
 pub struct RuleEngine {
-    rules: HashMap<String, Box<dyn Fn(&str) -> bool>>,
+    rules: Vec<(String, Rule)>,
+    index: HashMap<String, usize>,
 }
 
 impl std::fmt::Debug for RuleEngine {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RuleEngine")
             .field("rules_count", &self.rules.len())
-            .finish()
+            .finish_non_exhaustive()
     }
 }
 
 impl RuleEngine {
     pub fn new() -> Self {
         Self {
-            rules: HashMap::new(),
+            rules: Vec::new(),
+            index: HashMap::new(),
         }
     }
 
+    /// Registers `rule`, which returns `true` when `code` complies.
+    /// Re-registering a name replaces the rule but keeps its position.
+    pub fn add_rule<F: Fn(&str) -> bool + 'static>(&mut self, name: &str, rule: F) {
+        if let Some(&i) = self.index.get(name) {
+            self.rules[i].1 = Box::new(rule);
+        } else {
+            self.index.insert(name.to_string(), self.rules.len());
+            self.rules.push((name.to_string(), Box::new(rule)));
+        }
+    }
+
+    /// Names of the rules `code` violates, in registration order.
     pub fn evaluate(&self, code: &str) -> Vec<String> {
         let mut violations = Vec::new();
 
@@ -210,10 +668,29 @@ pub struct SarifTool {
 pub struct SarifDriver {
     pub name: String,
     pub version: String,
+    #[serde(default)]
+    pub rules: Vec<SarifRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRule {
+    pub id: String,
+    pub name: String,
+    pub short_description: SarifMessage,
+    pub help_uri: String,
+    pub default_configuration: SarifRuleConfiguration,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SarifRuleConfiguration {
+    pub level: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct SarifResult {
+    pub rule_id: String,
     pub message: SarifMessage,
     pub level: String,
 }
@@ -223,7 +700,9 @@ pub struct SarifMessage {
     pub text: String,
 }
 
-pub fn generate_sarif_report(violations: Vec<String>) -> SarifReport {
+/// Builds a SARIF 2.1.0 report whose driver lists every [`QualityRule`] and
+/// whose results reference them by `ruleId`.
+pub fn generate_sarif_report(violations: Vec<(QualityRule, String)>) -> SarifReport {
     SarifReport {
         version: "2.1.0".to_string(),
         runs: vec![SarifRun {
@@ -231,13 +710,18 @@ pub fn generate_sarif_report(violations: Vec<String>) -> SarifReport {
                 driver: SarifDriver {
                     name: "quality-gates".to_string(),
                     version: "0.1.0".to_string(),
+                    rules: QualityRule::ALL
+                        .into_iter()
+                        .map(QualityRule::sarif_rule)
+                        .collect(),
                 },
             },
             results: violations
                 .into_iter()
-                .map(|v| SarifResult {
+                .map(|(rule, v)| SarifResult {
+                    rule_id: rule.id().to_string(),
                     message: SarifMessage { text: v },
-                    level: "error".to_string(),
+                    level: rule.default_level().to_string(),
                 })
                 .collect(),
         }],
@@ -274,6 +758,64 @@ mod tests {
         assert!(complexity > 1);
     }
 
+    #[test]
+    fn test_scan_reader_yields_hits_per_line() {
+        let scanner = SatdScanner::new();
+        let input = std::io::Cursor::new(
+            "fn a() {}\n// TODO: split\nfn b() {}\n// FIXME HACK\n".to_string(),
+        );
+
+        let hits: Vec<(usize, String)> = scanner
+            .scan_reader(input)
+            .map(|hit| (hit.line, hit.pattern))
+            .collect();
+
+        assert_eq!(
+            hits,
+            [
+                (2, "TODO".to_string()),
+                (4, "FIXME".to_string()),
+                (4, "HACK".to_string()),
+            ]
+        );
+        assert_eq!(
+            scanner.scan("x\n  // TODO: later  "),
+            ["Line 2: // TODO: later"]
+        );
+    }
+
+    #[test]
+    fn test_scan_reader_continues_past_invalid_utf8() {
+        let scanner = SatdScanner::new();
+        let input: &[u8] = b"let s = \"\xff\xfe\";\n// TODO: after the bad bytes\n";
+
+        let lines: Vec<usize> = scanner.scan_reader(input).map(|hit| hit.line).collect();
+
+        assert_eq!(lines, [2]);
+    }
+
+    #[test]
+    fn test_complexity_cache_skips_unchanged_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "fn f(x: i32) {\n    if x > 0 {}\n}\n").unwrap();
+
+        let mut cache = ComplexityCache::new();
+        assert_eq!(cache.analyze_path(&file).unwrap(), 2);
+        assert_eq!(cache.analyze_path(&file).unwrap(), 2);
+        assert_eq!(cache.recomputations, 1);
+
+        let saved = dir.path().join("cache.json");
+        cache.save(&saved).unwrap();
+        let mut restored = ComplexityCache::load(&saved).unwrap();
+        assert_eq!(restored.analyze_path(&file).unwrap(), 2);
+        assert_eq!(restored.recomputations, 0);
+
+        std::fs::write(&file, "fn f() {}\n").unwrap();
+        assert_eq!(restored.analyze_path(&file).unwrap(), 1);
+        assert_eq!(restored.recomputations, 1);
+    }
+
     #[test]
     fn test_satd_scanner() {
         let scanner = SatdScanner::new();
@@ -285,10 +827,113 @@ mod tests {
         assert!(scanner.check(dirty_code).is_err());
     }
 
+    #[test]
+    fn test_quality_gate_reports_single_failure() {
+        let gate = QualityGate::new(QualityGateConfig::default());
+        let report = gate.run("fn add(a: i32, b: i32) -> i32 { a + b }", 80.0, 2.0);
+
+        assert!(!report.passed);
+        assert_eq!(report.exit_code, 1);
+        let failed: Vec<&str> = report
+            .gates
+            .iter()
+            .filter(|g| !g.passed)
+            .map(|g| g.gate.as_str())
+            .collect();
+        assert_eq!(failed, ["coverage"]);
+        assert_eq!(report.gates.len(), 4);
+    }
+
+    #[test]
+    fn test_quality_gate_checks_dead_code() {
+        let gate = QualityGate::new(QualityGateConfig::default());
+        let report = gate.run("fn main() {}", 99.0, 12.5);
+
+        let dead_code = report.gates.iter().find(|g| g.gate == "dead_code").unwrap();
+        assert!(!dead_code.passed);
+        assert_eq!(dead_code.rule(), Some(QualityRule::DeadCode));
+        assert_eq!(report.exit_code, 1);
+        assert_eq!(gate.run("fn main() {}", 99.0, 101.0).exit_code, 2);
+    }
+
+    #[test]
+    fn test_quality_gate_passes_and_rejects_bad_input() {
+        let gate = QualityGate::new(QualityGateConfig::default());
+        assert_eq!(gate.run("fn main() {}", 99.0, 0.0).exit_code, 0);
+        assert_eq!(gate.run("fn main() {}", f64::NAN, 0.0).exit_code, 2);
+    }
+
+    #[test]
+    fn test_sarif_results_reference_driver_rules() {
+        let violation = CoverageValidator::new(95.0).validate(90.0).unwrap_err();
+        let report = generate_sarif_report(vec![(violation.rule(), violation.to_string())]);
+        let json = serde_json::to_value(&report).unwrap();
+
+        let rules = json["runs"][0]["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap();
+        assert_eq!(rules.len(), 4);
+        assert!(rules.iter().all(
+            |rule| rule["helpUri"].is_string() && rule["shortDescription"]["text"].is_string()
+        ));
+
+        let rule_id = &json["runs"][0]["results"][0]["ruleId"];
+        assert_eq!(rule_id, "QG003");
+        assert!(rules.iter().any(|rule| &rule["id"] == rule_id));
+    }
+
+    #[test]
+    fn test_rule_engine_reports_in_registration_order() {
+        for _ in 0..20 {
+            let mut engine = RuleEngine::new();
+            engine.add_rule("no_unwrap", |code| !code.contains(".unwrap()"));
+            engine.add_rule("documented", |code| code.contains("///"));
+            engine.add_rule("no_panic", |code| !code.contains("panic!"));
+            engine.add_rule("no_unwrap", |code| !code.contains("unwrap"));
+
+            let violations = engine.evaluate("fn f() { x.unwrap(); panic!() }");
+            assert_eq!(violations, ["no_unwrap", "documented", "no_panic"]);
+        }
+    }
+
     #[test]
     fn test_coverage_validator() {
         let validator = CoverageValidator::new(95.0);
         assert!(validator.validate(96.0).is_ok());
         assert!(validator.validate(94.0).is_err());
     }
+
+    #[test]
+    fn test_scan_paths_orders_reports_by_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            ("c.rs", "fn main() {\n    helper();\n}\n\nfn helper() {}\n"),
+            ("a.rs", "// TODO: tidy\nfn main() {}\n"),
+            ("b.rs", "fn main() {}\n\nfn unused() {}\n"),
+        ];
+        let paths: Vec<PathBuf> = files
+            .iter()
+            .map(|(name, code)| {
+                let path = dir.path().join(name);
+                std::fs::write(&path, code).unwrap();
+                path
+            })
+            .collect();
+
+        let reports = scan_paths(&paths, &QualityGateConfig::default());
+
+        let names: Vec<_> = reports
+            .iter()
+            .map(|r| r.path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, ["a.rs", "b.rs", "c.rs"]);
+        assert_eq!(reports[0].satd.len(), 1);
+        assert!(!reports[0].passed);
+        assert!((reports[1].dead_code_percentage - 100.0).abs() < f64::EPSILON);
+        assert!(!reports[1].passed);
+        assert!(reports[2].passed);
+        assert!(reports
+            .iter()
+            .all(|r| r.complexity == 1 && r.error.is_none()));
+    }
 }
@@ -0,0 +1,176 @@
+use crate::quality::{generate_sarif_report, QualityGate, QualityGateConfig, QualityRule};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+pub const USAGE: &str =
+    "usage: quality-gate [--config <file.yaml>] [--coverage <percent>] [--dead-code <percent>] [--sarif <out.json>] <path>";
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("{0}\n{USAGE}")]
+    Usage(String),
+
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(Debug)]
+struct CliArgs {
+    config: Option<PathBuf>,
+    coverage: f64,
+    dead_code: f64,
+    sarif: Option<PathBuf>,
+    path: PathBuf,
+}
+
+/// Runs the quality gates over `path` (a file, or every `.rs` file below a
+/// directory) and returns the process exit code: 0 when every gate passes,
+/// 1 on violations, 2 on a usage, configuration or IO error. `args`
+/// excludes the program name. Coverage and dead code are not measured here;
+/// without `--coverage` or `--dead-code` those gates are treated as met.
+#[must_use]
+pub fn run_cli(args: &[String]) -> i32 {
+    match run(args) {
+        Ok(exit_code) => exit_code,
+        Err(e) => {
+            eprintln!("quality-gate: {e}");
+            2
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<i32, CliError> {
+    let args = parse_args(args)?;
+    let gate = QualityGate::new(load_config(args.config.as_deref())?);
+    let files = collect_sources(&args.path)?;
+    if files.is_empty() {
+        return Err(CliError::Usage(format!(
+            "No Rust sources found under {}",
+            args.path.display()
+        )));
+    }
+
+    let mut exit_code = 0;
+    let mut violations = Vec::new();
+    for file in &files {
+        let report = gate.run(&fs::read_to_string(file)?, args.coverage, args.dead_code);
+        if report.exit_code == 2 {
+            let detail = report.gates.into_iter().find_map(|g| g.detail);
+            return Err(CliError::Config(detail.unwrap_or_default()));
+        }
+        exit_code = exit_code.max(report.exit_code);
+
+        for failed in report.gates.into_iter().filter(|g| !g.passed) {
+            let detail = failed.detail.clone().unwrap_or_default();
+            let Some(rule) = failed.rule() else { continue };
+            // Coverage and dead code are project-wide, so report them once
+            // rather than per file.
+            if matches!(rule, QualityRule::Coverage | QualityRule::DeadCode) {
+                if violations.iter().all(|(r, _)| *r != rule) {
+                    println!("{}: {detail}", failed.gate);
+                    violations.push((rule, detail));
+                }
+                continue;
+            }
+            println!("{}: {}: {detail}", file.display(), failed.gate);
+            violations.push((rule, format!("{}: {detail}", file.display())));
+        }
+    }
+
+    if let Some(sarif) = &args.sarif {
+        let report = generate_sarif_report(violations);
+        let json =
+            serde_json::to_string_pretty(&report).map_err(|e| CliError::Config(e.to_string()))?;
+        fs::write(sarif, json)?;
+    }
+
+    println!(
+        "{} file(s) checked: {}",
+        files.len(),
+        if exit_code == 0 { "pass" } else { "fail" }
+    );
+    Ok(exit_code)
+}
+
+fn parse_args(args: &[String]) -> Result<CliArgs, CliError> {
+    let mut config = None;
+    let mut coverage = 100.0;
+    let mut dead_code = 0.0;
+    let mut sarif = None;
+    let mut path = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| CliError::Usage(format!("{flag} needs a value")))
+        };
+        match arg.as_str() {
+            "--config" => config = Some(PathBuf::from(value("--config")?)),
+            "--sarif" => sarif = Some(PathBuf::from(value("--sarif")?)),
+            "--coverage" => {
+                let raw = value("--coverage")?;
+                coverage = raw
+                    .parse()
+                    .map_err(|_| CliError::Usage(format!("Invalid coverage: {raw}")))?;
+            }
+            "--dead-code" => {
+                let raw = value("--dead-code")?;
+                dead_code = raw
+                    .parse()
+                    .map_err(|_| CliError::Usage(format!("Invalid dead code: {raw}")))?;
+            }
+            flag if flag.starts_with("--") => {
+                return Err(CliError::Usage(format!("Unknown option: {flag}")));
+            }
+            other if path.is_none() => path = Some(PathBuf::from(other)),
+            other => return Err(CliError::Usage(format!("Unexpected argument: {other}"))),
+        }
+    }
+
+    Ok(CliArgs {
+        config,
+        coverage,
+        dead_code,
+        sarif,
+        path: path.ok_or_else(|| CliError::Usage("Missing path".to_string()))?,
+    })
+}
+
+fn load_config(path: Option<&Path>) -> Result<QualityGateConfig, CliError> {
+    match path {
+        Some(path) => serde_yaml::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| CliError::Config(e.to_string())),
+        None => Ok(QualityGateConfig::default()),
+    }
+}
+
+fn collect_sources(path: &Path) -> Result<Vec<PathBuf>, CliError> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?.path();
+            let name = entry.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if name.starts_with('.') || name == "target" {
+                continue;
+            }
+            if entry.is_dir() {
+                pending.push(entry);
+            } else if entry.extension().is_some_and(|ext| ext == "rs") {
+                files.push(entry);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
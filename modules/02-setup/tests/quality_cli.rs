@@ -0,0 +1,73 @@
+use std::path::Path;
+use std::process::Command;
+
+fn quality_gate(args: &[&str]) -> i32 {
+    Command::new(env!("CARGO_BIN_EXE_quality-gate"))
+        .args(args)
+        .output()
+        .unwrap()
+        .status
+        .code()
+        .unwrap()
+}
+
+fn write(dir: &Path, name: &str, contents: &str) -> String {
+    let path = dir.join(name);
+    std::fs::write(&path, contents).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_clean_file_passes() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = write(
+        dir.path(),
+        "clean.rs",
+        "fn add(a: i64, b: i64) -> i64 { a + b }\n",
+    );
+
+    assert_eq!(quality_gate(&[&file]), 0);
+}
+
+#[test]
+fn test_todo_fails_and_writes_sarif() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = write(
+        dir.path(),
+        "debt.rs",
+        "fn add() {} // TODO: handle overflow\n",
+    );
+    let sarif = dir.path().join("out.json");
+
+    assert_eq!(
+        quality_gate(&["--sarif", sarif.to_str().unwrap(), &file]),
+        1
+    );
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(sarif).unwrap()).unwrap();
+    assert_eq!(report["runs"][0]["results"][0]["ruleId"], "QG002");
+}
+
+#[test]
+fn test_unreadable_config_is_exit_two() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = write(dir.path(), "clean.rs", "fn main() {}\n");
+    let config = write(
+        dir.path(),
+        "gate.yaml",
+        "max_complexity: [not, a, number]\n",
+    );
+
+    assert_eq!(quality_gate(&["--config", &config, &file]), 2);
+    assert_eq!(quality_gate(&["--config", "missing.yaml", &file]), 2);
+}
+
+#[test]
+fn test_dead_code_over_threshold_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = write(dir.path(), "clean.rs", "fn main() {}\n");
+
+    assert_eq!(quality_gate(&["--dead-code", "2", &file]), 0);
+    assert_eq!(quality_gate(&["--dead-code", "20", &file]), 1);
+}
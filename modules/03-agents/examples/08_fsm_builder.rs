@@ -1,3 +1,4 @@
+use module_03_agents::fsm_builder::typestate;
 use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::time::Instant;
@@ -197,9 +198,16 @@ fn demonstrate_compile_time_validation() {
     let _builder: FsmBuilder<TypedState<i32>, Event> =
         FsmBuilder::new().initial_state(TypedState { value: 0 });
 
+    let complete = typestate::Fsm::new().start().finish();
+    // `typestate::Fsm::new().finish()` is rejected by the compiler: `finish`
+    // only exists on `Fsm<Running>`.
+
     println!("  ✅ Type-safe state definitions");
     println!("  ✅ PhantomData prevents type errors");
-    println!("  ✅ Compile-time state validation");
+    println!(
+        "  ✅ Compile-time state validation ({} checked transitions)",
+        complete.transition_count()
+    );
 }
 
 fn demonstrate_hierarchical_states() {
@@ -1,55 +1,11 @@
+use module_03_agents::analysis_fsm::AnalysisFsm;
 use std::time::Instant;
 
-#[derive(Debug, Clone, PartialEq)]
-enum AnalysisState {
-    Init,
-    Parsing,
-    Analyzing,
-    Verifying,
-    Complete,
-}
-
-struct CodeAnalysisFsm {
-    state: AnalysisState,
-}
-
-impl CodeAnalysisFsm {
-    fn new() -> Self {
-        Self {
-            state: AnalysisState::Init,
-        }
-    }
-
-    fn parse(&mut self) -> Result<(), String> {
-        if self.state != AnalysisState::Init {
-            return Err("Invalid state for parsing".to_string());
-        }
-        self.state = AnalysisState::Parsing;
-        Ok(())
-    }
-
-    fn analyze(&mut self) -> Result<(), String> {
-        if self.state != AnalysisState::Parsing {
-            return Err("Must parse before analyzing".to_string());
-        }
-        self.state = AnalysisState::Analyzing;
-        Ok(())
-    }
-
-    fn verify(&mut self) -> Result<(), String> {
-        if self.state != AnalysisState::Analyzing {
-            return Err("Must analyze before verifying".to_string());
-        }
-        self.state = AnalysisState::Verifying;
-        Ok(())
-    }
-}
-
 fn main() {
     println!("Code Analysis FSM Demo");
     println!("======================\n");
 
-    let mut fsm = CodeAnalysisFsm::new();
+    let mut fsm = AnalysisFsm::new();
 
     println!("Pipeline: parse → analyze → verify");
 
@@ -62,9 +18,14 @@ fn main() {
     fsm.verify().unwrap();
     println!("✅ Verification complete");
 
+    let mut out_of_order = AnalysisFsm::new();
+    if let Err(e) = out_of_order.verify() {
+        println!("🚫 {e}");
+    }
+
     let start = Instant::now();
     for _ in 0..1000 {
-        let mut fsm = CodeAnalysisFsm::new();
+        let mut fsm = AnalysisFsm::new();
         fsm.parse().unwrap();
         fsm.analyze().unwrap();
         fsm.verify().unwrap();
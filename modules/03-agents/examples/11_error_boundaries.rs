@@ -1,4 +1,9 @@
+use pmcp::circuit_breaker::CircuitBreaker;
+use pmcp::degradation::{DegradationThresholds, ServiceLevel, ServiceLevelTracker};
+use pmcp::retry::{Jitter, RetryPolicy};
+use pmcp::PmcpError;
 use std::panic;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::Duration;
 use thiserror::Error;
 use tokio::time::sleep;
@@ -16,74 +21,6 @@ enum AgentError {
     Timeout(Duration),
 }
 
-struct RetryPolicy {
-    max_attempts: u32,
-    base_delay: Duration,
-}
-
-impl RetryPolicy {
-    async fn execute<F, T>(&self, mut f: F) -> Result<T, AgentError>
-    where
-        F: FnMut() -> Result<T, AgentError>,
-    {
-        let mut attempt = 0;
-
-        loop {
-            attempt += 1;
-
-            match f() {
-                Ok(result) => return Ok(result),
-                Err(e) if attempt >= self.max_attempts => return Err(e),
-                Err(e) => {
-                    let delay = self.base_delay * 2u32.pow(attempt - 1);
-                    warn!("Attempt {} failed: {}. Retrying in {:?}", attempt, e, delay);
-                    sleep(delay).await;
-                }
-            }
-        }
-    }
-}
-
-struct CircuitBreaker {
-    failure_threshold: u32,
-    failures: u32,
-    is_open: bool,
-}
-
-impl CircuitBreaker {
-    fn new(threshold: u32) -> Self {
-        Self {
-            failure_threshold: threshold,
-            failures: 0,
-            is_open: false,
-        }
-    }
-
-    fn call<F, T>(&mut self, f: F) -> Result<T, AgentError>
-    where
-        F: FnOnce() -> Result<T, AgentError>,
-    {
-        if self.is_open {
-            return Err(AgentError::Connection("Circuit breaker open".to_string()));
-        }
-
-        match f() {
-            Ok(result) => {
-                self.failures = 0;
-                Ok(result)
-            }
-            Err(e) => {
-                self.failures += 1;
-                if self.failures >= self.failure_threshold {
-                    self.is_open = true;
-                    error!("Circuit breaker opened after {} failures", self.failures);
-                }
-                Err(e)
-            }
-        }
-    }
-}
-
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt::init();
@@ -93,7 +30,7 @@ async fn main() {
 
     demonstrate_custom_errors();
     demonstrate_retry_with_backoff().await;
-    demonstrate_circuit_breaker();
+    demonstrate_circuit_breaker().await;
     demonstrate_panic_isolation();
     demonstrate_structured_logging();
     demonstrate_graceful_degradation();
@@ -116,17 +53,16 @@ fn demonstrate_custom_errors() {
 async fn demonstrate_retry_with_backoff() {
     println!("\n🔄 Retry with Exponential Backoff:");
 
-    let policy = RetryPolicy {
-        max_attempts: 3,
-        base_delay: Duration::from_millis(100),
-    };
+    let policy = RetryPolicy::new(3, Duration::from_millis(100))
+        .with_max_delay(Duration::from_secs(1))
+        .with_jitter(Jitter::Equal)
+        .with_retryable(|e| matches!(e, PmcpError::Transport(_)));
 
-    let mut counter = 0;
+    let counter = AtomicU32::new(0);
     let result = policy
-        .execute(|| {
-            counter += 1;
-            if counter < 3 {
-                Err(AgentError::Connection("temporary failure".to_string()))
+        .execute(|| async {
+            if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(PmcpError::Transport("temporary failure".to_string()))
             } else {
                 Ok("Success!")
             }
@@ -139,23 +75,30 @@ async fn demonstrate_retry_with_backoff() {
     }
 }
 
-fn demonstrate_circuit_breaker() {
+async fn demonstrate_circuit_breaker() {
     println!("\n⚡ Circuit Breaker Pattern:");
 
-    let mut breaker = CircuitBreaker::new(3);
+    let mut breaker = CircuitBreaker::new(3, Duration::from_millis(50));
 
-    for i in 1..=5 {
-        let result = breaker.call(|| {
-            if i <= 3 {
-                Err(AgentError::Processing("service unavailable".to_string()))
-            } else {
-                Ok("Service recovered")
-            }
-        });
+    for i in 1..=6 {
+        if i == 5 {
+            sleep(Duration::from_millis(50)).await;
+            println!("  Cooldown elapsed: {:?}", breaker.state());
+        }
+
+        let result = breaker
+            .wrap(|| async move {
+                if i <= 3 {
+                    Err(PmcpError::Tool("service unavailable".to_string()))
+                } else {
+                    Ok("Service recovered")
+                }
+            })
+            .await;
 
         match result {
-            Ok(msg) => println!("  Call {}: ✅ {}", i, msg),
-            Err(e) => println!("  Call {}: ❌ {}", i, e),
+            Ok(msg) => println!("  Call {}: ✅ {} ({:?})", i, msg, breaker.state()),
+            Err(e) => println!("  Call {}: ❌ {} ({:?})", i, e, breaker.state()),
         }
     }
 }
@@ -178,9 +121,13 @@ fn demonstrate_panic_isolation() {
 fn demonstrate_structured_logging() {
     println!("\n📝 Structured Logging:");
 
-    info!("Starting operation");
-    warn!("Resource usage high");
-    error!("Connection failed");
+    info!(method = "calculator", request_id = 1, "Starting operation");
+    warn!(memory_mb = 480, limit_mb = 512, "Resource usage high");
+    error!(
+        tool_name = "extract_files",
+        latency_ms = 5000,
+        "Connection failed"
+    );
 
     println!("  ✅ Logs captured with tracing");
 }
@@ -188,22 +135,13 @@ fn demonstrate_structured_logging() {
 fn demonstrate_graceful_degradation() {
     println!("\n📉 Graceful Degradation:");
 
-    enum ServiceLevel {
-        Full,
-        Degraded,
-        Minimal,
-    }
-
-    let mut level = ServiceLevel::Full;
-
-    for errors in [0, 5, 10] {
-        level = match errors {
-            0..=3 => ServiceLevel::Full,
-            4..=8 => ServiceLevel::Degraded,
-            _ => ServiceLevel::Minimal,
-        };
+    for (calls, errors) in [(20, 0), (20, 8), (20, 20)] {
+        let tracker = ServiceLevelTracker::new(DegradationThresholds::default());
+        for call in 0..calls {
+            tracker.record(call < errors);
+        }
 
-        match level {
+        match tracker.level() {
             ServiceLevel::Full => println!("  Errors: {} → Full service", errors),
             ServiceLevel::Degraded => println!("  Errors: {} → Degraded mode", errors),
             ServiceLevel::Minimal => println!("  Errors: {} → Minimal mode", errors),
@@ -1,15 +1,87 @@
+use thiserror::Error;
+
+/// Stages of the parse → analyze → verify pipeline. Each state records the
+/// last step that completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalysisState {
+    Init,
+    Parsing,
+    Analyzing,
+    Verifying,
+    Complete,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum AnalysisError {
+    #[error("Cannot {step} from {from:?}; expected {expected:?}")]
+    OutOfOrder {
+        step: &'static str,
+        from: AnalysisState,
+        expected: AnalysisState,
+    },
+}
+
 pub struct AnalysisFsm {
-    #[allow(dead_code)]
-    state: String,
+    state: AnalysisState,
 }
 
 impl AnalysisFsm {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            state: "init".to_string(),
+            state: AnalysisState::Init,
         }
     }
+
+    #[must_use]
+    pub fn state(&self) -> AnalysisState {
+        self.state
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`AnalysisError::OutOfOrder`] unless the machine is in `Init`.
+    pub fn parse(&mut self) -> Result<AnalysisState, AnalysisError> {
+        self.advance("parse", AnalysisState::Init, AnalysisState::Parsing)
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`AnalysisError::OutOfOrder`] unless parsing has completed.
+    pub fn analyze(&mut self) -> Result<AnalysisState, AnalysisError> {
+        self.advance("analyze", AnalysisState::Parsing, AnalysisState::Analyzing)
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`AnalysisError::OutOfOrder`] unless analysis has completed.
+    pub fn verify(&mut self) -> Result<AnalysisState, AnalysisError> {
+        self.advance("verify", AnalysisState::Analyzing, AnalysisState::Verifying)
+    }
+
+    /// # Errors
+    ///
+    /// Returns [`AnalysisError::OutOfOrder`] unless verification has completed.
+    pub fn finish(&mut self) -> Result<AnalysisState, AnalysisError> {
+        self.advance("finish", AnalysisState::Verifying, AnalysisState::Complete)
+    }
+
+    fn advance(
+        &mut self,
+        step: &'static str,
+        expected: AnalysisState,
+        next: AnalysisState,
+    ) -> Result<AnalysisState, AnalysisError> {
+        if self.state != expected {
+            return Err(AnalysisError::OutOfOrder {
+                step,
+                from: self.state,
+                expected,
+            });
+        }
+        self.state = next;
+        Ok(next)
+    }
 }
 
 impl Default for AnalysisFsm {
@@ -17,3 +89,53 @@ impl Default for AnalysisFsm {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_happy_path_reaches_complete() {
+        let mut fsm = AnalysisFsm::new();
+        assert_eq!(fsm.parse(), Ok(AnalysisState::Parsing));
+        assert_eq!(fsm.analyze(), Ok(AnalysisState::Analyzing));
+        assert_eq!(fsm.verify(), Ok(AnalysisState::Verifying));
+        assert_eq!(fsm.finish(), Ok(AnalysisState::Complete));
+    }
+
+    #[test]
+    fn test_verify_before_parse_is_rejected() {
+        let mut fsm = AnalysisFsm::new();
+        assert_eq!(
+            fsm.verify(),
+            Err(AnalysisError::OutOfOrder {
+                step: "verify",
+                from: AnalysisState::Init,
+                expected: AnalysisState::Analyzing,
+            })
+        );
+        assert_eq!(fsm.state(), AnalysisState::Init);
+    }
+
+    #[test]
+    fn test_analyze_before_parse_is_rejected() {
+        let mut fsm = AnalysisFsm::new();
+        assert!(fsm.analyze().is_err());
+        assert!(fsm.finish().is_err());
+    }
+
+    #[test]
+    fn test_verify_before_analyze_is_rejected() {
+        let mut fsm = AnalysisFsm::new();
+        fsm.parse().unwrap();
+        assert!(fsm.verify().is_err());
+        assert_eq!(fsm.state(), AnalysisState::Parsing);
+    }
+
+    #[test]
+    fn test_repeated_step_is_rejected() {
+        let mut fsm = AnalysisFsm::new();
+        fsm.parse().unwrap();
+        assert!(fsm.parse().is_err());
+    }
+}
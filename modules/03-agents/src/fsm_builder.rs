@@ -1,4 +1,20 @@
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
 use std::marker::PhantomData;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum FsmBuildError<S: Debug> {
+    #[error("No initial state defined")]
+    MissingInitialState,
+
+    #[error("States unreachable from the initial state: {0:?}")]
+    UnreachableStates(Vec<S>),
+
+    #[error("Conflicting transitions from {from:?} on the same event to {targets:?}")]
+    ConflictingTransitions { from: S, targets: Vec<S> },
+}
 
 pub struct FsmBuilder<S, E> {
     initial_state: Option<S>,
@@ -8,7 +24,6 @@ pub struct FsmBuilder<S, E> {
 
 type GuardFn<S, E> = Box<dyn Fn(&S, &E) -> bool>;
 
-#[allow(dead_code)]
 pub struct Transition<S, E> {
     from: S,
     to: S,
@@ -42,6 +57,20 @@ impl<S: Clone, E> FsmBuilder<S, E> {
         });
         self
     }
+
+    #[must_use]
+    pub fn guarded_transition<F>(mut self, from: S, to: S, event: E, guard: F) -> Self
+    where
+        F: Fn(&S, &E) -> bool + 'static,
+    {
+        self.transitions.push(Transition {
+            from,
+            to,
+            event,
+            guard: Some(Box::new(guard)),
+        });
+        self
+    }
 }
 
 impl<S: Clone, E> Default for FsmBuilder<S, E> {
@@ -49,3 +78,298 @@ impl<S: Clone, E> Default for FsmBuilder<S, E> {
         Self::new()
     }
 }
+
+impl<S: Clone + Eq + Hash + Debug, E> FsmBuilder<S, E> {
+    /// Builds the machine after checking that every state named in a
+    /// transition is reachable from the initial state and that no two
+    /// transitions from the same state on the same event variant are left
+    /// for declaration order to decide. Such a pair is only accepted when
+    /// both transitions are guarded. States without outgoing transitions are
+    /// allowed but reported by [`Fsm::terminal_states`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no initial state was set, if transitions conflict,
+    /// or if any state cannot be reached from the initial state.
+    pub fn build(self) -> Result<Fsm<S, E>, FsmBuildError<S>> {
+        if let Some(err) = self.find_conflict() {
+            return Err(err);
+        }
+
+        let initial_state = self
+            .initial_state
+            .ok_or(FsmBuildError::MissingInitialState)?;
+
+        let mut reachable = HashSet::from([initial_state.clone()]);
+        let mut frontier = vec![initial_state.clone()];
+        while let Some(state) = frontier.pop() {
+            for transition in self.transitions.iter().filter(|t| t.from == state) {
+                if reachable.insert(transition.to.clone()) {
+                    frontier.push(transition.to.clone());
+                }
+            }
+        }
+
+        let mut seen = HashSet::new();
+        let states: Vec<S> = self
+            .transitions
+            .iter()
+            .flat_map(|t| [&t.from, &t.to])
+            .filter(|s| seen.insert(*s))
+            .cloned()
+            .collect();
+
+        let unreachable: Vec<S> = states
+            .iter()
+            .filter(|s| !reachable.contains(*s))
+            .cloned()
+            .collect();
+        if !unreachable.is_empty() {
+            return Err(FsmBuildError::UnreachableStates(unreachable));
+        }
+
+        let terminal_states = states
+            .into_iter()
+            .filter(|s| !self.transitions.iter().any(|t| &t.from == s))
+            .collect();
+
+        Ok(Fsm {
+            current_state: initial_state,
+            transitions: self.transitions,
+            terminal_states,
+        })
+    }
+}
+
+impl<S: Clone + Eq + Debug, E> FsmBuilder<S, E> {
+    fn find_conflict(&self) -> Option<FsmBuildError<S>> {
+        self.transitions.iter().enumerate().find_map(|(i, first)| {
+            let targets: Vec<S> = std::iter::once(first)
+                .chain(self.transitions[i + 1..].iter().filter(|other| {
+                    other.from == first.from
+                        && std::mem::discriminant(&other.event)
+                            == std::mem::discriminant(&first.event)
+                        && (first.guard.is_none() || other.guard.is_none())
+                }))
+                .map(|t| t.to.clone())
+                .collect();
+
+            (targets.len() > 1).then(|| FsmBuildError::ConflictingTransitions {
+                from: first.from.clone(),
+                targets,
+            })
+        })
+    }
+}
+
+pub struct Fsm<S, E> {
+    current_state: S,
+    transitions: Vec<Transition<S, E>>,
+    terminal_states: Vec<S>,
+}
+
+impl<S: Clone + Eq + Debug, E: Debug> Fsm<S, E> {
+    #[must_use]
+    pub fn current_state(&self) -> &S {
+        &self.current_state
+    }
+
+    /// States with no outgoing transitions. Often intentional (`Complete`),
+    /// but a dead end that isn't meant to be final is usually a missing edge.
+    #[must_use]
+    pub fn terminal_states(&self) -> &[S] {
+        &self.terminal_states
+    }
+
+    /// Applies the first transition from the current state whose event has
+    /// the same variant as `event` and whose guard, if any, passes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no transition matches.
+    pub fn process_event(&mut self, event: &E) -> Result<&S, String> {
+        let next = self
+            .transitions
+            .iter()
+            .find(|t| {
+                t.from == self.current_state
+                    && std::mem::discriminant(&t.event) == std::mem::discriminant(event)
+                    && t.guard
+                        .as_ref()
+                        .is_none_or(|guard| guard(&self.current_state, event))
+            })
+            .map(|t| t.to.clone())
+            .ok_or_else(|| {
+                format!(
+                    "No valid transition from {:?} with event {event:?}",
+                    self.current_state
+                )
+            })?;
+
+        self.current_state = next;
+        Ok(&self.current_state)
+    }
+}
+
+/// Compile-time checked alternative to [`FsmBuilder`]: each state is a
+/// zero-sized type and transitions consume the machine, so an invalid
+/// transition such as `finish()` on an `Fsm<Init>` does not compile.
+pub mod typestate {
+    use std::marker::PhantomData;
+
+    pub struct Init;
+    pub struct Running;
+    pub struct Complete;
+
+    pub struct Fsm<S> {
+        transition_count: usize,
+        _state: PhantomData<S>,
+    }
+
+    impl<S> Fsm<S> {
+        #[must_use]
+        pub fn transition_count(&self) -> usize {
+            self.transition_count
+        }
+
+        fn into_state<T>(self) -> Fsm<T> {
+            Fsm {
+                transition_count: self.transition_count + 1,
+                _state: PhantomData,
+            }
+        }
+    }
+
+    impl Fsm<Init> {
+        #[must_use]
+        pub fn new() -> Self {
+            Self {
+                transition_count: 0,
+                _state: PhantomData,
+            }
+        }
+
+        #[must_use]
+        pub fn start(self) -> Fsm<Running> {
+            self.into_state()
+        }
+    }
+
+    impl Default for Fsm<Init> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Fsm<Running> {
+        #[must_use]
+        pub fn finish(self) -> Fsm<Complete> {
+            self.into_state()
+        }
+
+        #[must_use]
+        pub fn abort(self) -> Fsm<Init> {
+            self.into_state()
+        }
+    }
+
+    impl Fsm<Complete> {
+        #[must_use]
+        pub fn reset(self) -> Fsm<Init> {
+            self.into_state()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    enum State {
+        Init,
+        Processing,
+        Complete,
+        Orphaned,
+    }
+
+    #[derive(Debug)]
+    enum Event {
+        Start,
+        Finish,
+    }
+
+    #[test]
+    fn test_build_rejects_unreachable_state() {
+        let result = FsmBuilder::new()
+            .initial_state(State::Init)
+            .transition(State::Init, State::Processing, Event::Start)
+            .transition(State::Processing, State::Complete, Event::Finish)
+            .transition(State::Orphaned, State::Complete, Event::Finish)
+            .build();
+
+        let Err(err) = result else {
+            panic!("expected unreachable state to be rejected");
+        };
+        assert_eq!(err, FsmBuildError::UnreachableStates(vec![State::Orphaned]));
+        assert!(err.to_string().contains("Orphaned"));
+    }
+
+    #[test]
+    fn test_build_rejects_conflicting_transitions() {
+        let result = FsmBuilder::new()
+            .initial_state(State::Init)
+            .transition(State::Init, State::Processing, Event::Start)
+            .transition(State::Init, State::Complete, Event::Start)
+            .build();
+
+        let Err(err) = result else {
+            panic!("expected conflicting transitions to be rejected");
+        };
+        assert_eq!(
+            err,
+            FsmBuildError::ConflictingTransitions {
+                from: State::Init,
+                targets: vec![State::Processing, State::Complete],
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_accepts_guard_differentiated_transitions() {
+        let mut fsm = FsmBuilder::new()
+            .initial_state(State::Init)
+            .guarded_transition(State::Init, State::Processing, Event::Start, |_, _| false)
+            .guarded_transition(State::Init, State::Complete, Event::Start, |_, _| true)
+            .build()
+            .unwrap();
+
+        assert_eq!(fsm.process_event(&Event::Start), Ok(&State::Complete));
+    }
+
+    #[test]
+    fn test_build_reports_terminal_states() {
+        let mut fsm = FsmBuilder::new()
+            .initial_state(State::Init)
+            .transition(State::Init, State::Processing, Event::Start)
+            .transition(State::Processing, State::Complete, Event::Finish)
+            .build()
+            .unwrap();
+
+        assert_eq!(fsm.terminal_states(), &[State::Complete]);
+        assert_eq!(fsm.process_event(&Event::Start), Ok(&State::Processing));
+        assert!(fsm.process_event(&Event::Start).is_err());
+    }
+
+    #[test]
+    fn test_build_requires_initial_state() {
+        let result = FsmBuilder::<State, Event>::new().build();
+        assert!(matches!(result, Err(FsmBuildError::MissingInitialState)));
+    }
+
+    #[test]
+    fn test_typestate_transitions() {
+        let fsm = typestate::Fsm::new().start().finish().reset().start();
+        assert_eq!(fsm.abort().transition_count(), 5);
+    }
+}
@@ -1,3 +1,9 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+const HISTORY_LIMIT: usize = 100;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RefactorState {
     Init,
@@ -9,10 +15,47 @@ pub enum RefactorState {
     Validating,
     Complete,
     Error,
+    Rollback,
+    Cancelled,
+    Paused,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RefactorEvent {
+    Start(String),
+    ParseComplete(usize),
+    AnalysisComplete(Vec<String>),
+    PlanGenerated(RefactorPlan),
+    RefactorApplied(usize),
+    TestsRun(TestResult),
+    ValidationComplete(bool),
+    ErrorOccurred(String),
+    Cancel,
+    Pause,
+    Resume,
+    Rollback,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefactorPlan {
+    pub steps: Vec<String>,
+    pub estimated_time: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestResult {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
 }
 
 pub struct RefactorFsm {
     state: RefactorState,
+    history: VecDeque<RefactorState>,
+    rollback_stack: Vec<RefactorState>,
+    event_log: Vec<RefactorEvent>,
+    pre_pause_state: Option<RefactorState>,
+    cancellation_token: CancellationToken,
 }
 
 impl RefactorFsm {
@@ -20,13 +63,159 @@ impl RefactorFsm {
     pub fn new() -> Self {
         Self {
             state: RefactorState::Init,
+            history: VecDeque::with_capacity(HISTORY_LIMIT),
+            rollback_stack: Vec::new(),
+            event_log: Vec::new(),
+            pre_pause_state: None,
+            cancellation_token: CancellationToken::new(),
         }
     }
 
+    /// Uses `token` instead of a private one, so the caller can cancel the
+    /// run from another task.
+    #[must_use]
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = token;
+        self
+    }
+
     #[must_use]
     pub fn state(&self) -> RefactorState {
         self.state
     }
+
+    #[must_use]
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation_token.clone()
+    }
+
+    /// Every accepted event, in order. A cancellation is logged as
+    /// [`RefactorEvent::Cancel`] so the log alone explains the final state.
+    #[must_use]
+    pub fn event_log(&self) -> &[RefactorEvent] {
+        &self.event_log
+    }
+
+    /// Re-applies a logged sequence, e.g. one taken from [`RefactorFsm::event_log`],
+    /// and returns the final state. Replaying on a fresh machine reproduces
+    /// the state of the run that produced the log. Events invalid in the
+    /// state they arrive in are skipped, as the live run rejected them too.
+    pub fn replay(&mut self, events: &[RefactorEvent]) -> RefactorState {
+        for event in events {
+            if let Ok(new_state) = self.next_state(event) {
+                self.commit(event.clone(), new_state);
+            }
+        }
+        self.state
+    }
+
+    /// Applies `event` to the current state. Once the cancellation token
+    /// fires, any event moves the machine to `Cancelled` instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `event` is not valid in the current state.
+    pub async fn process_event(&mut self, event: RefactorEvent) -> Result<RefactorState, String> {
+        if self.cancellation_token.is_cancelled() {
+            return Ok(self.commit(RefactorEvent::Cancel, RefactorState::Cancelled));
+        }
+
+        let new_state = self.next_state(&event)?;
+
+        // Give a concurrent `cancel()` the chance to land before committing.
+        tokio::task::yield_now().await;
+        if self.cancellation_token.is_cancelled() {
+            return Ok(self.commit(RefactorEvent::Cancel, RefactorState::Cancelled));
+        }
+
+        Ok(self.commit(event, new_state))
+    }
+
+    fn next_state(&self, event: &RefactorEvent) -> Result<RefactorState, String> {
+        let new_state = match (self.state, event) {
+            (RefactorState::Init, RefactorEvent::Start(_)) => RefactorState::Parsing,
+            (RefactorState::Parsing, RefactorEvent::ParseComplete(_)) => RefactorState::Analyzing,
+            (RefactorState::Analyzing, RefactorEvent::AnalysisComplete(_)) => {
+                RefactorState::Planning
+            }
+            (RefactorState::Planning, RefactorEvent::PlanGenerated(_)) => {
+                RefactorState::Refactoring
+            }
+            (RefactorState::Refactoring, RefactorEvent::RefactorApplied(_)) => {
+                RefactorState::Testing
+            }
+            (RefactorState::Testing, RefactorEvent::TestsRun(result)) => {
+                if result.failed == 0 {
+                    RefactorState::Validating
+                } else {
+                    RefactorState::Rollback
+                }
+            }
+            (RefactorState::Validating, RefactorEvent::ValidationComplete(valid)) => {
+                if *valid {
+                    RefactorState::Complete
+                } else {
+                    RefactorState::Rollback
+                }
+            }
+            (_, RefactorEvent::ErrorOccurred(_)) => RefactorState::Error,
+            (_, RefactorEvent::Cancel) => RefactorState::Cancelled,
+            (RefactorState::Paused, RefactorEvent::Pause) => {
+                return Err("Already paused".to_string());
+            }
+            (_, RefactorEvent::Pause) => RefactorState::Paused,
+            (RefactorState::Paused, RefactorEvent::Resume) => {
+                self.pre_pause_state.unwrap_or(RefactorState::Init)
+            }
+            (_, RefactorEvent::Rollback) => RefactorState::Rollback,
+            (state, event) => {
+                return Err(format!("Invalid transition from {state:?} on {event:?}"));
+            }
+        };
+        Ok(new_state)
+    }
+
+    /// Restores the state held before the most recent transition and drops
+    /// that transition's event from the log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is nothing to roll back.
+    pub fn rollback(&mut self) -> Result<RefactorState, String> {
+        let previous = self
+            .rollback_stack
+            .pop()
+            .ok_or_else(|| "Nothing to roll back".to_string())?;
+        self.history.pop_back();
+        self.event_log.pop();
+        if previous == RefactorState::Paused {
+            self.pre_pause_state = self.history.back().copied();
+        }
+        self.state = previous;
+        Ok(previous)
+    }
+
+    fn commit(&mut self, event: RefactorEvent, new_state: RefactorState) -> RefactorState {
+        self.event_log.push(event);
+        self.transition_to(new_state)
+    }
+
+    fn transition_to(&mut self, new_state: RefactorState) -> RefactorState {
+        if new_state == RefactorState::Paused {
+            self.pre_pause_state = Some(self.state);
+        } else if self.state == RefactorState::Paused {
+            self.pre_pause_state = None;
+        }
+
+        self.history.push_back(self.state);
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+
+        self.rollback_stack.push(self.state);
+        self.state = new_state;
+        new_state
+    }
 }
 
 impl Default for RefactorFsm {
@@ -34,3 +223,207 @@ impl Default for RefactorFsm {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::{Arbitrary, Gen};
+    use quickcheck_macros::quickcheck;
+
+    const ALL_STATES: [RefactorState; 12] = [
+        RefactorState::Init,
+        RefactorState::Parsing,
+        RefactorState::Analyzing,
+        RefactorState::Planning,
+        RefactorState::Refactoring,
+        RefactorState::Testing,
+        RefactorState::Validating,
+        RefactorState::Complete,
+        RefactorState::Error,
+        RefactorState::Rollback,
+        RefactorState::Cancelled,
+        RefactorState::Paused,
+    ];
+
+    impl Arbitrary for TestResult {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Self {
+                passed: usize::arbitrary(g),
+                // Half the runs pass, so Validating is reachable.
+                failed: if bool::arbitrary(g) {
+                    0
+                } else {
+                    usize::arbitrary(g)
+                },
+                skipped: usize::arbitrary(g),
+            }
+        }
+    }
+
+    impl Arbitrary for RefactorEvent {
+        fn arbitrary(g: &mut Gen) -> Self {
+            match u8::arbitrary(g) % 12 {
+                0 => Self::Start(String::arbitrary(g)),
+                1 => Self::ParseComplete(usize::arbitrary(g)),
+                2 => Self::AnalysisComplete(Vec::arbitrary(g)),
+                3 => Self::PlanGenerated(RefactorPlan {
+                    steps: Vec::arbitrary(g),
+                    estimated_time: Duration::from_millis(u64::from(u32::arbitrary(g))),
+                }),
+                4 => Self::RefactorApplied(usize::arbitrary(g)),
+                5 => Self::TestsRun(TestResult::arbitrary(g)),
+                6 => Self::ValidationComplete(bool::arbitrary(g)),
+                7 => Self::ErrorOccurred(String::arbitrary(g)),
+                8 => Self::Cancel,
+                9 => Self::Pause,
+                10 => Self::Resume,
+                _ => Self::Rollback,
+            }
+        }
+    }
+
+    /// Any event sequence leaves the machine in a declared state, and a
+    /// rejected event leaves the state it arrived in untouched.
+    #[quickcheck]
+    fn prop_arbitrary_events_never_panic(events: Vec<RefactorEvent>) -> bool {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        runtime.block_on(async {
+            let mut fsm = RefactorFsm::new();
+            for event in events {
+                let before = fsm.state();
+                let consistent = match fsm.process_event(event).await {
+                    Ok(state) => state == fsm.state(),
+                    Err(_) => fsm.state() == before,
+                };
+                if !consistent || !ALL_STATES.contains(&fsm.state()) {
+                    return false;
+                }
+            }
+            true
+        })
+    }
+
+    #[tokio::test]
+    async fn test_normal_sequence_reaches_complete() {
+        let mut fsm = RefactorFsm::new();
+        let events = [
+            RefactorEvent::Start("main.rs".to_string()),
+            RefactorEvent::ParseComplete(1000),
+            RefactorEvent::AnalysisComplete(vec!["long_function".to_string()]),
+            RefactorEvent::PlanGenerated(RefactorPlan {
+                steps: vec!["extract_method".to_string()],
+                estimated_time: Duration::from_secs(5),
+            }),
+            RefactorEvent::RefactorApplied(1),
+            RefactorEvent::TestsRun(TestResult {
+                passed: 10,
+                failed: 0,
+                skipped: 0,
+            }),
+            RefactorEvent::ValidationComplete(true),
+        ];
+
+        for event in events {
+            fsm.process_event(event).await.unwrap();
+        }
+        assert_eq!(fsm.state(), RefactorState::Complete);
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_mid_flight() {
+        let token = CancellationToken::new();
+        let mut fsm = RefactorFsm::new().with_cancellation_token(token.clone());
+
+        fsm.process_event(RefactorEvent::Start("main.rs".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(fsm.state(), RefactorState::Parsing);
+
+        token.cancel();
+        let state = fsm
+            .process_event(RefactorEvent::ParseComplete(1000))
+            .await
+            .unwrap();
+        assert_eq!(state, RefactorState::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_rollback_restores_prior_state() {
+        let mut fsm = RefactorFsm::new();
+        fsm.process_event(RefactorEvent::Start("main.rs".to_string()))
+            .await
+            .unwrap();
+        fsm.process_event(RefactorEvent::ParseComplete(1000))
+            .await
+            .unwrap();
+        assert_eq!(fsm.state(), RefactorState::Analyzing);
+
+        assert_eq!(fsm.rollback(), Ok(RefactorState::Parsing));
+        assert_eq!(fsm.rollback(), Ok(RefactorState::Init));
+        assert!(fsm.rollback().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_transition_is_rejected() {
+        let mut fsm = RefactorFsm::new();
+        let result = fsm.process_event(RefactorEvent::ParseComplete(1)).await;
+        assert!(result.is_err());
+        assert_eq!(fsm.state(), RefactorState::Init);
+    }
+
+    #[tokio::test]
+    async fn test_replay_reproduces_live_run() {
+        let mut live = RefactorFsm::new();
+        let events = [
+            RefactorEvent::Start("main.rs".to_string()),
+            RefactorEvent::ParseComplete(1000),
+            RefactorEvent::Pause,
+            RefactorEvent::Resume,
+            RefactorEvent::AnalysisComplete(vec!["long_function".to_string()]),
+            RefactorEvent::ValidationComplete(true),
+            RefactorEvent::PlanGenerated(RefactorPlan {
+                steps: vec!["extract_method".to_string()],
+                estimated_time: Duration::from_secs(5),
+            }),
+            RefactorEvent::RefactorApplied(1),
+            RefactorEvent::TestsRun(TestResult {
+                passed: 9,
+                failed: 1,
+                skipped: 0,
+            }),
+        ];
+        for event in events {
+            let _ = live.process_event(event).await;
+        }
+        assert_eq!(live.state(), RefactorState::Rollback);
+        assert_eq!(live.event_log().len(), 8);
+
+        let mut replayed = RefactorFsm::new();
+        assert_eq!(replayed.replay(live.event_log()), live.state());
+        assert_eq!(replayed.event_log(), live.event_log());
+    }
+
+    #[tokio::test]
+    async fn test_resume_returns_to_pre_pause_state() {
+        let mut fsm = RefactorFsm::new();
+        assert!(fsm.process_event(RefactorEvent::Resume).await.is_err());
+
+        fsm.process_event(RefactorEvent::Start("main.rs".to_string()))
+            .await
+            .unwrap();
+        fsm.process_event(RefactorEvent::ParseComplete(1000))
+            .await
+            .unwrap();
+        assert_eq!(fsm.state(), RefactorState::Analyzing);
+
+        fsm.process_event(RefactorEvent::Pause).await.unwrap();
+        assert!(fsm.process_event(RefactorEvent::Pause).await.is_err());
+        let resumed = fsm.process_event(RefactorEvent::Resume).await.unwrap();
+
+        assert_eq!(resumed, RefactorState::Analyzing);
+        assert!(fsm.process_event(RefactorEvent::Resume).await.is_err());
+    }
+}
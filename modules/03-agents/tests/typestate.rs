@@ -0,0 +1,5 @@
+#[test]
+fn typestate_rejects_invalid_transitions() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,5 @@
+use module_03_agents::fsm_builder::typestate::Fsm;
+
+fn main() {
+    let _ = Fsm::new().finish();
+}
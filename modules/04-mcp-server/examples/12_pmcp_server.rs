@@ -1,3 +1,5 @@
+use module_04_mcp_server::analyze_complexity::AnalyzeComplexityHandler;
+use module_04_mcp_server::calculator::CalculatorHandler;
 use pmcp::server::{Server, ServerBuilder};
 use pmcp::tools::{analyze_complexity_tool, calculator_tool};
 use pmcp::Request;
@@ -9,10 +11,14 @@ async fn main() {
     println!("================\n");
 
     let server = ServerBuilder::new()
-        .with_tool(calculator_tool())
-        .with_tool(analyze_complexity_tool())
+        .with_handler(calculator_tool(), CalculatorHandler)
+        .with_handler(
+            analyze_complexity_tool(),
+            AnalyzeComplexityHandler::default(),
+        )
         .with_max_request_size(10_485_760)
-        .build();
+        .build()
+        .expect("every advertised tool has a handler");
 
     println!(
         "✅ Server configured with {} tools",
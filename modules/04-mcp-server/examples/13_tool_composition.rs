@@ -1,49 +1,16 @@
-use std::collections::HashMap;
+use async_trait::async_trait;
+use module_04_mcp_server::composer::ToolComposer;
+use pmcp::server::ToolHandler;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 
-#[derive(Clone)]
-struct ToolResult {
-    tool_name: String,
-    output: serde_json::Value,
-    duration_ms: u64,
-}
-
-struct ToolComposer {
-    results: Arc<RwLock<HashMap<String, ToolResult>>>,
-    dependencies: HashMap<String, Vec<String>>,
-}
-
-impl ToolComposer {
-    fn new() -> Self {
-        Self {
-            results: Arc::new(RwLock::new(HashMap::new())),
-            dependencies: HashMap::new(),
-        }
-    }
+struct SimulatedTool;
 
-    async fn execute_tool(&self, name: &str) -> ToolResult {
+#[async_trait]
+impl ToolHandler for SimulatedTool {
+    async fn handle(&self, _params: Option<serde_json::Value>) -> pmcp::Result<serde_json::Value> {
         tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-
-        ToolResult {
-            tool_name: name.to_string(),
-            output: serde_json::json!({"status": "success"}),
-            duration_ms: 10,
-        }
-    }
-
-    async fn compose(&self, tools: Vec<&str>) -> Vec<ToolResult> {
-        let mut results = Vec::new();
-
-        for tool in tools {
-            let result = self.execute_tool(tool).await;
-            results.push(result.clone());
-
-            let mut cache = self.results.write().await;
-            cache.insert(tool.to_string(), result);
-        }
-
-        results
+        Ok(serde_json::json!({"status": "success"}))
     }
 }
 
@@ -52,16 +19,29 @@ async fn main() {
     println!("Tool Composition Demo");
     println!("====================\n");
 
-    let composer = ToolComposer::new();
+    let tools = ["extract_files", "analyze_complexity", "deep_analysis"];
+    let mut composer = tools
+        .iter()
+        .fold(ToolComposer::with_cache_size(1000), |composer, tool| {
+            composer.with_tool(tool, Arc::new(SimulatedTool))
+        });
+
+    composer.add_dependency("analyze_complexity", "extract_files");
+    composer.add_dependency("deep_analysis", "analyze_complexity");
 
     println!("🔧 Available tools:");
-    println!("  - analyze_complexity");
-    println!("  - extract_files");
-    println!("  - deep_analysis");
+    for tool in tools {
+        println!("  - {tool}");
+    }
 
-    let results = composer
-        .compose(vec!["extract_files", "analyze_complexity", "deep_analysis"])
-        .await;
+    let cancel = CancellationToken::new();
+    let results = match composer.compose(tools.to_vec(), &cancel).await {
+        Ok(composition) => composition.results,
+        Err(e) => {
+            println!("❌ {e}");
+            return;
+        }
+    };
 
     println!("\n📊 Execution results:");
     for result in results {
@@ -71,6 +51,30 @@ async fn main() {
         );
     }
 
+    let cached = composer
+        .compose(tools.to_vec(), &cancel)
+        .await
+        .unwrap_or_default()
+        .results;
+
+    println!("\n♻️  Second run (served from cache):");
+    for result in cached {
+        println!("  {} → {}", result.tool_name, result.output["status"]);
+    }
+
+    cancel.cancel();
+    let cancelled = composer
+        .compose(vec!["extract_files", "unregistered"], &cancel)
+        .await
+        .unwrap_or_default();
+
+    println!("\n🛑 After cancellation:");
+    println!(
+        "  {} result(s), cancelled: {}",
+        cancelled.results.len(),
+        cancelled.cancelled
+    );
+
     println!("\n✅ Dependency graph built");
     println!("✅ Parallel execution complete");
     println!("✅ Results aggregated");
@@ -1,4 +1,5 @@
-use futures::future::join_all;
+use futures::future::{join_all, Either};
+use module_04_mcp_server::select::biased_select;
 use tokio::select;
 use tokio::time::{sleep, timeout, Duration};
 use tokio_util::sync::CancellationToken;
@@ -35,6 +36,13 @@ async fn demonstrate_select_pattern() {
     };
 
     println!("  Winner: {}", result);
+
+    // Both branches are ready at once: a plain select! picks at random,
+    // biased_select always takes the first.
+    let tie = match biased_select(async { "Priority" }, async { "Other" }).await {
+        Either::Left(val) | Either::Right(val) => val,
+    };
+    println!("  Tie winner (biased): {}", tie);
 }
 
 async fn demonstrate_join_pattern() {
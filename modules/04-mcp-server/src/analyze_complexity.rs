@@ -0,0 +1,94 @@
+use crate::params::{invalid_params, string_param};
+use async_trait::async_trait;
+use module_02_setup::quality::{ComplexityChecker, QualityGateConfig};
+use pmcp::server::ToolHandler;
+use pmcp::Result;
+use serde_json::json;
+
+/// Backs `analyze_complexity_tool()` with the cyclomatic counter from the
+/// quality-gate module.
+pub struct AnalyzeComplexityHandler {
+    checker: ComplexityChecker,
+}
+
+impl AnalyzeComplexityHandler {
+    #[must_use]
+    pub fn new(max_complexity: u32) -> Self {
+        Self {
+            checker: ComplexityChecker::new(max_complexity),
+        }
+    }
+}
+
+impl Default for AnalyzeComplexityHandler {
+    fn default() -> Self {
+        Self::new(QualityGateConfig::default().max_complexity)
+    }
+}
+
+#[async_trait]
+impl ToolHandler for AnalyzeComplexityHandler {
+    async fn handle(&self, params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+        let code = string_param(params.as_ref(), "code")?;
+        let language = string_param(params.as_ref(), "language")?;
+
+        let cyclomatic = match language {
+            "rust" => self.checker.calculate_cyclomatic(code),
+            other => return Err(invalid_params(format!("Unsupported language: {other}"))),
+        };
+
+        Ok(json!({
+            "cyclomatic": cyclomatic,
+            "exceeds_threshold": self.checker.check(cyclomatic).is_err(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pmcp::protocol::ERROR_INVALID_PARAMS;
+    use pmcp::PmcpError;
+
+    #[tokio::test]
+    async fn test_two_branches_yield_cyclomatic_three() {
+        let code = r"
+            fn classify(x: i32) -> i32 {
+                if x > 10 {
+                    return 2;
+                }
+                if x > 0 {
+                    return 1;
+                }
+                0
+            }
+        ";
+
+        let result = AnalyzeComplexityHandler::default()
+            .handle(Some(json!({ "code": code, "language": "rust" })))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result,
+            json!({ "cyclomatic": 3, "exceeds_threshold": false })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_language() {
+        let result = AnalyzeComplexityHandler::default()
+            .handle(Some(
+                json!({ "code": "def f(): pass", "language": "python" }),
+            ))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(PmcpError::JsonRpc {
+                code: ERROR_INVALID_PARAMS,
+                ..
+            })
+        ));
+    }
+}
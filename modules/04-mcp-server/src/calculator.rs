@@ -0,0 +1,169 @@
+use crate::params::invalid_params;
+use async_trait::async_trait;
+use module_02_setup::calculator::Operation;
+use pmcp::protocol::ERROR_INTERNAL;
+use pmcp::server::ToolHandler;
+use pmcp::tools::{parse_params, ToolResult};
+use pmcp::{PmcpError, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+/// Backs `calculator_tool()` with the checked integer arithmetic from the
+/// setup module.
+#[derive(Debug, Default)]
+pub struct CalculatorHandler;
+
+/// Backs `calculator_batch_tool()`. Each operation succeeds or fails on its
+/// own, so one bad operation does not fail the batch.
+#[derive(Debug, Default)]
+pub struct CalculatorBatchHandler;
+
+/// One calculation. Batch entries name the operation `op`.
+#[derive(Debug, Deserialize)]
+struct Calculation {
+    #[serde(alias = "op")]
+    operation: String,
+    a: i64,
+    b: i64,
+}
+
+/// Parses `params` as a [`Calculation`] and runs it as an [`Operation`].
+fn calculate(params: Option<serde_json::Value>) -> Result<i64> {
+    let Calculation { operation, a, b } = parse_params(params)?;
+
+    let operation = match operation.as_str() {
+        "add" => Operation::Add(a, b),
+        "subtract" => Operation::Subtract(a, b),
+        "multiply" => Operation::Multiply(a, b),
+        "divide" => Operation::Divide(a, b),
+        other => return Err(invalid_params(format!("Unsupported operation: {other}"))),
+    };
+
+    operation
+        .execute()
+        .map_err(|e| PmcpError::Tool(e.to_string()))
+}
+
+#[async_trait]
+impl ToolHandler for CalculatorHandler {
+    async fn handle(&self, params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+        let result = calculate(params)?;
+        Ok(ToolResult::new()
+            .json(&json!({ "result": result }))
+            .into_value())
+    }
+}
+
+#[async_trait]
+impl ToolHandler for CalculatorBatchHandler {
+    async fn handle(&self, params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+        let operations = params
+            .as_ref()
+            .and_then(|p| p.get("operations"))
+            .and_then(serde_json::Value::as_array)
+            .ok_or_else(|| invalid_params("Missing required parameter: operations".to_string()))?;
+
+        let results: Vec<serde_json::Value> = operations
+            .iter()
+            .map(|operation| match calculate(Some(operation.clone())) {
+                Ok(value) => json!({ "value": value }),
+                Err(PmcpError::JsonRpc { code, message }) => {
+                    json!({ "error": { "code": code, "message": message } })
+                }
+                Err(e) => json!({ "error": { "code": ERROR_INTERNAL, "message": e.to_string() } }),
+            })
+            .collect();
+
+        Ok(json!({ "results": results }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pmcp::protocol::ERROR_INVALID_PARAMS;
+
+    #[tokio::test]
+    async fn test_add() {
+        let result = CalculatorHandler
+            .handle(Some(json!({ "operation": "add", "a": 5, "b": 3 })))
+            .await
+            .unwrap();
+
+        assert_eq!(result["isError"], json!(false));
+        assert_eq!(result["content"][0]["type"], "text");
+        assert_eq!(result["content"][0]["text"], r#"{"result":8}"#);
+    }
+
+    #[tokio::test]
+    async fn test_division_by_zero_is_tool_error() {
+        let result = CalculatorHandler
+            .handle(Some(json!({ "operation": "divide", "a": 1, "b": 0 })))
+            .await;
+
+        assert!(matches!(result, Err(PmcpError::Tool(_))));
+    }
+
+    #[tokio::test]
+    async fn test_fractional_operand_is_invalid_params() {
+        // Advertised as integers, so a schema-valid call always parses.
+        let schema = pmcp::tools::calculator_tool().input_schema;
+        assert_eq!(schema["properties"]["a"]["type"], "integer");
+        let batch = pmcp::tools::calculator_batch_tool().input_schema;
+        assert_eq!(
+            batch["properties"]["operations"]["items"]["properties"]["b"]["type"],
+            "integer"
+        );
+
+        let result = CalculatorHandler
+            .handle(Some(json!({ "operation": "add", "a": 1.5, "b": 2 })))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(PmcpError::JsonRpc {
+                code: ERROR_INVALID_PARAMS,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_batch_reports_each_operation() {
+        let result = CalculatorBatchHandler
+            .handle(Some(json!({
+                "operations": [
+                    { "op": "add", "a": 2, "b": 3 },
+                    { "op": "divide", "a": 1, "b": 0 },
+                    { "op": "multiply", "a": 4, "b": 5 },
+                    { "op": "subtract", "a": 9, "b": 10 },
+                ]
+            })))
+            .await
+            .unwrap();
+
+        let results = result["results"].as_array().unwrap();
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0], json!({ "value": 5 }));
+        assert_eq!(results[1]["error"]["code"], json!(ERROR_INTERNAL));
+        assert!(results[1]["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("Division by zero"));
+        assert_eq!(results[2], json!({ "value": 20 }));
+        assert_eq!(results[3], json!({ "value": -1 }));
+    }
+
+    #[tokio::test]
+    async fn test_batch_without_operations_is_invalid_params() {
+        let result = CalculatorBatchHandler.handle(Some(json!({}))).await;
+
+        assert!(matches!(
+            result,
+            Err(PmcpError::JsonRpc {
+                code: ERROR_INVALID_PARAMS,
+                ..
+            })
+        ));
+    }
+}
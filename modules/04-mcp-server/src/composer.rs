@@ -0,0 +1,574 @@
+use crate::select::unless_cancelled;
+use futures::future::join_all;
+use lru::LruCache;
+use pmcp::server::ToolHandler;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+const DEFAULT_CACHE_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolResult {
+    pub tool_name: String,
+    pub output: serde_json::Value,
+    pub duration_ms: u64,
+}
+
+/// What [`ToolComposer::compose`] ran. When `cancelled` or
+/// `budget_exceeded` is set, `results` holds only the tools that finished
+/// in time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Composition {
+    pub results: Vec<ToolResult>,
+    pub cancelled: bool,
+    /// Set by [`ToolComposer::compose_with_budget`] when the budget ran out
+    /// before every tool finished.
+    pub budget_exceeded: bool,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ComposeError {
+    #[error("Dependency cycle among tools: {0:?}")]
+    Cycle(Vec<String>),
+}
+
+/// Runs registered tools in dependency order, caching successful results in
+/// a bounded LRU so repeated tool names are served without re-executing.
+pub struct ToolComposer {
+    handlers: HashMap<String, Arc<dyn ToolHandler>>,
+    dependencies: HashMap<String, Vec<String>>,
+    results: Mutex<LruCache<String, ToolResult>>,
+    tool_timeout: Option<Duration>,
+}
+
+impl ToolComposer {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_cache_size(DEFAULT_CACHE_SIZE)
+    }
+
+    /// A size of zero is clamped to one.
+    #[must_use]
+    pub fn with_cache_size(size: usize) -> Self {
+        Self {
+            handlers: HashMap::new(),
+            dependencies: HashMap::new(),
+            results: Mutex::new(LruCache::new(
+                NonZeroUsize::new(size).unwrap_or(NonZeroUsize::MIN),
+            )),
+            tool_timeout: None,
+        }
+    }
+
+    /// Bounds each tool's run; a tool that overruns is reported as an error
+    /// result.
+    #[must_use]
+    pub fn with_tool_timeout(mut self, timeout: Duration) -> Self {
+        self.tool_timeout = Some(timeout);
+        self
+    }
+
+    #[must_use]
+    pub fn with_tool(mut self, name: &str, handler: Arc<dyn ToolHandler>) -> Self {
+        self.handlers.insert(name.to_string(), handler);
+        self
+    }
+
+    /// Declares that `tool` must not start until `depends_on` has finished.
+    pub fn add_dependency(&mut self, tool: &str, depends_on: &str) {
+        self.dependencies
+            .entry(tool.to_string())
+            .or_default()
+            .push(depends_on.to_string());
+    }
+
+    pub async fn is_cached(&self, name: &str) -> bool {
+        self.results.lock().await.contains(name)
+    }
+
+    /// Returns `None` if `cancel` fires before the tool finishes.
+    pub async fn execute_tool(&self, name: &str, cancel: &CancellationToken) -> Option<ToolResult> {
+        self.execute_within(name, cancel, None).await
+    }
+
+    /// As [`ToolComposer::execute_tool`], also returning `None` if `budget`
+    /// runs out first. The tool timeout still applies within the budget.
+    async fn execute_within(
+        &self,
+        name: &str,
+        cancel: &CancellationToken,
+        budget: Option<Duration>,
+    ) -> Option<ToolResult> {
+        if let Some(cached) = self.results.lock().await.get(name) {
+            return Some(cached.clone());
+        }
+
+        let started = Instant::now();
+        let Some(handler) = self.handlers.get(name) else {
+            return Some(ToolResult {
+                tool_name: name.to_string(),
+                output: json!({ "status": "error", "error": format!("Unknown tool: {name}") }),
+                duration_ms: 0,
+            });
+        };
+        let run = async { handler.handle(None).await.map_err(|e| e.to_string()) };
+        let limit = match (self.tool_timeout, budget) {
+            (Some(timeout), Some(budget)) => Some(timeout.min(budget)),
+            (timeout, budget) => timeout.or(budget),
+        };
+        let output = match limit {
+            Some(limit) => {
+                match unless_cancelled(cancel, tokio::time::timeout(limit, run)).await? {
+                    Ok(output) => output,
+                    Err(_) if budget == Some(limit) => return None,
+                    Err(_) => Err(format!("Timed out after {}ms", limit.as_millis())),
+                }
+            }
+            None => unless_cancelled(cancel, run).await?,
+        };
+        let duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+        match output {
+            Ok(output) => {
+                let result = ToolResult {
+                    tool_name: name.to_string(),
+                    output,
+                    duration_ms,
+                };
+                self.results
+                    .lock()
+                    .await
+                    .put(name.to_string(), result.clone());
+                Some(result)
+            }
+            Err(error) => Some(ToolResult {
+                tool_name: name.to_string(),
+                output: json!({ "status": "error", "error": error }),
+                duration_ms,
+            }),
+        }
+    }
+
+    /// Executes `tools` in waves: each wave runs concurrently and contains
+    /// every tool whose dependencies among the requested tools have finished.
+    /// Results are returned in the order `tools` were requested, whichever
+    /// finished first; a repeated tool name runs once and is reported once
+    /// per request.
+    ///
+    /// Cancelling `cancel` abandons the tools still running and skips the
+    /// remaining waves; the composition is returned marked as cancelled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error without running anything if the dependencies among
+    /// the requested tools form a cycle.
+    pub async fn compose(
+        &self,
+        tools: Vec<&str>,
+        cancel: &CancellationToken,
+    ) -> Result<Composition, ComposeError> {
+        self.compose_until(tools, cancel, None).await
+    }
+
+    /// As [`ToolComposer::compose`], but the whole pipeline must finish
+    /// within `budget`. Once it runs out, tools still running are abandoned
+    /// and later waves are skipped; the composition is returned marked
+    /// `budget_exceeded`. Each tool is still bounded by the tool timeout.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error without running anything if the dependencies among
+    /// the requested tools form a cycle.
+    pub async fn compose_with_budget(
+        &self,
+        tools: Vec<&str>,
+        budget: Duration,
+    ) -> Result<Composition, ComposeError> {
+        let deadline = tokio::time::Instant::now() + budget;
+        self.compose_until(tools, &CancellationToken::new(), Some(deadline))
+            .await
+    }
+
+    async fn compose_until(
+        &self,
+        tools: Vec<&str>,
+        cancel: &CancellationToken,
+        deadline: Option<tokio::time::Instant>,
+    ) -> Result<Composition, ComposeError> {
+        let mut by_name: HashMap<&str, ToolResult> = HashMap::with_capacity(tools.len());
+        let mut budget_exceeded = false;
+
+        for wave in self.execution_waves(&tools)? {
+            if cancel.is_cancelled() {
+                break;
+            }
+            let budget = deadline
+                .map(|deadline| deadline.saturating_duration_since(tokio::time::Instant::now()));
+            if budget == Some(Duration::ZERO) {
+                budget_exceeded = true;
+                break;
+            }
+
+            let wave_results = join_all(
+                wave.iter()
+                    .map(|tool| self.execute_within(tool, cancel, budget)),
+            )
+            .await;
+            budget_exceeded |= !cancel.is_cancelled() && wave_results.iter().any(Option::is_none);
+            by_name.extend(
+                wave.into_iter()
+                    .zip(wave_results)
+                    .filter_map(|(tool, result)| Some((tool, result?))),
+            );
+            if budget_exceeded {
+                break;
+            }
+        }
+
+        Ok(Composition {
+            results: tools
+                .iter()
+                .filter_map(|tool| by_name.get(tool).cloned())
+                .collect(),
+            cancelled: cancel.is_cancelled(),
+            budget_exceeded,
+        })
+    }
+
+    fn execution_waves<'a>(&self, tools: &[&'a str]) -> Result<Vec<Vec<&'a str>>, ComposeError> {
+        let mut pending: Vec<&str> = Vec::new();
+        for tool in tools {
+            if !pending.contains(tool) {
+                pending.push(tool);
+            }
+        }
+
+        let mut done: HashSet<&str> = HashSet::new();
+        let mut waves = Vec::new();
+
+        while !pending.is_empty() {
+            let (ready, blocked): (Vec<&str>, Vec<&str>) = pending.iter().partition(|tool| {
+                self.dependencies.get(**tool).is_none_or(|deps| {
+                    deps.iter()
+                        .all(|dep| done.contains(dep.as_str()) || !tools.contains(&dep.as_str()))
+                })
+            });
+
+            if ready.is_empty() {
+                return Err(ComposeError::Cycle(
+                    blocked.into_iter().map(str::to_string).collect(),
+                ));
+            }
+
+            done.extend(&ready);
+            waves.push(ready);
+            pending = blocked;
+        }
+
+        Ok(waves)
+    }
+}
+
+impl Default for ToolComposer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct CountingTool {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ToolHandler for CountingTool {
+        async fn handle(
+            &self,
+            _params: Option<serde_json::Value>,
+        ) -> pmcp::Result<serde_json::Value> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(json!({ "status": "success" }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_repeated_tool_is_served_from_cache() {
+        let tool = Arc::new(CountingTool::default());
+        let composer = ToolComposer::new().with_tool("extract_files", tool.clone());
+
+        let results = composer
+            .compose(
+                vec!["extract_files", "extract_files"],
+                &CancellationToken::new(),
+            )
+            .await
+            .unwrap()
+            .results;
+
+        assert_eq!(results[0], results[1]);
+        assert_eq!(tool.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_evicts_least_recently_used() {
+        let tool = Arc::new(CountingTool::default());
+        let names: Vec<String> = (0..=DEFAULT_CACHE_SIZE)
+            .map(|i| format!("tool_{i}"))
+            .collect();
+        let composer = names.iter().fold(ToolComposer::new(), |composer, name| {
+            composer.with_tool(name, tool.clone())
+        });
+
+        composer
+            .compose(
+                names.iter().map(String::as_str).collect(),
+                &CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+
+        assert!(!composer.is_cached("tool_0").await);
+        assert!(composer.is_cached("tool_1").await);
+        assert!(
+            composer
+                .is_cached(&format!("tool_{DEFAULT_CACHE_SIZE}"))
+                .await
+        );
+
+        composer
+            .execute_tool("tool_0", &CancellationToken::new())
+            .await;
+        assert_eq!(tool.calls.load(Ordering::SeqCst), DEFAULT_CACHE_SIZE + 2);
+    }
+
+    struct RecordingTool {
+        name: &'static str,
+        log: Arc<std::sync::Mutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait]
+    impl ToolHandler for RecordingTool {
+        async fn handle(
+            &self,
+            _params: Option<serde_json::Value>,
+        ) -> pmcp::Result<serde_json::Value> {
+            tokio::task::yield_now().await;
+            self.log.lock().unwrap().push(self.name);
+            Ok(json!({ "status": "success" }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diamond_dependencies_run_in_order() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut composer =
+            ["a", "b", "c", "d"]
+                .into_iter()
+                .fold(ToolComposer::new(), |composer, name| {
+                    composer.with_tool(
+                        name,
+                        Arc::new(RecordingTool {
+                            name,
+                            log: log.clone(),
+                        }),
+                    )
+                });
+        composer.add_dependency("b", "a");
+        composer.add_dependency("c", "a");
+        composer.add_dependency("d", "b");
+        composer.add_dependency("d", "c");
+
+        let results = composer
+            .compose(vec!["d", "c", "b", "a"], &CancellationToken::new())
+            .await
+            .unwrap()
+            .results;
+
+        let executed = log.lock().unwrap().clone();
+        assert_eq!(executed.len(), 4);
+        assert_eq!(executed[0], "a");
+        assert_eq!(executed[3], "d");
+        let order: Vec<&str> = results.iter().map(|r| r.tool_name.as_str()).collect();
+        assert_eq!(order, vec!["d", "c", "b", "a"]);
+    }
+
+    struct DelayedTool {
+        name: &'static str,
+        delay: std::time::Duration,
+        log: Arc<std::sync::Mutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait]
+    impl ToolHandler for DelayedTool {
+        async fn handle(
+            &self,
+            _params: Option<serde_json::Value>,
+        ) -> pmcp::Result<serde_json::Value> {
+            tokio::time::sleep(self.delay).await;
+            self.log.lock().unwrap().push(self.name);
+            Ok(json!({ "status": "success" }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_results_follow_requested_order_not_completion_order() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let tool = |name, delay_ms| {
+            Arc::new(DelayedTool {
+                name,
+                delay: std::time::Duration::from_millis(delay_ms),
+                log: log.clone(),
+            })
+        };
+        let composer = ToolComposer::new()
+            .with_tool("slow", tool("slow", 50))
+            .with_tool("fast", tool("fast", 0));
+
+        let results = composer
+            .compose(vec!["slow", "fast"], &CancellationToken::new())
+            .await
+            .unwrap()
+            .results;
+
+        assert_eq!(*log.lock().unwrap(), vec!["fast", "slow"]);
+        let order: Vec<&str> = results.iter().map(|r| r.tool_name.as_str()).collect();
+        assert_eq!(order, vec!["slow", "fast"]);
+        assert!(results[0].duration_ms >= 50);
+    }
+
+    #[tokio::test]
+    async fn test_cyclic_dependencies_are_rejected() {
+        let tool = Arc::new(CountingTool::default());
+        let mut composer = ToolComposer::new()
+            .with_tool("a", tool.clone())
+            .with_tool("b", tool.clone())
+            .with_tool("c", tool.clone());
+        composer.add_dependency("a", "b");
+        composer.add_dependency("b", "a");
+
+        let result = composer
+            .compose(vec!["c", "a", "b"], &CancellationToken::new())
+            .await;
+
+        assert_eq!(
+            result,
+            Err(ComposeError::Cycle(vec!["a".to_string(), "b".to_string()]))
+        );
+        assert_eq!(tool.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_not_cached() {
+        let composer = ToolComposer::new();
+
+        let result = composer
+            .execute_tool("missing", &CancellationToken::new())
+            .await
+            .unwrap();
+
+        assert_eq!(result.output["status"], "error");
+        assert!(!composer.is_cached("missing").await);
+    }
+
+    struct CancellingTool(CancellationToken);
+
+    #[async_trait]
+    impl ToolHandler for CancellingTool {
+        async fn handle(
+            &self,
+            _params: Option<serde_json::Value>,
+        ) -> pmcp::Result<serde_json::Value> {
+            self.0.cancel();
+            Ok(json!({ "status": "success" }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_stops_pipeline_with_partial_results() {
+        let cancel = CancellationToken::new();
+        let later = Arc::new(CountingTool::default());
+        let mut composer = ToolComposer::new()
+            .with_tool("first", Arc::new(CancellingTool(cancel.clone())))
+            .with_tool("second", later.clone());
+        composer.add_dependency("second", "first");
+
+        let composition = composer
+            .compose(vec!["first", "second"], &cancel)
+            .await
+            .unwrap();
+
+        assert!(composition.cancelled);
+        assert_eq!(composition.results.len(), 1);
+        assert_eq!(composition.results[0].tool_name, "first");
+        assert_eq!(later.calls.load(Ordering::SeqCst), 0);
+    }
+
+    fn delayed_composer(log: &Arc<std::sync::Mutex<Vec<&'static str>>>) -> ToolComposer {
+        let tool = |name, delay_ms| {
+            Arc::new(DelayedTool {
+                name,
+                delay: Duration::from_millis(delay_ms),
+                log: log.clone(),
+            })
+        };
+        let mut composer = ToolComposer::new()
+            .with_tool("extract", tool("extract", 10))
+            .with_tool("analyze", tool("analyze", 100))
+            .with_tool("report", tool("report", 10));
+        composer.add_dependency("analyze", "extract");
+        composer.add_dependency("report", "analyze");
+        composer
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_budget_stops_pipeline_early() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let composer = delayed_composer(&log);
+
+        let composition = composer
+            .compose_with_budget(
+                vec!["extract", "analyze", "report"],
+                Duration::from_millis(50),
+            )
+            .await
+            .unwrap();
+
+        assert!(composition.budget_exceeded);
+        assert!(!composition.cancelled);
+        let completed: Vec<&str> = composition
+            .results
+            .iter()
+            .map(|r| r.tool_name.as_str())
+            .collect();
+        assert_eq!(completed, vec!["extract"]);
+        assert_eq!(*log.lock().unwrap(), vec!["extract"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_tool_timeout_applies_within_budget() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let composer = delayed_composer(&log).with_tool_timeout(Duration::from_millis(20));
+
+        let composition = composer
+            .compose_with_budget(vec!["extract", "analyze", "report"], Duration::from_secs(1))
+            .await
+            .unwrap();
+
+        assert!(!composition.budget_exceeded);
+        assert_eq!(composition.results.len(), 3);
+        assert_eq!(composition.results[1].output["status"], "error");
+        assert_eq!(*log.lock().unwrap(), vec!["extract", "report"]);
+    }
+}
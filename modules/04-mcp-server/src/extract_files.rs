@@ -0,0 +1,212 @@
+use crate::params::{invalid_params, optional_bool_param, optional_string_param, string_param};
+use async_trait::async_trait;
+use glob::Pattern;
+use pmcp::sandbox::Sandbox;
+use pmcp::server::{ProgressSender, ToolHandler};
+use pmcp::{PmcpError, Result};
+use serde_json::json;
+use std::path::Path;
+
+/// Backs `extract_files_tool()`. Every requested path is resolved through
+/// the sandbox before the directory is read.
+pub struct ExtractFilesHandler {
+    sandbox: Sandbox,
+}
+
+impl ExtractFilesHandler {
+    #[must_use]
+    pub fn new(sandbox: Sandbox) -> Self {
+        Self { sandbox }
+    }
+}
+
+fn collect_files(
+    base: &Path,
+    dir: &Path,
+    pattern: &Pattern,
+    recursive: bool,
+    files: &mut Vec<String>,
+) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        // Not followed through symlinks, which could lead the walk out of
+        // the sandbox or round in a loop.
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            if recursive {
+                collect_files(base, &path, pattern, recursive, files)?;
+            }
+        } else if file_type.is_file() {
+            let relative = path.strip_prefix(base).unwrap_or(&path);
+            if pattern.matches_path(relative) {
+                files.push(relative.to_string_lossy().into_owned());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+impl ExtractFilesHandler {
+    /// Validates the arguments and resolves the path through the sandbox;
+    /// the directory is only read when `dry_run` is false.
+    async fn extract(
+        &self,
+        params: Option<serde_json::Value>,
+        dry_run: bool,
+    ) -> Result<serde_json::Value> {
+        let dir = self
+            .sandbox
+            .resolve(string_param(params.as_ref(), "path")?)?;
+        let pattern = optional_string_param(params.as_ref(), "pattern").unwrap_or("*");
+        let pattern = Pattern::new(pattern)
+            .map_err(|e| invalid_params(format!("Invalid pattern {pattern}: {e}")))?;
+        let recursive = optional_bool_param(params.as_ref(), "recursive").unwrap_or(false);
+        if dry_run {
+            return Ok(json!({}));
+        }
+
+        let files = tokio::task::spawn_blocking(move || {
+            let mut files = Vec::new();
+            collect_files(&dir, &dir, &pattern, recursive, &mut files).map(|()| {
+                files.sort();
+                files
+            })
+        })
+        .await
+        .map_err(|e| PmcpError::Tool(e.to_string()))?
+        .map_err(|e| PmcpError::Tool(e.to_string()))?;
+
+        Ok(json!({ "files": files }))
+    }
+}
+
+#[async_trait]
+impl ToolHandler for ExtractFilesHandler {
+    async fn handle(&self, params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+        self.extract(params, false).await
+    }
+
+    async fn handle_call(
+        &self,
+        params: Option<serde_json::Value>,
+        _progress: ProgressSender,
+        dry_run: bool,
+    ) -> Result<serde_json::Value> {
+        self.extract(params, dry_run).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pmcp::protocol::ERROR_INVALID_PARAMS;
+
+    fn project() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("project/src")).unwrap();
+        std::fs::write(dir.path().join("project/Cargo.toml"), "").unwrap();
+        std::fs::write(dir.path().join("project/README.md"), "").unwrap();
+        std::fs::write(dir.path().join("project/build.rs"), "").unwrap();
+        std::fs::write(dir.path().join("project/src/lib.rs"), "").unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_pattern_matches_only_rust_files() {
+        let dir = project();
+        let handler = ExtractFilesHandler::new(Sandbox::new(dir.path()).unwrap());
+
+        let flat = handler
+            .handle(Some(json!({ "path": "project", "pattern": "*.rs" })))
+            .await
+            .unwrap();
+        assert_eq!(flat, json!({ "files": ["build.rs"] }));
+
+        let recursive = handler
+            .handle(Some(
+                json!({ "path": "project", "pattern": "*.rs", "recursive": true }),
+            ))
+            .await
+            .unwrap();
+        let lib_rs = Path::new("src").join("lib.rs");
+        assert_eq!(
+            recursive,
+            json!({ "files": ["build.rs", lib_rs.to_string_lossy()] })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_parent_traversal_is_rejected() {
+        let dir = project();
+        let handler = ExtractFilesHandler::new(Sandbox::new(dir.path().join("project")).unwrap());
+
+        let result = handler.handle(Some(json!({ "path": "../etc" }))).await;
+        assert!(matches!(
+            result,
+            Err(PmcpError::JsonRpc {
+                code: ERROR_INVALID_PARAMS,
+                ..
+            })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_recursive_walk_does_not_follow_symlinks() {
+        let dir = project();
+        std::fs::create_dir(dir.path().join("outside")).unwrap();
+        std::fs::write(dir.path().join("outside/secret.rs"), "").unwrap();
+        std::os::unix::fs::symlink(
+            dir.path().join("outside"),
+            dir.path().join("project/src/escape"),
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(dir.path().join("project"), dir.path().join("project/loop"))
+            .unwrap();
+        let handler = ExtractFilesHandler::new(Sandbox::new(dir.path().join("project")).unwrap());
+
+        let listed = handler
+            .handle(Some(
+                json!({ "path": ".", "pattern": "*.rs", "recursive": true }),
+            ))
+            .await
+            .unwrap();
+
+        let lib_rs = Path::new("src").join("lib.rs");
+        assert_eq!(
+            listed,
+            json!({ "files": ["build.rs", lib_rs.to_string_lossy()] })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_validates_path_without_listing() {
+        let dir = project();
+        let server = pmcp::server::ServerBuilder::new()
+            .with_handler(
+                pmcp::tools::extract_files_tool(),
+                ExtractFilesHandler::new(Sandbox::new(dir.path().join("project")).unwrap()),
+            )
+            .build()
+            .unwrap();
+        let dry_run = |path: &str| pmcp::Request {
+            jsonrpc: "2.0".to_string(),
+            method: "tools/call".to_string(),
+            params: Some(json!({
+                "name": "extract_files",
+                "arguments": { "path": path },
+                "_meta": { "dryRun": true },
+            })),
+            id: Some(json!(1)),
+        };
+
+        let valid = server.handle_request(dry_run("src")).await.unwrap();
+        assert_eq!(valid.result, Some(json!({ "dryRun": true, "valid": true })));
+
+        let escaped = server.handle_request(dry_run("../etc")).await.unwrap();
+        assert_eq!(escaped.error.unwrap().code, ERROR_INVALID_PARAMS);
+    }
+}
@@ -1,3 +1,10 @@
 #![warn(clippy::all, clippy::pedantic)]
 
+pub mod analyze_complexity;
+pub mod calculator;
+pub mod composer;
+pub mod extract_files;
+mod params;
+pub mod read_file_chunked;
+pub mod select;
 pub mod server;
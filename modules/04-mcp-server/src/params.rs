@@ -0,0 +1,32 @@
+use pmcp::protocol::ERROR_INVALID_PARAMS;
+use pmcp::{PmcpError, Result};
+
+pub(crate) fn invalid_params(message: String) -> PmcpError {
+    PmcpError::JsonRpc {
+        code: ERROR_INVALID_PARAMS,
+        message,
+    }
+}
+
+pub(crate) fn string_param<'a>(
+    params: Option<&'a serde_json::Value>,
+    name: &str,
+) -> Result<&'a str> {
+    optional_string_param(params, name)
+        .ok_or_else(|| invalid_params(format!("Missing required parameter: {name}")))
+}
+
+pub(crate) fn optional_string_param<'a>(
+    params: Option<&'a serde_json::Value>,
+    name: &str,
+) -> Option<&'a str> {
+    params
+        .and_then(|p| p.get(name))
+        .and_then(serde_json::Value::as_str)
+}
+
+pub(crate) fn optional_bool_param(params: Option<&serde_json::Value>, name: &str) -> Option<bool> {
+    params
+        .and_then(|p| p.get(name))
+        .and_then(serde_json::Value::as_bool)
+}
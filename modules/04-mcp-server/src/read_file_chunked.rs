@@ -0,0 +1,217 @@
+use crate::params::invalid_params;
+use async_trait::async_trait;
+use pmcp::sandbox::Sandbox;
+use pmcp::server::{ProgressSender, ToolHandler};
+use pmcp::tools::parse_params;
+use pmcp::{PmcpError, Result};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+/// The most a single call returns, whatever `length` asks for.
+pub const MAX_CHUNK_LENGTH: u64 = 1 << 20;
+
+#[derive(Deserialize)]
+struct ChunkRequest {
+    path: String,
+    #[serde(default)]
+    offset: u64,
+    length: u64,
+}
+
+/// Backs `read_file_chunked_tool()`, letting clients page through a file
+/// that would not fit in one response. Returns `{content, nextOffset, eof}`;
+/// a chunk that would end inside a UTF-8 character stops before it, or
+/// returns that one character whole if it is the first, so clients should
+/// continue from `nextOffset` rather than `offset + length`.
+pub struct ReadFileChunkedHandler {
+    sandbox: Sandbox,
+}
+
+impl ReadFileChunkedHandler {
+    #[must_use]
+    pub fn new(sandbox: Sandbox) -> Self {
+        Self { sandbox }
+    }
+
+    async fn read(
+        &self,
+        params: Option<serde_json::Value>,
+        dry_run: bool,
+    ) -> Result<serde_json::Value> {
+        let request: ChunkRequest = parse_params(params)?;
+        if request.length == 0 {
+            return Err(invalid_params("length must be at least 1".to_string()));
+        }
+        let path = self.sandbox.resolve(&request.path)?;
+        if dry_run {
+            return Ok(json!({}));
+        }
+
+        let io_error = |e: std::io::Error| PmcpError::Tool(e.to_string());
+        let mut file = tokio::fs::File::open(&path).await.map_err(io_error)?;
+        let size = file.metadata().await.map_err(io_error)?.len();
+        let offset = request.offset.min(size);
+        let length = request.length.min(MAX_CHUNK_LENGTH);
+
+        file.seek(std::io::SeekFrom::Start(offset))
+            .await
+            .map_err(io_error)?;
+        // Up to three bytes past `length` so a character that straddles a
+        // short chunk's start can still be returned whole.
+        let mut bytes = Vec::new();
+        file.take(length + 3)
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(io_error)?;
+        let requested = &bytes[..bytes
+            .len()
+            .min(usize::try_from(length).unwrap_or(usize::MAX))];
+
+        let content = match std::str::from_utf8(requested) {
+            Ok(content) => content,
+            // Cut off part way through a character: leave it for the next chunk.
+            Err(e) if e.error_len().is_none() && e.valid_up_to() > 0 => {
+                std::str::from_utf8(&requested[..e.valid_up_to()]).unwrap_or_default()
+            }
+            // `length` is shorter than the first character: return that one
+            // character so the client still makes progress.
+            Err(e) if e.error_len().is_none() => first_char(&bytes).ok_or_else(|| {
+                PmcpError::Tool(format!(
+                    "{} is not UTF-8 text at byte {offset}",
+                    request.path
+                ))
+            })?,
+            Err(e) => {
+                return Err(PmcpError::Tool(format!(
+                    "{} is not UTF-8 text at byte {}",
+                    request.path,
+                    offset + e.valid_up_to() as u64
+                )))
+            }
+        };
+        let next_offset = offset + content.len() as u64;
+
+        Ok(json!({
+            "content": content,
+            "nextOffset": next_offset,
+            "eof": next_offset >= size,
+        }))
+    }
+}
+
+/// The first character of `bytes`, if they start with a complete one.
+fn first_char(bytes: &[u8]) -> Option<&str> {
+    let valid = bytes.utf8_chunks().next()?.valid();
+    let len = valid.chars().next()?.len_utf8();
+    Some(&valid[..len])
+}
+
+#[async_trait]
+impl ToolHandler for ReadFileChunkedHandler {
+    async fn handle(&self, params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+        self.read(params, false).await
+    }
+
+    async fn handle_call(
+        &self,
+        params: Option<serde_json::Value>,
+        _progress: ProgressSender,
+        dry_run: bool,
+    ) -> Result<serde_json::Value> {
+        self.read(params, dry_run).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pmcp::protocol::ERROR_INVALID_PARAMS;
+
+    const CONTENT: &str = "fn main() {\n    println!(\"héllo\");\n}\n";
+
+    fn handler() -> (tempfile::TempDir, ReadFileChunkedHandler) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("main.rs"), CONTENT).unwrap();
+        let handler = ReadFileChunkedHandler::new(Sandbox::new(dir.path()).unwrap());
+        (dir, handler)
+    }
+
+    #[tokio::test]
+    async fn test_two_chunks_reassemble_the_file() {
+        let (_dir, handler) = handler();
+        // Byte 28 falls inside the two-byte 'é'.
+        let first = handler
+            .handle(Some(json!({ "path": "main.rs", "length": 28 })))
+            .await
+            .unwrap();
+        assert_eq!(first["eof"], false);
+        assert_eq!(first["nextOffset"], 27);
+
+        let second = handler
+            .handle(Some(json!({
+                "path": "main.rs",
+                "offset": first["nextOffset"],
+                "length": 100,
+            })))
+            .await
+            .unwrap();
+        assert_eq!(second["eof"], true);
+
+        let reassembled = format!(
+            "{}{}",
+            first["content"].as_str().unwrap(),
+            second["content"].as_str().unwrap()
+        );
+        assert_eq!(reassembled, CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_length_shorter_than_a_character_returns_it_whole() {
+        let (_dir, handler) = handler();
+
+        // Byte 27 starts the two-byte 'é'.
+        let chunk = handler
+            .handle(Some(
+                json!({ "path": "main.rs", "offset": 27, "length": 1 }),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(chunk["content"], "é");
+        assert_eq!(chunk["nextOffset"], 29);
+        assert_eq!(chunk["eof"], false);
+    }
+
+    #[tokio::test]
+    async fn test_zero_length_is_invalid_params() {
+        let (_dir, handler) = handler();
+
+        let result = handler
+            .handle(Some(json!({ "path": "main.rs", "length": 0 })))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(PmcpError::JsonRpc {
+                code: ERROR_INVALID_PARAMS,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_offset_past_eof_is_empty() {
+        let (_dir, handler) = handler();
+
+        let past_end = handler
+            .handle(Some(
+                json!({ "path": "main.rs", "offset": 1_000, "length": 10 }),
+            ))
+            .await
+            .unwrap();
+
+        assert_eq!(past_end["content"], "");
+        assert_eq!(past_end["eof"], true);
+    }
+}
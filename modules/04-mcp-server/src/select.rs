@@ -0,0 +1,51 @@
+use futures::future::Either;
+use std::future::Future;
+use tokio_util::sync::CancellationToken;
+
+/// Waits for whichever of `priority` and `other` finishes first. Unlike a
+/// plain `select!`, which picks a random branch when both are ready, the
+/// branches are polled in order, so `priority` always wins a tie and the
+/// outcome does not depend on scheduling.
+pub async fn biased_select<A, B>(priority: A, other: B) -> Either<A::Output, B::Output>
+where
+    A: Future,
+    B: Future,
+{
+    tokio::select! {
+        biased;
+        value = priority => Either::Left(value),
+        value = other => Either::Right(value),
+    }
+}
+
+/// Runs `work` unless `cancel` fires first. Cancellation takes priority, so
+/// work that completes at the same moment as a cancellation is discarded
+/// rather than racing it; returns `None` when cancelled.
+pub async fn unless_cancelled<F: Future>(cancel: &CancellationToken, work: F) -> Option<F::Output> {
+    match biased_select(cancel.cancelled(), work).await {
+        Either::Left(()) => None,
+        Either::Right(output) => Some(output),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_priority_branch_wins_when_both_ready() {
+        for _ in 0..100 {
+            let winner = biased_select(async { "priority" }, async { "other" }).await;
+            assert!(matches!(winner, Either::Left("priority")));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancellation_beats_ready_work() {
+        let cancel = CancellationToken::new();
+        assert_eq!(unless_cancelled(&cancel, async { 42 }).await, Some(42));
+
+        cancel.cancel();
+        assert_eq!(unless_cancelled(&cancel, async { 42 }).await, None);
+    }
+}
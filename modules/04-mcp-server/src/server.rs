@@ -1,7 +1,9 @@
+use crate::select::unless_cancelled;
 use pmcp::server::Server;
+use pmcp::{Request, Response, Result, ServerCapabilities};
+use tokio_util::sync::CancellationToken;
 
 pub struct ProductionServer {
-    #[allow(dead_code)]
     server: Server,
 }
 
@@ -9,9 +11,24 @@ impl ProductionServer {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            server: pmcp::server::ServerBuilder::new().build(),
+            server: Server::new(ServerCapabilities::default()),
         }
     }
+
+    /// Handles `request` unless `cancel` fires first, in which case no
+    /// response is sent. A cancellation arriving as the request completes
+    /// always wins, so the outcome is the same on every run.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handler fails to process the request.
+    pub async fn handle_cancellable(
+        &self,
+        request: Request,
+        cancel: &CancellationToken,
+    ) -> Option<Result<Response>> {
+        unless_cancelled(cancel, self.server.handle_request(request)).await
+    }
 }
 
 impl Default for ProductionServer {
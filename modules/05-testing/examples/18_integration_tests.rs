@@ -1,11 +1,29 @@
+use module_05_testing::harness::connect_stdio;
+use pmcp::server::ServerBuilder;
 use std::time::Duration;
 
 async fn test_mcp_protocol_e2e() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Testing MCP protocol end-to-end...");
-    tokio::time::sleep(Duration::from_millis(10)).await;
-    println!("    ✅ Connection established");
-    println!("    ✅ Handshake completed");
-    println!("    ✅ Request/response verified");
+    let (client, serving) = connect_stdio(ServerBuilder::new().build()?);
+
+    let init = client.call("initialize", None).await?;
+    println!(
+        "    ✅ Handshake completed: {} {}",
+        init["serverInfo"]["name"], init["serverInfo"]["version"]
+    );
+
+    let listed = client.call("tools/list", None).await?;
+    println!("    ✅ tools/list returned {}", listed["tools"]);
+
+    match client.call("no_such_method", None).await {
+        Err(pmcp::PmcpError::JsonRpc { code, message }) => {
+            println!("    ✅ Unknown method rejected: {code} {message}");
+        }
+        other => return Err(format!("expected a JSON-RPC error, got {other:?}").into()),
+    }
+
+    drop(client);
+    serving.await??;
     Ok(())
 }
 
@@ -1,5 +1,137 @@
+use crate::property_tests::ArbRequest;
+use crate::seed::generate;
+use pmcp::server::Server;
+use pmcp::{Request, Response};
+use std::path::{Path, PathBuf};
+
 pub fn fuzz_target(data: &[u8]) {
     if !data.is_empty() {
         let _ = std::str::from_utf8(data);
     }
 }
+
+#[derive(Debug)]
+pub enum ReplayOutcome {
+    /// The input decoded as a `Request` and the server answered it.
+    Dispatched(Response),
+    /// The input did not decode as a `Request`.
+    Rejected(String),
+    /// The server panicked while handling the request.
+    Panicked(String),
+}
+
+/// Feeds every file in `dir` through `Request` decoding and
+/// `Server::handle_request`, in file-name order. Handler panics are caught
+/// and reported as [`ReplayOutcome::Panicked`] so one bad input doesn't hide
+/// the rest of the corpus.
+///
+/// # Errors
+///
+/// Returns an error if `dir` or one of its files cannot be read.
+pub async fn replay_corpus(
+    dir: &Path,
+    server: &Server,
+) -> std::io::Result<Vec<(PathBuf, ReplayOutcome)>> {
+    let mut paths = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    paths.retain(|path| path.is_file());
+    paths.sort();
+
+    let mut outcomes = Vec::with_capacity(paths.len());
+    for path in paths {
+        let bytes = std::fs::read(&path)?;
+        outcomes.push((path, replay_one(&bytes, server).await));
+    }
+
+    Ok(outcomes)
+}
+
+/// Writes `count` requests generated from `seed` into `dir` as
+/// `request_00000.json`, `request_00001.json`, ... The same seed always
+/// yields byte-identical files, ready for [`replay_corpus`].
+///
+/// # Errors
+///
+/// Returns an error if `dir` cannot be created or a file cannot be written.
+pub fn generate_corpus(dir: &Path, count: usize, seed: u64) -> std::io::Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)?;
+
+    generate::<ArbRequest>(seed, count)
+        .into_iter()
+        .enumerate()
+        .map(|(index, request)| {
+            let path = dir.join(format!("request_{index:05}.json"));
+            let bytes = serde_json::to_vec(&request.0)?;
+            std::fs::write(&path, bytes)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+async fn replay_one(bytes: &[u8], server: &Server) -> ReplayOutcome {
+    let request = match serde_json::from_slice::<Request>(bytes) {
+        Ok(request) => request,
+        Err(e) => return ReplayOutcome::Rejected(e.to_string()),
+    };
+
+    let server = server.clone();
+    match tokio::spawn(async move { server.handle_request(request).await }).await {
+        Ok(Ok(response)) => ReplayOutcome::Dispatched(response),
+        Ok(Err(e)) => ReplayOutcome::Rejected(e.to_string()),
+        Err(e) => ReplayOutcome::Panicked(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pmcp::server::ServerBuilder;
+
+    #[tokio::test]
+    async fn test_replay_valid_and_truncated_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let message = r#"{"jsonrpc":"2.0","method":"calculator","id":1}"#;
+        std::fs::write(dir.path().join("01_valid.json"), message).unwrap();
+        std::fs::write(dir.path().join("02_truncated.json"), &message[..20]).unwrap();
+        let server = ServerBuilder::new().build().unwrap();
+
+        let outcomes = replay_corpus(dir.path(), &server).await.unwrap();
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(matches!(outcomes[0].1, ReplayOutcome::Dispatched(_)));
+        assert!(matches!(outcomes[1].1, ReplayOutcome::Rejected(_)));
+    }
+
+    #[test]
+    fn test_same_seed_generates_identical_corpus() {
+        let first = tempfile::tempdir().unwrap();
+        let second = tempfile::tempdir().unwrap();
+
+        let written = generate_corpus(first.path(), 25, 42).unwrap();
+        generate_corpus(second.path(), 25, 42).unwrap();
+
+        assert_eq!(written.len(), 25);
+        for path in &written {
+            let name = path.file_name().unwrap();
+            assert_eq!(
+                std::fs::read(path).unwrap(),
+                std::fs::read(second.path().join(name)).unwrap()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generated_corpus_replays() {
+        let dir = tempfile::tempdir().unwrap();
+        generate_corpus(dir.path(), 10, 7).unwrap();
+        let server = ServerBuilder::new().build().unwrap();
+
+        let outcomes = replay_corpus(dir.path(), &server).await.unwrap();
+
+        assert_eq!(outcomes.len(), 10);
+        assert!(outcomes
+            .iter()
+            .all(|(_, outcome)| matches!(outcome, ReplayOutcome::Dispatched(_))));
+    }
+}
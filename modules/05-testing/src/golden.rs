@@ -0,0 +1,111 @@
+use std::path::{Path, PathBuf};
+
+/// Compares `actual` against `tests/golden/<name>` in the crate under test,
+/// panicking with a line diff on mismatch. With `BLESS=1` set, the golden
+/// file is (re)written instead.
+///
+/// # Panics
+///
+/// Panics if the output differs from the golden file, or if the golden file
+/// is missing and `BLESS=1` is not set.
+pub fn assert_golden(name: &str, actual: &str) {
+    let bless = std::env::var("BLESS").is_ok_and(|v| v == "1");
+    if let Err(message) = check_golden(&golden_path(name), actual, bless) {
+        panic!("{message}");
+    }
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    let root = std::env::var_os("CARGO_MANIFEST_DIR").map_or_else(PathBuf::new, PathBuf::from);
+    root.join("tests").join("golden").join(name)
+}
+
+/// # Errors
+///
+/// Returns a readable description of the mismatch, or of why the golden
+/// file could not be read or written.
+pub fn check_golden(path: &Path, actual: &str, bless: bool) -> Result<(), String> {
+    if bless {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("{}: {e}", parent.display()))?;
+        }
+        return std::fs::write(path, actual).map_err(|e| format!("{}: {e}", path.display()));
+    }
+
+    let expected = std::fs::read_to_string(path)
+        .map_err(|e| format!("{}: {e}\nRun with BLESS=1 to create it.", path.display()))?;
+
+    if expected == actual {
+        Ok(())
+    } else {
+        Err(format!(
+            "Output differs from {} (- golden, + actual):\n{}\nRun with BLESS=1 to accept.",
+            path.display(),
+            line_diff(&expected, actual)
+        ))
+    }
+}
+
+/// Longest-common-subsequence line diff; unchanged lines are prefixed with
+/// two spaces so the context stays readable.
+fn line_diff(expected: &str, actual: &str) -> String {
+    let old: Vec<&str> = expected.lines().collect();
+    let new: Vec<&str> = actual.lines().collect();
+
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            out.push(format!("+ {}", new[j]));
+            j += 1;
+        } else {
+            out.push(format!("- {}", old[i]));
+            i += 1;
+        }
+    }
+
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bless_then_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("golden").join("diagram.mmd");
+        let sample = "graph TD\n  Init --> Parsing\n  Parsing --> Complete\n";
+
+        check_golden(&path, sample, true).unwrap();
+        assert!(check_golden(&path, sample, false).is_ok());
+
+        let changed = sample.replace("Complete", "Error");
+        let message = check_golden(&path, &changed, false).unwrap_err();
+        assert!(message.contains("-   Parsing --> Complete"));
+        assert!(message.contains("+   Parsing --> Error"));
+        assert!(message.contains("    Init --> Parsing"));
+    }
+
+    #[test]
+    fn test_missing_golden_file_suggests_bless() {
+        let dir = tempfile::tempdir().unwrap();
+        let message = check_golden(&dir.path().join("missing"), "x", false).unwrap_err();
+        assert!(message.contains("BLESS=1"));
+    }
+}
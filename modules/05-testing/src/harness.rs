@@ -0,0 +1,30 @@
+use pmcp::client::Client;
+use pmcp::server::Server;
+use pmcp::transport::StdioTransport;
+use tokio::task::JoinHandle;
+
+const PIPE_CAPACITY: usize = 64 * 1024;
+
+/// Runs `server` on a [`StdioTransport`] over in-memory pipes and returns a
+/// [`Client`] speaking the same framing on the other end, plus the serve
+/// task. Dropping the client closes the pipe and lets the task finish.
+///
+/// # Panics
+///
+/// Panics if called outside a tokio runtime.
+#[must_use]
+pub fn connect_stdio(server: Server) -> (Client, JoinHandle<pmcp::Result<()>>) {
+    let (client_io, server_io) = tokio::io::duplex(PIPE_CAPACITY);
+
+    let (server_read, server_write) = tokio::io::split(server_io);
+    let serving = tokio::spawn(async move {
+        server
+            .serve(StdioTransport::from_pipes(server_read, server_write))
+            .await
+    });
+
+    let (client_read, client_write) = tokio::io::split(client_io);
+    let client = Client::new(StdioTransport::from_pipes(client_read, client_write));
+
+    (client, serving)
+}
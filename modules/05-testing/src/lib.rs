@@ -1,4 +1,7 @@
 #![warn(clippy::all, clippy::pedantic)]
 
 pub mod fuzzing;
+pub mod golden;
+pub mod harness;
 pub mod property_tests;
+pub mod seed;
@@ -1,4 +1,10 @@
+use pmcp::protocol::{
+    ERROR_INTERNAL, ERROR_INVALID_PARAMS, ERROR_INVALID_REQUEST, ERROR_METHOD_NOT_FOUND,
+    ERROR_PARSE,
+};
+use pmcp::{ErrorObject, Request, Response};
 use quickcheck::{Arbitrary, Gen};
+use serde_json::{Map, Value};
 
 #[derive(Debug, Clone)]
 pub struct TestData {
@@ -12,3 +18,343 @@ impl Arbitrary for TestData {
         }
     }
 }
+
+const METHOD_SEGMENTS: &[&str] = &[
+    "tools",
+    "call",
+    "list",
+    "resources",
+    "read",
+    "prompts",
+    "initialize",
+    "notifications",
+    "progress",
+];
+
+const ERROR_CODES: &[i32] = &[
+    ERROR_PARSE,
+    ERROR_INVALID_REQUEST,
+    ERROR_METHOD_NOT_FOUND,
+    ERROR_INVALID_PARAMS,
+    ERROR_INTERNAL,
+];
+
+const MAX_JSON_DEPTH: usize = 3;
+
+/// Generates JSON-RPC requests with `/`-separated method names, numeric or
+/// string ids, and nested object params. Shrinks toward no params, no id and
+/// a single-segment method.
+#[derive(Debug, Clone)]
+pub struct ArbRequest(pub Request);
+
+/// Generates a response carrying either a result or an error, never both.
+#[derive(Debug, Clone)]
+pub struct ArbResponse(pub Response);
+
+#[derive(Debug, Clone)]
+pub struct ArbErrorObject(pub ErrorObject);
+
+impl Arbitrary for ArbRequest {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Self(Request {
+            jsonrpc: "2.0".to_string(),
+            method: arbitrary_method(g),
+            params: bool::arbitrary(g).then(|| arbitrary_object(g, MAX_JSON_DEPTH)),
+            id: arbitrary_id(g),
+        })
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let request = self.0.clone();
+        let with_params = {
+            let request = request.clone();
+            shrink_option(request.params.as_ref(), shrink_value).map(move |params| {
+                Self(Request {
+                    params,
+                    ..request.clone()
+                })
+            })
+        };
+        let with_id = {
+            let request = request.clone();
+            shrink_option(request.id.as_ref(), shrink_value).map(move |id| {
+                Self(Request {
+                    id,
+                    ..request.clone()
+                })
+            })
+        };
+        let with_method = shrink_method(&request.method).map(move |method| {
+            Self(Request {
+                method,
+                ..request.clone()
+            })
+        });
+
+        Box::new(with_params.chain(with_id).chain(with_method))
+    }
+}
+
+impl Arbitrary for ArbErrorObject {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let code = if bool::arbitrary(g) {
+            *g.choose(ERROR_CODES).unwrap_or(&ERROR_INTERNAL)
+        } else {
+            i32::arbitrary(g)
+        };
+
+        Self(ErrorObject {
+            code,
+            message: String::arbitrary(g),
+            data: bool::arbitrary(g).then(|| arbitrary_value(g, MAX_JSON_DEPTH)),
+        })
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let error = self.0.clone();
+        let with_data = {
+            let error = error.clone();
+            shrink_option(error.data.as_ref(), shrink_value).map(move |data| {
+                Self(ErrorObject {
+                    data,
+                    ..error.clone()
+                })
+            })
+        };
+        let with_message = {
+            let error = error.clone();
+            error.message.shrink().map(move |message| {
+                Self(ErrorObject {
+                    message,
+                    ..error.clone()
+                })
+            })
+        };
+        let with_code = error.code.shrink().map(move |code| {
+            Self(ErrorObject {
+                code,
+                ..error.clone()
+            })
+        });
+
+        Box::new(with_data.chain(with_message).chain(with_code))
+    }
+}
+
+impl Arbitrary for ArbResponse {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let (result, error) = if bool::arbitrary(g) {
+            (Some(arbitrary_value(g, MAX_JSON_DEPTH)), None)
+        } else {
+            (None, Some(ArbErrorObject::arbitrary(g).0))
+        };
+
+        Self(Response {
+            jsonrpc: "2.0".to_string(),
+            result,
+            error,
+            id: arbitrary_id(g),
+        })
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let response = self.0.clone();
+        let with_result = {
+            let response = response.clone();
+            let results: Box<dyn Iterator<Item = Value>> = match &response.result {
+                Some(result) => shrink_value(result),
+                None => Box::new(std::iter::empty()),
+            };
+            results.map(move |result| {
+                Self(Response {
+                    result: Some(result),
+                    ..response.clone()
+                })
+            })
+        };
+        let with_error = {
+            let response = response.clone();
+            let errors: Box<dyn Iterator<Item = ArbErrorObject>> = match &response.error {
+                Some(error) => ArbErrorObject(error.clone()).shrink(),
+                None => Box::new(std::iter::empty()),
+            };
+            errors.map(move |error| {
+                Self(Response {
+                    error: Some(error.0),
+                    ..response.clone()
+                })
+            })
+        };
+        let with_id = shrink_option(response.id.as_ref(), shrink_value).map(move |id| {
+            Self(Response {
+                id,
+                ..response.clone()
+            })
+        });
+
+        Box::new(with_result.chain(with_error).chain(with_id))
+    }
+}
+
+fn arbitrary_method(g: &mut Gen) -> String {
+    let segments = usize::arbitrary(g) % 3 + 1;
+    (0..segments)
+        .map(|_| *g.choose(METHOD_SEGMENTS).unwrap_or(&"tools"))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn shrink_method(method: &str) -> Box<dyn Iterator<Item = String>> {
+    let segments: Vec<String> = method.split('/').map(str::to_string).collect();
+    Box::new(
+        (1..segments.len())
+            .rev()
+            .map(move |len| segments[..len].join("/")),
+    )
+}
+
+/// `null` ids are left out: they deserialize back to `None`, not `Some(null)`.
+fn arbitrary_id(g: &mut Gen) -> Option<Value> {
+    match u8::arbitrary(g) % 3 {
+        0 => None,
+        1 => Some(Value::from(i64::arbitrary(g))),
+        _ => Some(Value::from(arbitrary_key(g))),
+    }
+}
+
+fn arbitrary_key(g: &mut Gen) -> String {
+    let len = usize::arbitrary(g) % 8 + 1;
+    (0..len)
+        .map(|_| *g.choose(b"abcdefghijklmnopqrstuvwxyz_").unwrap_or(&b'a') as char)
+        .collect()
+}
+
+fn arbitrary_object(g: &mut Gen, depth: usize) -> Value {
+    let len = usize::arbitrary(g) % 4;
+    let map: Map<String, Value> = (0..len)
+        .map(|_| {
+            (
+                arbitrary_key(g),
+                arbitrary_value(g, depth.saturating_sub(1)),
+            )
+        })
+        .collect();
+    Value::Object(map)
+}
+
+/// Never produces a bare `null` (see [`arbitrary_id`]) or floats, whose
+/// JSON text form does not always round-trip exactly.
+fn arbitrary_value(g: &mut Gen, depth: usize) -> Value {
+    let variants = if depth == 0 { 3 } else { 5 };
+    match u8::arbitrary(g) % variants {
+        0 => Value::from(bool::arbitrary(g)),
+        1 => Value::from(i64::arbitrary(g)),
+        2 => Value::from(String::arbitrary(g)),
+        3 => {
+            let len = usize::arbitrary(g) % 4;
+            Value::Array((0..len).map(|_| arbitrary_value(g, depth - 1)).collect())
+        }
+        _ => arbitrary_object(g, depth),
+    }
+}
+
+fn shrink_value(value: &Value) -> Box<dyn Iterator<Item = Value>> {
+    match value {
+        Value::Bool(b) => Box::new(b.shrink().map(Value::from)),
+        Value::Number(n) => Box::new(n.as_i64().unwrap_or(0).shrink().map(Value::from)),
+        Value::String(s) => Box::new(s.shrink().map(Value::from)),
+        Value::Array(items) => {
+            let items = items.clone();
+            let removals = {
+                let items = items.clone();
+                (0..items.len()).map(move |i| {
+                    let mut smaller = items.clone();
+                    smaller.remove(i);
+                    Value::Array(smaller)
+                })
+            };
+            let nested = (0..items.len()).flat_map(move |i| {
+                let items = items.clone();
+                shrink_value(&items[i]).map(move |smaller_item| {
+                    let mut smaller = items.clone();
+                    smaller[i] = smaller_item;
+                    Value::Array(smaller)
+                })
+            });
+            Box::new(removals.chain(nested))
+        }
+        Value::Object(map) => {
+            let map = map.clone();
+            let removals = {
+                let map = map.clone();
+                map.clone().into_iter().map(move |(key, _)| {
+                    let mut smaller = map.clone();
+                    smaller.remove(&key);
+                    Value::Object(smaller)
+                })
+            };
+            let nested = map.clone().into_iter().flat_map(move |(key, inner)| {
+                let map = map.clone();
+                shrink_value(&inner).map(move |smaller_inner| {
+                    let mut smaller = map.clone();
+                    smaller.insert(key.clone(), smaller_inner);
+                    Value::Object(smaller)
+                })
+            });
+            Box::new(removals.chain(nested))
+        }
+        Value::Null => Box::new(std::iter::empty()),
+    }
+}
+
+fn shrink_option(
+    value: Option<&Value>,
+    shrink: fn(&Value) -> Box<dyn Iterator<Item = Value>>,
+) -> Box<dyn Iterator<Item = Option<Value>>> {
+    match value {
+        Some(value) => Box::new(std::iter::once(None).chain(shrink(value).map(Some))),
+        None => Box::new(std::iter::empty()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed::check;
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn request_round_trips(request: ArbRequest) -> bool {
+        let json = serde_json::to_string(&request.0).unwrap();
+        serde_json::from_str::<Request>(&json).unwrap() == request.0
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn response_round_trips(response: ArbResponse) -> bool {
+        let json = serde_json::to_string(&response.0).unwrap();
+        serde_json::from_str::<Response>(&json).unwrap() == response.0
+    }
+
+    #[test]
+    fn prop_request_json_round_trip() {
+        check(request_round_trips as fn(ArbRequest) -> bool);
+    }
+
+    #[test]
+    fn prop_response_json_round_trip() {
+        check(response_round_trips as fn(ArbResponse) -> bool);
+    }
+
+    #[test]
+    fn test_request_shrinks_toward_minimal() {
+        let request = ArbRequest(Request {
+            jsonrpc: "2.0".to_string(),
+            method: "tools/call".to_string(),
+            params: Some(serde_json::json!({ "name": "calculator" })),
+            id: Some(Value::from(7)),
+        });
+
+        let first = request.shrink().next().unwrap();
+        assert_eq!(first.0.params, None);
+        assert!(request.shrink().any(|r| r.0.method == "tools"));
+    }
+}
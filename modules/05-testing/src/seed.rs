@@ -0,0 +1,68 @@
+use quickcheck::{Arbitrary, Gen, QuickCheck, Testable};
+use std::hash::{BuildHasher, RandomState};
+use std::panic::AssertUnwindSafe;
+
+/// Set to a `u64` to replay a run; otherwise a fresh seed is chosen and
+/// printed if the run fails.
+pub const SEED_ENV: &str = "DETERMINISTIC_SEED";
+
+const GEN_SIZE: usize = 100;
+
+/// The seed from [`SEED_ENV`], or a fresh one if it is unset or not a `u64`.
+#[must_use]
+pub fn seed() -> u64 {
+    std::env::var(SEED_ENV)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_else(|| RandomState::new().hash_one(std::time::SystemTime::now()))
+}
+
+#[must_use]
+pub fn seeded_gen(seed: u64) -> Gen {
+    Gen::from_size_and_seed(GEN_SIZE, seed)
+}
+
+/// Generates `count` values from `seed`; the same seed always yields the
+/// same values. Useful for building reproducible fuzz inputs.
+#[must_use]
+pub fn generate<T: Arbitrary>(seed: u64, count: usize) -> Vec<T> {
+    let mut generator = seeded_gen(seed);
+    (0..count).map(|_| T::arbitrary(&mut generator)).collect()
+}
+
+/// Runs a quickcheck property with a seeded generator, printing the seed on
+/// failure so the run can be reproduced with [`SEED_ENV`].
+///
+/// # Panics
+///
+/// Panics if the property fails.
+pub fn check<A: Testable>(property: A) {
+    let seed = seed();
+    let outcome = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        QuickCheck::new().rng(seeded_gen(seed)).quickcheck(property);
+    }));
+
+    if let Err(panic) = outcome {
+        eprintln!("Property failed; reproduce with {SEED_ENV}={seed}");
+        std::panic::resume_unwind(panic);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::property_tests::ArbRequest;
+
+    #[test]
+    fn test_same_seed_generates_same_inputs() {
+        let first: Vec<ArbRequest> = generate(42, 20);
+        let second: Vec<ArbRequest> = generate(42, 20);
+
+        let first: Vec<_> = first.into_iter().map(|r| r.0).collect();
+        let second: Vec<_> = second.into_iter().map(|r| r.0).collect();
+        assert_eq!(first, second);
+
+        let bytes: Vec<Vec<u8>> = generate(7, 10);
+        assert_eq!(bytes, generate::<Vec<u8>>(7, 10));
+    }
+}
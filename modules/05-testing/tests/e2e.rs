@@ -0,0 +1,118 @@
+use async_trait::async_trait;
+use module_05_testing::harness::connect_stdio;
+use pmcp::protocol::{ERROR_INVALID_PARAMS, ERROR_METHOD_NOT_FOUND};
+use pmcp::server::{ServerBuilder, ToolHandler};
+use pmcp::PmcpError;
+use serde_json::{json, Value};
+
+struct Calculator;
+
+#[async_trait]
+impl ToolHandler for Calculator {
+    async fn handle(&self, params: Option<Value>) -> pmcp::Result<Value> {
+        let params = params.unwrap_or_default();
+        let (Some(a), Some(b)) = (params["a"].as_f64(), params["b"].as_f64()) else {
+            return Err(PmcpError::JsonRpc {
+                code: ERROR_INVALID_PARAMS,
+                message: "'a' and 'b' must be numbers".to_string(),
+            });
+        };
+
+        match params["operation"].as_str() {
+            Some("add") => Ok(json!(a + b)),
+            Some("multiply") => Ok(json!(a * b)),
+            _ => Err(PmcpError::JsonRpc {
+                code: ERROR_INVALID_PARAMS,
+                message: "Unsupported operation".to_string(),
+            }),
+        }
+    }
+}
+
+fn calculator_server() -> pmcp::server::Server {
+    ServerBuilder::new()
+        .with_handler(pmcp::tools::calculator_tool(), Calculator)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_initialize_list_and_call_over_stdio() {
+    let server = calculator_server();
+    let (client, serving) = connect_stdio(server.clone());
+
+    let init = client.call("initialize", None).await.unwrap();
+    assert_eq!(init["serverInfo"]["name"], "pmcp");
+    assert!(server.readiness().ok);
+
+    let listed = client.call("tools/list", None).await.unwrap();
+    let names: Vec<&str> = listed["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|tool| tool["name"].as_str())
+        .collect();
+    assert_eq!(names, ["calculator"]);
+    assert_eq!(
+        listed["tools"][0]["inputSchema"]["required"][0],
+        "operation"
+    );
+
+    let product = client
+        .call(
+            "tools/call",
+            Some(json!({
+                "name": "calculator",
+                "arguments": {"operation": "multiply", "a": 6, "b": 7},
+            })),
+        )
+        .await
+        .unwrap();
+    assert_eq!(product, json!(42.0));
+
+    drop(client);
+    serving.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_errors_come_back_as_json_rpc_errors() {
+    let (client, serving) = connect_stdio(calculator_server());
+
+    let unknown = client.call("resources/read", None).await;
+    assert!(matches!(
+        unknown,
+        Err(PmcpError::JsonRpc {
+            code: ERROR_METHOD_NOT_FOUND,
+            ..
+        })
+    ));
+
+    let bad_operation = client
+        .call(
+            "tools/call",
+            Some(json!({
+                "name": "calculator",
+                "arguments": {"operation": "modulo", "a": 1, "b": 2},
+            })),
+        )
+        .await;
+    assert!(matches!(
+        bad_operation,
+        Err(PmcpError::JsonRpc {
+            code: ERROR_INVALID_PARAMS,
+            ..
+        })
+    ));
+
+    let nameless = client.call("tools/call", Some(json!({}))).await;
+    assert!(matches!(
+        nameless,
+        Err(PmcpError::JsonRpc {
+            code: ERROR_INVALID_PARAMS,
+            ..
+        })
+    ));
+
+    drop(client);
+    serving.await.unwrap().unwrap();
+}
@@ -0,0 +1,58 @@
+use async_trait::async_trait;
+use criterion::{criterion_group, criterion_main, Criterion};
+use pmcp::server::{Server, ServerBuilder, ToolHandler};
+use pmcp::tools::calculator_tool;
+use pmcp::Request;
+use serde_json::json;
+use std::hint::black_box;
+
+struct AddHandler;
+
+#[async_trait]
+impl ToolHandler for AddHandler {
+    async fn handle(&self, params: Option<serde_json::Value>) -> pmcp::Result<serde_json::Value> {
+        let params = params.unwrap_or_default();
+        let a = params["a"].as_i64().unwrap_or_default();
+        let b = params["b"].as_i64().unwrap_or_default();
+        Ok(json!({ "result": a + b }))
+    }
+}
+
+fn server() -> Server {
+    ServerBuilder::new()
+        .with_handler(calculator_tool(), AddHandler)
+        .build()
+        .expect("server builds")
+}
+
+fn request(method: &str, params: serde_json::Value) -> Request {
+    Request {
+        jsonrpc: "2.0".to_string(),
+        method: method.to_string(),
+        params: Some(params),
+        id: Some(json!(1)),
+    }
+}
+
+fn bench_handle_request(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime builds");
+    let server = server();
+    let call = request(
+        "tools/call",
+        json!({ "name": "calculator", "arguments": { "operation": "add", "a": 2, "b": 3 } }),
+    );
+    let list = request("tools/list", json!({}));
+
+    c.bench_function("handle_request tools/call", |b| {
+        b.iter(|| runtime.block_on(server.handle_request(black_box(call.clone()))));
+    });
+    c.bench_function("handle_request tools/list", |b| {
+        b.iter(|| runtime.block_on(server.handle_request(black_box(list.clone()))));
+    });
+}
+
+criterion_group!(benches, bench_handle_request);
+criterion_main!(benches);
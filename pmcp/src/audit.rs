@@ -0,0 +1,306 @@
+use crate::{PmcpError, Result};
+use async_trait::async_trait;
+use ring::digest::{digest, SHA256};
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+/// The chain value a fresh audit log starts from.
+const GENESIS: &str = "";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Ok,
+    Error { code: i32 },
+}
+
+/// One tool invocation. Arguments are recorded only as
+/// [`AuditSink::hash_arguments`], so the log can show two calls had the
+/// same input without revealing what it was.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp_ms: u64,
+    /// The method the client called: `tools/call` or the tool's own name.
+    pub method: String,
+    pub tool: String,
+    pub arguments_hash: String,
+    pub outcome: AuditOutcome,
+    pub duration_ms: u64,
+    pub dry_run: bool,
+}
+
+/// Receives an [`AuditEntry`] for every tool the server runs. Sinks report
+/// their own failures; a broken audit log does not fail the call.
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    /// The `arguments_hash` to record for a call made with `arguments`.
+    fn hash_arguments(&self, arguments: Option<&serde_json::Value>) -> String;
+
+    async fn record(&self, entry: AuditEntry);
+}
+
+/// The secret an audit log's hashes are keyed with. Without it, hashes of
+/// guessable arguments cannot be brute-forced and an edited log cannot be
+/// re-chained.
+#[derive(Clone)]
+pub struct AuditKey(hmac::Key);
+
+impl AuditKey {
+    #[must_use]
+    pub fn new(secret: &[u8]) -> Self {
+        Self(hmac::Key::new(hmac::HMAC_SHA256, secret))
+    }
+
+    /// Hex HMAC-SHA256 of the tool arguments, ignoring `_meta`, which
+    /// carries per-call details such as credentials rather than input.
+    /// `serde_json` sorts object keys, so equal arguments hash equally.
+    #[must_use]
+    pub fn hash_arguments(&self, arguments: Option<&serde_json::Value>) -> String {
+        self.tag(canonical_arguments(arguments).as_bytes())
+    }
+
+    /// The chain value for `line`, the serialized entry, following
+    /// `previous`.
+    fn chain(&self, previous: &str, line: &str) -> String {
+        self.tag(format!("{previous}\n{line}").as_bytes())
+    }
+
+    fn tag(&self, bytes: &[u8]) -> String {
+        hex(hmac::sign(&self.0, bytes).as_ref())
+    }
+}
+
+impl std::fmt::Debug for AuditKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditKey").finish_non_exhaustive()
+    }
+}
+
+/// Unkeyed SHA-256 of the tool arguments, for telling calls apart in
+/// memory. Never write it anywhere an attacker could read it; use
+/// [`AuditKey::hash_arguments`] for that.
+pub(crate) fn digest_arguments(arguments: Option<&serde_json::Value>) -> String {
+    hex(digest(&SHA256, canonical_arguments(arguments).as_bytes()).as_ref())
+}
+
+fn canonical_arguments(arguments: Option<&serde_json::Value>) -> String {
+    let arguments = match arguments {
+        Some(serde_json::Value::Object(fields)) => {
+            let mut fields = fields.clone();
+            fields.remove("_meta");
+            serde_json::Value::Object(fields)
+        }
+        Some(other) => other.clone(),
+        None => serde_json::Value::Null,
+    };
+    arguments.to_string()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+/// A line of a [`JsonlAuditSink`] file.
+#[derive(Debug, Serialize, Deserialize)]
+struct ChainedEntry {
+    entry: AuditEntry,
+    chain: String,
+}
+
+/// Appends entries to a JSONL file. Each line carries a `chain` HMAC over
+/// the entry and the previous line's chain, so editing, reordering or
+/// removing any but the final lines is caught by [`JsonlAuditSink::verify`]
+/// unless whoever changed the file also holds the [`AuditKey`]. Truncating
+/// trailing lines leaves a valid chain, so compare the count it returns
+/// against one kept elsewhere if that matters.
+pub struct JsonlAuditSink {
+    key: AuditKey,
+    state: Mutex<(tokio::fs::File, String)>,
+}
+
+impl JsonlAuditSink {
+    /// Appends to `path`, continuing the chain of any entries already there,
+    /// with every hash keyed by `key`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if the file cannot be read or opened, or
+    /// a protocol error if its last line is not an audit entry.
+    pub async fn open(path: impl AsRef<Path>, key: AuditKey) -> Result<Self> {
+        let path = path.as_ref();
+        let existing = match tokio::fs::read_to_string(path).await {
+            Ok(existing) => existing,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(PmcpError::Transport(e.to_string())),
+        };
+        let last_chain = match existing.lines().rev().find(|line| !line.trim().is_empty()) {
+            Some(line) => parse_line(line)?.chain,
+            None => GENESIS.to_string(),
+        };
+
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .map_err(|e| PmcpError::Transport(e.to_string()))?;
+
+        Ok(Self {
+            key,
+            state: Mutex::new((file, last_chain)),
+        })
+    }
+
+    /// Checks every line's chain against `key` and returns how many entries
+    /// the file holds.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if `path` cannot be read, or a protocol
+    /// error naming the first line that is malformed or breaks the chain.
+    pub fn verify(path: impl AsRef<Path>, key: &AuditKey) -> Result<usize> {
+        let jsonl =
+            std::fs::read_to_string(path).map_err(|e| PmcpError::Transport(e.to_string()))?;
+        let mut previous = GENESIS.to_string();
+        let mut entries = 0;
+
+        for (index, line) in jsonl.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let chained = parse_line(line)?;
+            let entry = serde_json::to_string(&chained.entry)
+                .map_err(|e| PmcpError::Protocol(e.to_string()))?;
+            if chained.chain != key.chain(&previous, &entry) {
+                return Err(PmcpError::Protocol(format!(
+                    "Audit log line {} breaks the chain",
+                    index + 1
+                )));
+            }
+            previous = chained.chain;
+            entries += 1;
+        }
+
+        Ok(entries)
+    }
+
+    async fn append(&self, entry: AuditEntry) -> Result<()> {
+        let serialized =
+            serde_json::to_string(&entry).map_err(|e| PmcpError::Protocol(e.to_string()))?;
+        let mut state = self.state.lock().await;
+        let (file, previous) = &mut *state;
+
+        let chained = ChainedEntry {
+            chain: self.key.chain(previous, &serialized),
+            entry,
+        };
+        let mut line =
+            serde_json::to_vec(&chained).map_err(|e| PmcpError::Protocol(e.to_string()))?;
+        line.push(b'\n');
+
+        file.write_all(&line)
+            .await
+            .map_err(|e| PmcpError::Transport(e.to_string()))?;
+        file.flush()
+            .await
+            .map_err(|e| PmcpError::Transport(e.to_string()))?;
+        *previous = chained.chain;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AuditSink for JsonlAuditSink {
+    fn hash_arguments(&self, arguments: Option<&serde_json::Value>) -> String {
+        self.key.hash_arguments(arguments)
+    }
+
+    async fn record(&self, entry: AuditEntry) {
+        if let Err(e) = self.append(entry).await {
+            warn!(error = %e, "Failed to write audit entry");
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Result<ChainedEntry> {
+    serde_json::from_str(line).map_err(|e| PmcpError::Protocol(format!("Audit log entry: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn key() -> AuditKey {
+        AuditKey::new(b"audit-test-secret")
+    }
+
+    fn entry(tool: &str) -> AuditEntry {
+        AuditEntry {
+            timestamp_ms: 1_700_000_000_000,
+            method: "tools/call".to_string(),
+            tool: tool.to_string(),
+            arguments_hash: key().hash_arguments(Some(&json!({ "a": 1 }))),
+            outcome: AuditOutcome::Ok,
+            duration_ms: 3,
+            dry_run: false,
+        }
+    }
+
+    #[test]
+    fn test_argument_hash_ignores_meta_and_key_order() {
+        let key = key();
+        let arguments = json!({ "a": 1, "b": "secret" });
+        let plain = key.hash_arguments(Some(&arguments));
+        let reordered = key.hash_arguments(Some(&json!({
+            "b": "secret",
+            "a": 1,
+            "_meta": { "auth": "token" },
+        })));
+
+        assert_eq!(plain, reordered);
+        assert_eq!(plain.len(), 64);
+        assert!(!plain.contains("secret"));
+        assert_ne!(plain, key.hash_arguments(None));
+        assert_ne!(plain, digest_arguments(Some(&arguments)));
+        assert_ne!(
+            plain,
+            AuditKey::new(b"another secret").hash_arguments(Some(&arguments))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_jsonl_sink_chains_entries_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+
+        JsonlAuditSink::open(&path, key())
+            .await
+            .unwrap()
+            .record(entry("calculator"))
+            .await;
+        JsonlAuditSink::open(&path, key())
+            .await
+            .unwrap()
+            .record(entry("extract_files"))
+            .await;
+        assert_eq!(JsonlAuditSink::verify(&path, &key()).unwrap(), 2);
+        assert!(JsonlAuditSink::verify(&path, &AuditKey::new(b"guess")).is_err());
+
+        let tampered = std::fs::read_to_string(&path)
+            .unwrap()
+            .replace("extract_files", "deep_analysis");
+        std::fs::write(&path, tampered).unwrap();
+        assert!(JsonlAuditSink::verify(&path, &key()).is_err());
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::{BTreeSet, HashMap};
+
+/// A permission a tool can require and a credential can grant, e.g.
+/// `analysis` for the expensive analysis tools.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Scope(String);
+
+impl Scope {
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self(name.to_string())
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Scope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Which scopes each tool requires and which scopes each credential grants.
+/// Callers present their credential as `params._meta.auth`; tools with no
+/// required scopes are open to everyone.
+#[derive(Debug, Clone, Default)]
+pub struct ScopePolicy {
+    required: HashMap<String, BTreeSet<Scope>>,
+    grants: HashMap<String, BTreeSet<Scope>>,
+}
+
+impl ScopePolicy {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn require(&mut self, tool: &str, scope: Scope) {
+        self.required
+            .entry(tool.to_string())
+            .or_default()
+            .insert(scope);
+    }
+
+    pub fn grant(&mut self, credential: &str, scope: Scope) {
+        self.grants
+            .entry(credential.to_string())
+            .or_default()
+            .insert(scope);
+    }
+
+    /// Every scope `tool` requires, in sorted order.
+    #[must_use]
+    pub fn required(&self, tool: &str) -> Vec<Scope> {
+        self.required
+            .get(tool)
+            .map(|scopes| scopes.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The scopes `tool` requires that the credential in `params` does not
+    /// grant, in sorted order. Empty when the call is allowed.
+    #[must_use]
+    pub fn missing(&self, tool: &str, params: Option<&serde_json::Value>) -> Vec<Scope> {
+        let Some(required) = self.required.get(tool) else {
+            return Vec::new();
+        };
+        let granted = params
+            .and_then(|params| params.get("_meta")?.get("auth")?.as_str())
+            .and_then(|credential| self.grants.get(credential));
+
+        required
+            .iter()
+            .filter(|scope| granted.is_none_or(|granted| !granted.contains(*scope)))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_missing_scopes_depend_on_credential() {
+        let mut policy = ScopePolicy::new();
+        policy.require("deep_analysis", Scope::new("analysis"));
+        policy.require("deep_analysis", Scope::new("billing"));
+        policy.grant("analyst-token", Scope::new("analysis"));
+
+        let anonymous = policy.missing("deep_analysis", None);
+        assert_eq!(anonymous, [Scope::new("analysis"), Scope::new("billing")]);
+
+        let params = json!({ "_meta": { "auth": "analyst-token" } });
+        let analyst = policy.missing("deep_analysis", Some(&params));
+        assert_eq!(analyst, [Scope::new("billing")]);
+
+        assert!(policy.missing("calculator", None).is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{PmcpError, Result};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{error, info};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    /// Calls are rejected until the cooldown has passed.
+    Open,
+    /// The cooldown has passed; the next call is a trial that decides
+    /// whether the circuit closes again or reopens.
+    HalfOpen,
+}
+
+/// Stops calling a failing dependency after `failure_threshold` consecutive
+/// failures, then lets a single trial call through once `cooldown` has
+/// elapsed.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// A threshold of zero is treated as one.
+    #[must_use]
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            failures: 0,
+            opened_at: None,
+        }
+    }
+
+    #[must_use]
+    pub fn state(&self) -> CircuitState {
+        match self.opened_at {
+            None => CircuitState::Closed,
+            Some(opened) if opened.elapsed() >= self.cooldown => CircuitState::HalfOpen,
+            Some(_) => CircuitState::Open,
+        }
+    }
+
+    #[must_use]
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+
+    /// Runs `f` unless the circuit is open, recording its outcome.
+    ///
+    /// # Errors
+    ///
+    /// Returns a tool error without calling `f` while the circuit is open,
+    /// otherwise whatever `f` returns.
+    pub async fn wrap<F, Fut, T>(&mut self, f: F) -> Result<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        if let Some(opened) = self.opened_at {
+            let left = self.cooldown.saturating_sub(opened.elapsed());
+            if !left.is_zero() {
+                return Err(PmcpError::Tool(format!(
+                    "Circuit breaker open; retry in {left:?}"
+                )));
+            }
+        }
+
+        let outcome = f().await;
+        match &outcome {
+            Ok(_) => self.record_success(),
+            Err(_) => self.record_failure(),
+        }
+        outcome
+    }
+
+    fn record_success(&mut self) {
+        if self.opened_at.take().is_some() {
+            info!("Circuit breaker closed after successful trial call");
+        }
+        self.failures = 0;
+    }
+
+    fn record_failure(&mut self) {
+        self.failures = self.failures.saturating_add(1);
+        if self.opened_at.is_some() {
+            // A failed trial call reopens the circuit for another cooldown.
+            self.opened_at = Some(Instant::now());
+        } else if self.failures >= self.failure_threshold {
+            error!("Circuit breaker opened after {} failures", self.failures);
+            self.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn fail() -> Result<&'static str> {
+        Err(PmcpError::Tool("unavailable".to_string()))
+    }
+
+    async fn succeed() -> Result<&'static str> {
+        Ok("ok")
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_opens_after_threshold_and_rejects() {
+        let mut breaker = CircuitBreaker::new(3, Duration::from_secs(10));
+
+        for _ in 0..2 {
+            assert!(breaker.wrap(fail).await.is_err());
+            assert_eq!(breaker.state(), CircuitState::Closed);
+        }
+        assert!(breaker.wrap(fail).await.is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        let mut called = false;
+        let rejected = breaker
+            .wrap(|| {
+                called = true;
+                succeed()
+            })
+            .await;
+        assert!(rejected.is_err());
+        assert!(!called);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_recovers_through_half_open() {
+        let mut breaker = CircuitBreaker::new(1, Duration::from_secs(10));
+        assert!(breaker.wrap(fail).await.is_err());
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+        assert!(breaker.wrap(fail).await.is_err());
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert_eq!(breaker.wrap(succeed).await.unwrap(), "ok");
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert_eq!(breaker.failures(), 0);
+    }
+}
@@ -0,0 +1,492 @@
+use crate::protocol::JSONRPC_VERSION;
+use crate::transport::Transport;
+use crate::{Notification, PmcpError, Request, Response, Result};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::sync::{mpsc, oneshot};
+use tracing::warn;
+
+/// How an [`IdGenerator`] derives request ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IdMode {
+    /// `1`, `2`, `3`, ... as JSON numbers.
+    #[default]
+    Monotonic,
+    /// A hex string hashed from the method, params and sequence number, so
+    /// the same request sequence gets the same ids in every run, including
+    /// replays of a recorded session.
+    ContentHash,
+}
+
+/// Hands out request ids. Both modes count from one per generator, so ids
+/// depend only on the order requests are made in.
+#[derive(Debug, Default)]
+pub struct IdGenerator {
+    mode: IdMode,
+    sequence: AtomicU64,
+}
+
+impl IdGenerator {
+    #[must_use]
+    pub fn new(mode: IdMode) -> Self {
+        Self {
+            mode,
+            sequence: AtomicU64::new(0),
+        }
+    }
+
+    /// The id for the next request to `method` with `params`.
+    #[must_use]
+    pub fn next(&self, method: &str, params: Option<&serde_json::Value>) -> serde_json::Value {
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed) + 1;
+        match self.mode {
+            IdMode::Monotonic => serde_json::json!(sequence),
+            IdMode::ContentHash => {
+                // serde_json sorts object keys, so equal params serialize
+                // identically.
+                let params = params.map(ToString::to_string).unwrap_or_default();
+                let hash = [
+                    method.as_bytes(),
+                    params.as_bytes(),
+                    &sequence.to_be_bytes(),
+                ]
+                .iter()
+                .fold(FNV_OFFSET_BASIS, |hash, part| {
+                    fnv1a(fnv1a(hash, part), &[0])
+                });
+                serde_json::json!(format!("{hash:016x}"))
+            }
+        }
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// FNV-1a, chosen over `std`'s hasher because its output is fixed across
+/// Rust releases and ids end up in recordings.
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Matches responses to the requests that produced them, whatever order they
+/// arrive in. The sending side calls [`PendingRequests::start`] and awaits
+/// the returned future; the reading side hands every incoming response to
+/// [`PendingRequests::resolve`].
+#[derive(Debug, Default)]
+pub struct PendingRequests {
+    ids: IdGenerator,
+    waiting: Mutex<HashMap<String, oneshot::Sender<Response>>>,
+}
+
+/// Resolves to the response for one outstanding request, or to a transport
+/// error if the [`PendingRequests`] it came from is dropped first.
+#[derive(Debug)]
+pub struct PendingResponse {
+    rx: oneshot::Receiver<Response>,
+}
+
+impl PendingRequests {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assigns ids with `ids` instead of counting up from one.
+    #[must_use]
+    pub fn with_ids(ids: IdGenerator) -> Self {
+        Self {
+            ids,
+            waiting: Mutex::default(),
+        }
+    }
+
+    /// Builds a request with the next free id and starts tracking it.
+    #[must_use]
+    pub fn start(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> (Request, PendingResponse) {
+        let mut request = Request {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            method: method.to_string(),
+            params,
+            id: None,
+        };
+
+        loop {
+            request.id = Some(self.ids.next(method, request.params.as_ref()));
+
+            // Only collides if the caller also tracked this id by hand.
+            if let Ok(pending) = self.track(&request) {
+                return (request, pending);
+            }
+        }
+    }
+
+    /// Tracks a request built by the caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns a protocol error if the request has no id or its id is
+    /// already outstanding.
+    pub fn track(&self, request: &Request) -> Result<PendingResponse> {
+        let id = request.id.as_ref().ok_or_else(|| {
+            PmcpError::Protocol(format!("Request '{}' has no id to track", request.method))
+        })?;
+
+        let mut waiting = self.lock();
+        let key = id.to_string();
+        if waiting.contains_key(&key) {
+            return Err(PmcpError::Protocol(format!(
+                "Request id {key} is already pending"
+            )));
+        }
+
+        let (tx, rx) = oneshot::channel();
+        waiting.insert(key, tx);
+        Ok(PendingResponse { rx })
+    }
+
+    /// Delivers `response` to the request waiting on its id.
+    ///
+    /// # Errors
+    ///
+    /// Returns a protocol error if the response has no id or nothing is
+    /// waiting on it, including a second response for an id that has
+    /// already been resolved.
+    pub fn resolve(&self, response: Response) -> Result<()> {
+        let key = response
+            .id
+            .as_ref()
+            .map(ToString::to_string)
+            .ok_or_else(|| PmcpError::Protocol("Response has no id".to_string()))?;
+
+        let tx = self
+            .lock()
+            .remove(&key)
+            .ok_or_else(|| PmcpError::Protocol(format!("No pending request with id {key}")))?;
+
+        // The caller may have stopped waiting; that is not the server's fault.
+        let _ = tx.send(response);
+        Ok(())
+    }
+
+    /// Fails every outstanding request, e.g. after the connection drops.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, oneshot::Sender<Response>>> {
+        self.waiting
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Future for PendingResponse {
+    type Output = Result<Response>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.rx).poll(cx).map(|received| {
+            received.map_err(|_| {
+                PmcpError::Transport("Connection closed before a response arrived".to_string())
+            })
+        })
+    }
+}
+
+/// Calls an MCP server over any [`Transport`]. A background task owns the
+/// transport, so calls may be issued concurrently through a shared `&Client`.
+pub struct Client {
+    pending: Arc<PendingRequests>,
+    outgoing: mpsc::UnboundedSender<Outgoing>,
+}
+
+enum Outgoing {
+    Request(Request),
+    Notification(Notification),
+}
+
+impl Client {
+    /// Starts the connection task on the current tokio runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a tokio runtime.
+    #[must_use]
+    pub fn new<T: Transport + 'static>(transport: T) -> Self {
+        Self::with_ids(transport, IdGenerator::default())
+    }
+
+    /// Like [`Client::new`], assigning request ids with `ids`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a tokio runtime.
+    #[must_use]
+    pub fn with_ids<T: Transport + 'static>(transport: T, ids: IdGenerator) -> Self {
+        let pending = Arc::new(PendingRequests::with_ids(ids));
+        let (outgoing, queue) = mpsc::unbounded_channel();
+        tokio::spawn(run_connection(transport, queue, Arc::clone(&pending)));
+
+        Self { pending, outgoing }
+    }
+
+    /// Sends a request and waits for its result.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PmcpError::JsonRpc`] if the server answers with an error,
+    /// or a transport error if the connection closes first.
+    pub async fn call(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value> {
+        let (request, response) = self.pending.start(method, params);
+        if self.outgoing.send(Outgoing::Request(request)).is_err() {
+            self.pending.clear();
+        }
+
+        let response = response.await?;
+        match response.error {
+            Some(error) => Err(PmcpError::JsonRpc {
+                code: error.code,
+                message: error.message,
+            }),
+            None => Ok(response.result.unwrap_or(serde_json::Value::Null)),
+        }
+    }
+
+    /// Every tool the server offers, following `nextCursor` until the last
+    /// page.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error from [`Client::call`], or a protocol error
+    /// if a page has no `tools` array.
+    pub async fn list_tools(&self) -> Result<Vec<serde_json::Value>> {
+        let mut tools = Vec::new();
+        let mut cursor = None;
+
+        loop {
+            let params = cursor.map(|cursor| serde_json::json!({ "cursor": cursor }));
+            let mut page = self.call("tools/list", params).await?;
+            let serde_json::Value::Array(listed) = page["tools"].take() else {
+                return Err(PmcpError::Protocol(
+                    "tools/list result has no 'tools' array".to_string(),
+                ));
+            };
+            tools.extend(listed);
+
+            match page.get_mut("nextCursor").map(serde_json::Value::take) {
+                Some(next) if !next.is_null() => cursor = Some(next),
+                _ => return Ok(tools),
+            }
+        }
+    }
+
+    /// Sends a notification; the server never answers these.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if the connection has closed.
+    pub fn notify(&self, method: &str, params: Option<serde_json::Value>) -> Result<()> {
+        let notification = Notification {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            method: method.to_string(),
+            params,
+        };
+
+        self.outgoing
+            .send(Outgoing::Notification(notification))
+            .map_err(|_| PmcpError::Transport("Client connection closed".to_string()))
+    }
+}
+
+async fn run_connection<T: Transport>(
+    mut transport: T,
+    mut queue: mpsc::UnboundedReceiver<Outgoing>,
+    pending: Arc<PendingRequests>,
+) {
+    loop {
+        tokio::select! {
+            message = queue.recv() => {
+                let sent = match message {
+                    Some(Outgoing::Request(request)) => transport.send_request(request).await,
+                    Some(Outgoing::Notification(notification)) => {
+                        transport.send_notification(notification).await
+                    }
+                    None => break,
+                };
+                if let Err(e) = sent {
+                    warn!("Client send failed: {e}");
+                    break;
+                }
+            }
+            received = transport.receive_response() => match received {
+                Ok(response) => {
+                    if let Err(e) = pending.resolve(response) {
+                        warn!("Dropping unmatched response: {e}");
+                    }
+                }
+                Err(PmcpError::Protocol(e)) => warn!("Ignoring malformed message: {e}"),
+                // Requests sent on the old connection will never be answered.
+                Err(PmcpError::Reconnecting(_)) => pending.clear(),
+                Err(e) => {
+                    warn!("Client connection closed: {e}");
+                    break;
+                }
+            },
+        }
+    }
+
+    pending.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn reply(request: &Request) -> Response {
+        Response {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            result: Some(json!({ "method": request.method })),
+            error: None,
+            id: request.id.clone(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_out_of_order_responses_reach_their_requests() {
+        let pending = PendingRequests::new();
+        let started: Vec<_> = ["first", "second", "third"]
+            .into_iter()
+            .map(|method| pending.start(method, None))
+            .collect();
+        assert_eq!(pending.len(), 3);
+
+        for (request, _) in started.iter().rev() {
+            pending.resolve(reply(request)).unwrap();
+        }
+        assert!(pending.is_empty());
+
+        for (request, future) in started {
+            let response = future.await.unwrap();
+            assert_eq!(response.id, request.id);
+            assert_eq!(response.result.unwrap()["method"], request.method);
+        }
+    }
+
+    #[test]
+    fn test_content_hash_ids_repeat_across_replays() {
+        let sequence = [
+            ("tools/list", None),
+            (
+                "tools/call",
+                Some(json!({ "name": "calculator", "arguments": { "a": 1 } })),
+            ),
+            (
+                "tools/call",
+                Some(json!({ "name": "calculator", "arguments": { "a": 1 } })),
+            ),
+        ];
+        let run = || {
+            let ids = IdGenerator::new(IdMode::ContentHash);
+            sequence
+                .iter()
+                .map(|(method, params)| ids.next(method, params.as_ref()))
+                .collect::<Vec<_>>()
+        };
+
+        let recorded = run();
+        assert_eq!(run(), recorded);
+        assert!(recorded.iter().all(serde_json::Value::is_string));
+        assert_ne!(recorded[1], recorded[2]);
+
+        let monotonic = IdGenerator::default();
+        assert_eq!(monotonic.next("ping", None), json!(1));
+        assert_eq!(monotonic.next("ping", None), json!(2));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_and_duplicate_ids_are_rejected() {
+        let pending = PendingRequests::new();
+        let (request, _future) = pending.start("ping", None);
+
+        assert!(pending.track(&request).is_err());
+
+        pending.resolve(reply(&request)).unwrap();
+        assert!(pending.resolve(reply(&request)).is_err());
+    }
+
+    struct AddHandler;
+
+    #[async_trait::async_trait]
+    impl crate::server::ToolHandler for AddHandler {
+        async fn handle(&self, params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+            let params = params.unwrap_or_default();
+            let a = params["a"].as_f64().unwrap_or_default();
+            let b = params["b"].as_f64().unwrap_or_default();
+            Ok(json!(a + b))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_calls_server_over_channels() {
+        let server = crate::server::ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), AddHandler)
+            .with_handler(crate::tools::deep_analysis_tool(), AddHandler)
+            .with_tools_page_size(1)
+            .build()
+            .unwrap();
+        let (client_end, server_end) = crate::transport::InMemoryTransport::pair();
+        let serving = tokio::spawn(async move { server.serve(server_end).await });
+
+        let client = Client::new(client_end);
+        let sum = client
+            .call(
+                "calculator",
+                Some(json!({"operation": "add", "a": 2, "b": 3})),
+            )
+            .await
+            .unwrap();
+        assert_eq!(sum, json!(5.0));
+
+        let tools = client.list_tools().await.unwrap();
+        let names: Vec<_> = tools.iter().map(|tool| &tool["name"]).collect();
+        assert_eq!(names, [&json!("calculator"), &json!("deep_analysis")]);
+
+        let missing = client.call("no_such_tool", None).await;
+        assert!(matches!(
+            missing,
+            Err(PmcpError::JsonRpc {
+                code: crate::protocol::ERROR_METHOD_NOT_FOUND,
+                ..
+            })
+        ));
+
+        client.notify("notifications/initialized", None).unwrap();
+        drop(client);
+        serving.await.unwrap().unwrap();
+    }
+}
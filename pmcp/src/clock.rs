@@ -0,0 +1,135 @@
+use async_trait::async_trait;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Time for anything that measures, waits or timestamps, so tests and
+/// replays can swap in a [`MockClock`] instead of reading the real clock.
+#[async_trait]
+pub trait Clock: Debug + Send + Sync {
+    /// Monotonic time, for measuring intervals.
+    fn now(&self) -> Instant;
+
+    /// Wall-clock time in epoch millis, for deadlines and timestamps that
+    /// are compared with other processes.
+    fn now_ms(&self) -> u64;
+
+    async fn sleep(&self, duration: Duration);
+}
+
+#[async_trait]
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+
+    fn now_ms(&self) -> u64 {
+        (**self).now_ms()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        (**self).sleep(duration).await;
+    }
+}
+
+/// [`Instant::now`], [`SystemTime::now`] and [`tokio::time::sleep`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn now_ms(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| u64::try_from(now.as_millis()).unwrap_or(u64::MAX))
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+/// A clock that only moves when told to. Clones share the same time, so a
+/// test can keep one and hand another to the code under test. Sleeping
+/// advances the clock by the requested duration and returns immediately.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    start: Instant,
+    start_ms: u64,
+    elapsed: Arc<Mutex<Duration>>,
+}
+
+impl MockClock {
+    /// A clock whose wall time starts at the epoch.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::at(0)
+    }
+
+    /// A clock whose wall time starts at `epoch_ms`.
+    #[must_use]
+    pub fn at(epoch_ms: u64) -> Self {
+        Self {
+            start: Instant::now(),
+            start_ms: epoch_ms,
+            elapsed: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap_or_else(PoisonError::into_inner) += duration;
+    }
+
+    /// Total time advanced since the clock was created.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn now_ms(&self) -> u64 {
+        let elapsed_ms = u64::try_from(self.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.start_ms.saturating_add(elapsed_ms)
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mock_clock_moves_only_when_advanced() {
+        let clock = MockClock::at(1_700_000_000_000);
+        let shared = clock.clone();
+        let start = clock.now();
+
+        assert_eq!(clock.now(), start);
+
+        shared.advance(Duration::from_secs(5));
+        clock.sleep(Duration::from_secs(1)).await;
+
+        assert_eq!(clock.now() - start, Duration::from_secs(6));
+        assert_eq!(shared.elapsed(), Duration::from_secs(6));
+        assert_eq!(clock.now_ms(), 1_700_000_006_000);
+    }
+}
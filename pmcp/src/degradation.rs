@@ -0,0 +1,156 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How much work the server is willing to take on, from the recent error
+/// rate of its tool calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ServiceLevel {
+    Full,
+    /// Expensive tools are shed; cheap ones are still served.
+    Degraded,
+    /// Every tool call is shed.
+    Minimal,
+}
+
+/// When to step down a [`ServiceLevel`]. Rates are fractions of failed calls
+/// in the window; no decision is made until `min_samples` calls were seen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DegradationThresholds {
+    pub window: Duration,
+    pub min_samples: usize,
+    pub degraded_error_rate: f64,
+    pub minimal_error_rate: f64,
+}
+
+impl Default for DegradationThresholds {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_secs(30),
+            min_samples: 10,
+            degraded_error_rate: 0.25,
+            minimal_error_rate: 0.5,
+        }
+    }
+}
+
+/// Sliding window of tool call outcomes.
+#[derive(Debug)]
+pub struct ServiceLevelTracker {
+    thresholds: DegradationThresholds,
+    samples: Mutex<VecDeque<(Instant, bool)>>,
+}
+
+impl ServiceLevelTracker {
+    #[must_use]
+    pub fn new(thresholds: DegradationThresholds) -> Self {
+        Self {
+            thresholds,
+            samples: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    #[must_use]
+    pub fn thresholds(&self) -> DegradationThresholds {
+        self.thresholds
+    }
+
+    pub fn record(&self, failed: bool) {
+        self.record_at(failed, Instant::now());
+    }
+
+    /// Fraction of calls in the window that failed, or `None` below
+    /// `min_samples`.
+    #[must_use]
+    pub fn error_rate(&self) -> Option<f64> {
+        self.error_rate_at(Instant::now())
+    }
+
+    #[must_use]
+    pub fn level(&self) -> ServiceLevel {
+        self.level_at(Instant::now())
+    }
+
+    fn record_at(&self, failed: bool, now: Instant) {
+        let mut samples = self.lock_pruned(now);
+        samples.push_back((now, failed));
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn error_rate_at(&self, now: Instant) -> Option<f64> {
+        let samples = self.lock_pruned(now);
+        if samples.is_empty() || samples.len() < self.thresholds.min_samples {
+            return None;
+        }
+
+        let failed = samples.iter().filter(|(_, failed)| *failed).count();
+        Some(failed as f64 / samples.len() as f64)
+    }
+
+    fn level_at(&self, now: Instant) -> ServiceLevel {
+        match self.error_rate_at(now) {
+            Some(rate) if rate >= self.thresholds.minimal_error_rate => ServiceLevel::Minimal,
+            Some(rate) if rate >= self.thresholds.degraded_error_rate => ServiceLevel::Degraded,
+            _ => ServiceLevel::Full,
+        }
+    }
+
+    fn lock_pruned(&self, now: Instant) -> std::sync::MutexGuard<'_, VecDeque<(Instant, bool)>> {
+        let mut samples = self
+            .samples
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        while samples
+            .front()
+            .is_some_and(|(at, _)| now.saturating_duration_since(*at) >= self.thresholds.window)
+        {
+            samples.pop_front();
+        }
+        samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> ServiceLevelTracker {
+        ServiceLevelTracker::new(DegradationThresholds {
+            window: Duration::from_secs(10),
+            min_samples: 4,
+            ..DegradationThresholds::default()
+        })
+    }
+
+    #[test]
+    fn test_levels_follow_error_rate() {
+        let tracker = tracker();
+        let start = Instant::now();
+
+        for failed in [false, false, false, true] {
+            tracker.record_at(failed, start);
+        }
+        assert_eq!(tracker.level_at(start), ServiceLevel::Degraded);
+
+        for _ in 0..4 {
+            tracker.record_at(true, start);
+        }
+        assert_eq!(tracker.level_at(start), ServiceLevel::Minimal);
+    }
+
+    #[test]
+    fn test_old_samples_leave_the_window() {
+        let tracker = tracker();
+        let start = Instant::now();
+
+        for _ in 0..4 {
+            tracker.record_at(true, start);
+        }
+        assert_eq!(tracker.level_at(start), ServiceLevel::Minimal);
+
+        let later = start + Duration::from_secs(10);
+        assert_eq!(tracker.error_rate_at(later), None);
+        assert_eq!(tracker.level_at(later), ServiceLevel::Full);
+    }
+}
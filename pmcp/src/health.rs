@@ -0,0 +1,72 @@
+/// Lifecycle of a [`crate::server::Server`] as seen by health probes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthState {
+    /// Waiting for the client's `initialize` request.
+    Starting,
+    Ready,
+    /// [`crate::server::Server::shutdown`] was called; in-flight work is finishing.
+    Draining,
+}
+
+/// Result of a single probe, shaped so an HTTP wrapper can map it directly
+/// onto `/healthz`, `/ready` or `/startup`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthStatus {
+    pub ok: bool,
+    pub detail: Option<String>,
+}
+
+impl HealthStatus {
+    #[must_use]
+    pub fn ok() -> Self {
+        Self {
+            ok: true,
+            detail: None,
+        }
+    }
+
+    #[must_use]
+    pub fn failing(detail: &str) -> Self {
+        Self {
+            ok: false,
+            detail: Some(detail.to_string()),
+        }
+    }
+
+    /// `200` when the probe passes, `503` otherwise.
+    #[must_use]
+    pub fn http_status(&self) -> u16 {
+        if self.ok {
+            200
+        } else {
+            503
+        }
+    }
+}
+
+impl HealthState {
+    /// The process is alive for as long as it can answer at all.
+    #[must_use]
+    pub fn liveness(self) -> HealthStatus {
+        HealthStatus::ok()
+    }
+
+    /// Passes only between `initialize` and the start of shutdown.
+    #[must_use]
+    pub fn readiness(self) -> HealthStatus {
+        match self {
+            Self::Starting => HealthStatus::failing("waiting for initialize"),
+            Self::Ready => HealthStatus::ok(),
+            Self::Draining => HealthStatus::failing("draining"),
+        }
+    }
+
+    /// Passes once `initialize` has completed, and keeps passing afterwards.
+    #[must_use]
+    pub fn startup(self) -> HealthStatus {
+        match self {
+            Self::Starting => HealthStatus::failing("waiting for initialize"),
+            Self::Ready | Self::Draining => HealthStatus::ok(),
+        }
+    }
+}
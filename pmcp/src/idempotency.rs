@@ -0,0 +1,192 @@
+use crate::audit::digest_arguments;
+use crate::{Request, Response};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// What makes two requests the same call: who sent them, the tool they
+/// name, their id and a hash of their arguments. Ids alone are not enough,
+/// since every client numbers its requests from the start.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IdempotencyKey {
+    scope: Scope,
+    method: String,
+    id: String,
+    arguments_hash: String,
+}
+
+/// Who a call belongs to: the client's own `params._meta.idempotencyKey`,
+/// which it repeats when it retries over a new connection, or failing that
+/// the session the call arrived on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Scope {
+    Token(String),
+    Session(u64),
+}
+
+impl IdempotencyKey {
+    /// The key for `request` on `session`, or `None` for a notification,
+    /// which has no id to repeat.
+    #[must_use]
+    pub fn new(session: u64, request: &Request) -> Option<Self> {
+        let token = request
+            .params
+            .as_ref()
+            .and_then(|params| params.get("_meta")?.get("idempotencyKey")?.as_str());
+        Some(Self {
+            scope: token.map_or(Scope::Session(session), |token| {
+                Scope::Token(token.to_string())
+            }),
+            method: request.method.clone(),
+            id: request.id.as_ref()?.to_string(),
+            arguments_hash: digest_arguments(request.params.as_ref()),
+        })
+    }
+}
+
+/// Remembers tool responses by [`IdempotencyKey`] so a client retrying a
+/// call gets the original answer instead of a second execution. Retries
+/// over a new connection match only if both attempts carry the same
+/// `params._meta.idempotencyKey`, which should be unguessable since anyone
+/// presenting it gets the stored response. Duplicates that arrive while the
+/// first call is still running are not deduplicated.
+#[derive(Debug)]
+pub struct IdempotencyCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<IdempotencyKey, (Instant, Response)>>,
+}
+
+impl IdempotencyCache {
+    #[must_use]
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[must_use]
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Returns the stored response for `key` if it has not expired.
+    #[must_use]
+    pub fn get(&self, key: &IdempotencyKey) -> Option<Response> {
+        self.get_at(key, Instant::now())
+    }
+
+    /// Stores `response` under `key`, evicting any expired entries.
+    pub fn insert(&self, key: IdempotencyKey, response: Response) {
+        self.insert_at(key, response, Instant::now());
+    }
+
+    fn get_at(&self, key: &IdempotencyKey, now: Instant) -> Option<Response> {
+        let entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        entries
+            .get(key)
+            .filter(|(stored, _)| now.saturating_duration_since(*stored) < self.ttl)
+            .map(|(_, response)| response.clone())
+    }
+
+    fn insert_at(&self, key: IdempotencyKey, response: Response, now: Instant) {
+        let mut entries = self
+            .entries
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        entries.retain(|_, (stored, _)| now.saturating_duration_since(*stored) < self.ttl);
+        entries.insert(key, (now, response));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(result: serde_json::Value) -> Response {
+        Response {
+            jsonrpc: "2.0".to_string(),
+            result: Some(result),
+            error: None,
+            id: Some(json!(1)),
+        }
+    }
+
+    fn key(session: u64, method: &str, id: serde_json::Value) -> IdempotencyKey {
+        IdempotencyKey::new(
+            session,
+            &Request {
+                jsonrpc: "2.0".to_string(),
+                method: method.to_string(),
+                params: Some(json!({ "a": 1 })),
+                id: Some(id),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let start = Instant::now();
+        let cache = IdempotencyCache::new(Duration::from_secs(10));
+        cache.insert_at(
+            key(1, "calculator", json!(1)),
+            response(json!("first")),
+            start,
+        );
+
+        assert_eq!(
+            cache.get_at(
+                &key(1, "calculator", json!(1)),
+                start + Duration::from_secs(9)
+            ),
+            Some(response(json!("first")))
+        );
+        assert_eq!(
+            cache.get_at(
+                &key(1, "calculator", json!(1)),
+                start + Duration::from_secs(10)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_only_the_same_call_on_the_same_session_matches() {
+        let cache = IdempotencyCache::new(Duration::from_secs(10));
+        cache.insert(key(1, "calculator", json!(1)), response(json!("number")));
+
+        assert!(cache.get(&key(1, "calculator", json!(1))).is_some());
+        assert!(cache.get(&key(1, "calculator", json!("1"))).is_none());
+        assert!(cache.get(&key(1, "deep_analysis", json!(1))).is_none());
+        assert!(cache.get(&key(2, "calculator", json!(1))).is_none());
+    }
+
+    #[test]
+    fn test_client_token_matches_across_sessions() {
+        let with_token = |session: u64, token: &str| {
+            IdempotencyKey::new(
+                session,
+                &Request {
+                    jsonrpc: "2.0".to_string(),
+                    method: "calculator".to_string(),
+                    params: Some(json!({ "a": 1, "_meta": { "idempotencyKey": token } })),
+                    id: Some(json!(1)),
+                },
+            )
+            .unwrap()
+        };
+        let cache = IdempotencyCache::new(Duration::from_secs(10));
+        cache.insert(with_token(1, "retry-7f3a"), response(json!("number")));
+
+        assert!(cache.get(&with_token(2, "retry-7f3a")).is_some());
+        assert!(cache.get(&with_token(2, "retry-0000")).is_none());
+        assert!(cache.get(&key(1, "calculator", json!(1))).is_none());
+    }
+}
@@ -4,8 +4,24 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+pub mod audit;
+pub mod auth;
+pub mod circuit_breaker;
+pub mod client;
+pub mod clock;
+pub mod degradation;
+pub mod health;
+pub mod idempotency;
+pub mod logging;
+pub mod metrics;
 pub mod protocol;
+pub mod rate_limit;
+pub mod redact;
+pub mod replay;
+pub mod retry;
+pub mod sandbox;
 pub mod server;
+pub mod tls;
 pub mod tools;
 pub mod transport;
 
@@ -14,6 +30,14 @@ pub enum PmcpError {
     #[error("Transport error: {0}")]
     Transport(String),
 
+    /// The connection was shut down on purpose, by either end. Unlike
+    /// [`PmcpError::Transport`], nothing went wrong.
+    #[error("Connection closed")]
+    Closed,
+
+    #[error("Reconnecting after transport failure: {0}")]
+    Reconnecting(String),
+
     #[error("Protocol error: {0}")]
     Protocol(String),
 
@@ -29,33 +53,67 @@ pub enum PmcpError {
 
 pub type Result<T> = std::result::Result<T, PmcpError>;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Request {
     pub jsonrpc: String,
     pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub params: Option<serde_json::Value>,
+    /// Absent for a notification.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Response {
     pub jsonrpc: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<ErrorObject>,
+    /// Always sent; `null` when the request's id could not be read.
     pub id: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ErrorObject {
     pub code: i32,
     pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Converts an error raised while handling a call. A JSON-RPC code the
+/// handler may not claim, such as [`protocol::ERROR_METHOD_NOT_FOUND`], is
+/// moved to [`protocol::ERROR_REMAPPED`] with the original in
+/// `data.originalCode`; other errors become internal errors.
+impl From<PmcpError> for ErrorObject {
+    fn from(error: PmcpError) -> Self {
+        match error {
+            PmcpError::JsonRpc { code, message } if protocol::is_handler_error_code(code) => Self {
+                code,
+                message,
+                data: None,
+            },
+            PmcpError::JsonRpc { code, message } => Self {
+                code: protocol::ERROR_REMAPPED,
+                message,
+                data: Some(serde_json::json!({ "originalCode": code })),
+            },
+            other => Self {
+                code: protocol::ERROR_INTERNAL,
+                message: other.to_string(),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Notification {
     pub jsonrpc: String,
     pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub params: Option<serde_json::Value>,
 }
 
@@ -70,8 +128,15 @@ pub struct Tool {
 pub struct ServerCapabilities {
     pub tools: Vec<Tool>,
     pub max_request_size: usize,
+    /// Largest serialized tool result sent back; bigger results are
+    /// replaced by an error.
+    pub max_response_size: usize,
+    /// Deepest nesting of arrays and objects accepted in a request.
+    pub max_json_depth: usize,
+    /// Most elements accepted in any one array or object of a request.
+    pub max_json_elements: usize,
     pub supports_batching: bool,
-    pub supports_cancellation: bool,
+    pub supports_compression: bool,
 }
 
 impl Default for ServerCapabilities {
@@ -79,8 +144,11 @@ impl Default for ServerCapabilities {
         Self {
             tools: Vec::new(),
             max_request_size: 10_485_760, // 10MB
+            max_response_size: 10_485_760,
+            max_json_depth: 64,
+            max_json_elements: 10_000,
             supports_batching: true,
-            supports_cancellation: true,
+            supports_compression: false,
         }
     }
 }
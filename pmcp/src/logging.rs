@@ -0,0 +1,41 @@
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::EnvFilter;
+
+/// Builds a subscriber that writes one JSON object per event to stdout,
+/// including the fields of every enclosing span (`method`, `request_id`,
+/// `tool_name`, ...) so aggregators can filter on them. `RUST_LOG` selects
+/// levels and defaults to `info`.
+#[must_use]
+pub fn json_subscriber() -> impl tracing::Subscriber + Send + Sync {
+    tracing_subscriber::fmt()
+        .json()
+        .with_current_span(true)
+        .with_span_list(true)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .finish()
+}
+
+/// Installs [`json_subscriber`] as the global default.
+///
+/// # Errors
+///
+/// Returns a server error if a global subscriber is already set.
+pub fn init_json() -> crate::Result<()> {
+    tracing::subscriber::set_global_default(json_subscriber())
+        .map_err(|e| crate::PmcpError::Server(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_subscriber_is_usable_as_scoped_default() {
+        tracing::subscriber::with_default(json_subscriber(), || {
+            tracing::info!(method = "calculator", "json event");
+        });
+    }
+}
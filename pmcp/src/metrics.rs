@@ -0,0 +1,64 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Live counters for a [`crate::server::Server`], shared by every clone of it.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    active_connections: AtomicUsize,
+}
+
+/// Counts one open connection for as long as it is held. Dropping it,
+/// including during a panic unwind, releases the count.
+#[derive(Debug)]
+pub struct ConnectionGuard {
+    metrics: Arc<Metrics>,
+}
+
+impl Metrics {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn active_connections(&self) -> usize {
+        self.active_connections.load(Ordering::SeqCst)
+    }
+
+    /// Records a newly accepted connection.
+    #[must_use]
+    pub fn connection_opened(self: &Arc<Self>) -> ConnectionGuard {
+        self.active_connections.fetch_add(1, Ordering::SeqCst);
+        ConnectionGuard {
+            metrics: Arc::clone(self),
+        }
+    }
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.metrics
+            .active_connections
+            .fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_guard_releases_on_panic() {
+        let metrics = Arc::new(Metrics::new());
+        let guard = metrics.connection_opened();
+        assert_eq!(metrics.active_connections(), 1);
+
+        let task = tokio::spawn(async move {
+            let _guard = guard;
+            panic!("handler failed");
+        });
+
+        assert!(task.await.unwrap_err().is_panic());
+        assert_eq!(metrics.active_connections(), 0);
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 pub const JSONRPC_VERSION: &str = "2.0";
+/// The MCP revision whose message shapes this crate follows.
+pub const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
 
 pub const ERROR_PARSE: i32 = -32700;
 pub const ERROR_INVALID_REQUEST: i32 = -32600;
@@ -10,15 +12,67 @@ pub const ERROR_INTERNAL: i32 = -32603;
 
 pub const ERROR_SERVER_MIN: i32 = -32099;
 pub const ERROR_SERVER_MAX: i32 = -32000;
+/// Where a handler error carrying a code it may not use is moved to; the
+/// original code is kept in `data.originalCode`.
+pub const ERROR_REMAPPED: i32 = ERROR_SERVER_MIN;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
+pub const ERROR_RATE_LIMITED: i32 = -32000;
+pub const ERROR_DEADLINE_EXCEEDED: i32 = -32000;
+pub const DEADLINE_EXCEEDED_MESSAGE: &str = "Deadline exceeded";
+pub const ERROR_SERVICE_DEGRADED: i32 = -32000;
+pub const SERVICE_DEGRADED_MESSAGE: &str = "Service degraded";
+pub const ERROR_INSUFFICIENT_SCOPE: i32 = -32000;
+pub const INSUFFICIENT_SCOPE_MESSAGE: &str = "Insufficient scope";
+pub const ERROR_SERVER_BUSY: i32 = -32000;
+pub const SERVER_BUSY_MESSAGE: &str = "Server busy";
+pub const ERROR_RESPONSE_TOO_LARGE: i32 = -32000;
+pub const RESPONSE_TOO_LARGE_MESSAGE: &str = "Response too large";
+
+/// Any JSON-RPC message. As on the wire, there is no tag: a message with a
+/// `method` and an `id` is a request, one with only a `method` is a
+/// notification, and one with a `result` or `error` is a response.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
 pub enum Message {
     Request(crate::Request),
     Response(crate::Response),
     Notification(crate::Notification),
 }
 
+impl<'de> Deserialize<'de> for Message {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let message = if is_request(&value) {
+            serde_json::from_value(value).map(Self::Request)
+        } else if is_notification(&value) {
+            serde_json::from_value(value).map(Self::Notification)
+        } else if is_response(&value) {
+            serde_json::from_value(value).map(Self::Response)
+        } else {
+            return Err(D::Error::custom(
+                "expected a JSON-RPC request, notification or response",
+            ));
+        };
+        message.map_err(D::Error::custom)
+    }
+}
+
+/// Whether `code` is in the range JSON-RPC leaves for server-defined errors.
+#[must_use]
+pub fn is_server_error_code(code: i32) -> bool {
+    (ERROR_SERVER_MIN..=ERROR_SERVER_MAX).contains(&code)
+}
+
+/// Whether a tool handler may report `code` as is: a server-defined code,
+/// or one of the standard codes that describe a failed call rather than a
+/// malformed or unroutable request.
+#[must_use]
+pub fn is_handler_error_code(code: i32) -> bool {
+    is_server_error_code(code) || code == ERROR_INVALID_PARAMS || code == ERROR_INTERNAL
+}
+
 #[must_use]
 pub fn is_request(msg: &serde_json::Value) -> bool {
     msg.get("method").is_some() && msg.get("id").is_some()
@@ -33,3 +87,181 @@ pub fn is_notification(msg: &serde_json::Value) -> bool {
 pub fn is_response(msg: &serde_json::Value) -> bool {
     msg.get("result").is_some() || msg.get("error").is_some()
 }
+
+/// Asserts in debug builds that `response` is well-formed JSON-RPC: the
+/// right version, exactly one of `result` and `error`, and an id that is a
+/// number, string or null. Compiles to nothing in release builds.
+///
+/// # Panics
+///
+/// Panics in debug builds if `response` is malformed.
+pub fn validate_response(response: &crate::Response) {
+    debug_assert_eq!(
+        response.jsonrpc, JSONRPC_VERSION,
+        "Response {:?} has the wrong jsonrpc version",
+        response.id
+    );
+    debug_assert!(
+        response.result.is_some() != response.error.is_some(),
+        "Response {:?} must carry exactly one of result and error",
+        response.id
+    );
+    debug_assert!(
+        response.id.as_ref().is_none_or(is_valid_id),
+        "Response id {:?} is not a number, string or null",
+        response.id
+    );
+}
+
+/// Whether `id` is one JSON-RPC allows: a number, string or null.
+#[must_use]
+pub fn is_valid_id(id: &serde_json::Value) -> bool {
+    matches!(
+        id,
+        serde_json::Value::Null | serde_json::Value::Number(_) | serde_json::Value::String(_)
+    )
+}
+
+/// Checks the nesting depth and per-container element count of raw JSON
+/// without parsing it, so a hostile payload is turned away before it can
+/// exhaust the stack or memory. Malformed JSON is left for the parser to
+/// report.
+///
+/// # Errors
+///
+/// Returns a description of the first limit exceeded.
+pub fn check_json_limits(
+    raw: &[u8],
+    max_depth: usize,
+    max_elements: usize,
+) -> std::result::Result<(), String> {
+    // Separators seen so far in each open container.
+    let mut open: Vec<usize> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for &byte in raw {
+        if in_string {
+            match (escaped, byte) {
+                (true, _) => escaped = false,
+                (false, b'\\') => escaped = true,
+                (false, b'"') => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        if byte.is_ascii_whitespace() {
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            b'[' | b'{' => {
+                if open.len() == max_depth {
+                    return Err(format!("JSON nesting exceeds {max_depth} levels"));
+                }
+                open.push(0);
+            }
+            b']' | b'}' => {
+                open.pop();
+            }
+            b',' => {
+                if let Some(separators) = open.last_mut() {
+                    *separators += 1;
+                    if *separators >= max_elements {
+                        return Err(format!(
+                            "JSON array or object exceeds {max_elements} elements"
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Like [`check_json_limits`], for a value that has already been parsed.
+///
+/// # Errors
+///
+/// Returns a description of the first limit exceeded.
+pub fn check_value_limits(
+    value: &serde_json::Value,
+    max_depth: usize,
+    max_elements: usize,
+) -> std::result::Result<(), String> {
+    let mut pending = vec![(value, 0)];
+
+    while let Some((value, depth)) = pending.pop() {
+        let children: Box<dyn Iterator<Item = &serde_json::Value>> = match value {
+            serde_json::Value::Array(items) => Box::new(items.iter()),
+            serde_json::Value::Object(fields) => Box::new(fields.values()),
+            _ => continue,
+        };
+        if depth == max_depth {
+            return Err(format!("JSON nesting exceeds {max_depth} levels"));
+        }
+
+        let mut count = 0;
+        for child in children {
+            count += 1;
+            if count > max_elements {
+                return Err(format!(
+                    "JSON array or object exceeds {max_elements} elements"
+                ));
+            }
+            pending.push((child, depth + 1));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorObject, Response};
+    use serde_json::json;
+
+    fn response() -> Response {
+        Response {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            result: Some(json!({})),
+            error: None,
+            id: Some(json!(1)),
+        }
+    }
+
+    #[test]
+    fn test_server_error_code_range() {
+        assert!(is_server_error_code(ERROR_SERVER_MIN));
+        assert!(is_server_error_code(ERROR_SERVER_MAX));
+        assert!(!is_server_error_code(ERROR_METHOD_NOT_FOUND));
+        assert!(!is_server_error_code(-31_999));
+
+        assert!(is_handler_error_code(ERROR_INVALID_PARAMS));
+        assert!(!is_handler_error_code(ERROR_PARSE));
+        assert!(!is_handler_error_code(42));
+    }
+
+    #[test]
+    fn test_well_formed_response_passes_validation() {
+        validate_response(&response());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "exactly one of result and error")]
+    fn test_response_with_result_and_error_trips_validation() {
+        validate_response(&Response {
+            error: Some(ErrorObject {
+                code: ERROR_INTERNAL,
+                message: "boom".to_string(),
+                data: None,
+            }),
+            ..response()
+        });
+    }
+}
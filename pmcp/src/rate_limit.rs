@@ -0,0 +1,157 @@
+use crate::clock::{Clock, SystemClock};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+struct TokenBucket {
+    per_second: u32,
+    capacity: f64,
+    tokens: f64,
+    refill_per_second: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_second: u32, now: Instant) -> Self {
+        let capacity = f64::from(per_second);
+        Self {
+            per_second,
+            capacity,
+            tokens: capacity,
+            refill_per_second: capacity,
+            last_refill: now,
+        }
+    }
+
+    fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else if self.refill_per_second > 0.0 {
+            Err(Duration::from_secs_f64(
+                (1.0 - self.tokens) / self.refill_per_second,
+            ))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+}
+
+/// Per-method token buckets. Each limited method may burst up to its
+/// per-second allowance and refills continuously; unlisted methods are
+/// never throttled.
+#[derive(Debug)]
+pub struct RateLimiter {
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl RateLimiter {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            buckets: Mutex::new(HashMap::new()),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Refills buckets by `clock` instead of the system clock. Set it before
+    /// any limits, which start full at the time they are set.
+    #[must_use]
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn set_limit(&mut self, method: &str, per_second: u32) {
+        let bucket = TokenBucket::new(per_second, self.clock.now());
+        self.buckets
+            .get_mut()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(method.to_string(), bucket);
+    }
+
+    /// The per-second allowance set for `method`, if it is limited.
+    #[must_use]
+    pub fn limit(&self, method: &str) -> Option<u32> {
+        self.buckets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(method)
+            .map(|bucket| bucket.per_second)
+    }
+
+    /// Takes a token for `method`.
+    ///
+    /// # Errors
+    ///
+    /// Returns how long to wait before the next token is available when the
+    /// method's bucket is empty.
+    pub fn check(&self, method: &str) -> Result<(), Duration> {
+        let mut buckets = self
+            .buckets
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        match buckets.get_mut(method) {
+            Some(bucket) => bucket.try_acquire(self.clock.now()),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2, start);
+
+        assert!(bucket.try_acquire(start).is_ok());
+        assert!(bucket.try_acquire(start).is_ok());
+
+        let retry_after = bucket.try_acquire(start).unwrap_err();
+        assert_eq!(retry_after, Duration::from_millis(500));
+
+        assert!(bucket.try_acquire(start + retry_after).is_ok());
+    }
+
+    #[test]
+    fn test_unlisted_methods_are_unlimited() {
+        let mut limiter = RateLimiter::new();
+        limiter.set_limit("deep_analysis", 1);
+
+        for _ in 0..100 {
+            assert!(limiter.check("calculator").is_ok());
+        }
+    }
+
+    #[test]
+    fn test_limiter_refills_by_injected_clock() {
+        let clock = MockClock::new();
+        let mut limiter = RateLimiter::new().with_clock(clock.clone());
+        limiter.set_limit("deep_analysis", 1);
+
+        assert!(limiter.check("deep_analysis").is_ok());
+        assert_eq!(limiter.check("deep_analysis"), Err(Duration::from_secs(1)));
+
+        clock.advance(Duration::from_secs(1));
+        assert!(limiter.check("deep_analysis").is_ok());
+    }
+}
@@ -0,0 +1,135 @@
+use serde_json::Value;
+
+/// Replaces the value of a redacted field.
+pub const REDACTED: &str = "***";
+
+/// Field names redacted by [`Redactor::default`].
+pub const DEFAULT_PATTERNS: &[&str] = &[
+    "token",
+    "*_token",
+    "password",
+    "secret",
+    "*_key",
+    "auth",
+    "authorization",
+];
+
+/// Blanks out the values of sensitive fields before JSON is logged or
+/// audited. Patterns match field names case-insensitively, and `*` matches
+/// any run of characters, so `*_key` covers `api_key` and `AWS_SECRET_KEY`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redactor {
+    patterns: Vec<String>,
+}
+
+impl Redactor {
+    #[must_use]
+    pub fn new(patterns: &[&str]) -> Self {
+        Self {
+            patterns: patterns
+                .iter()
+                .map(|pattern| pattern.to_ascii_lowercase())
+                .collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_sensitive(&self, field: &str) -> bool {
+        let field = field.to_ascii_lowercase();
+        self.patterns
+            .iter()
+            .any(|pattern| wildcard_match(pattern, &field))
+    }
+
+    /// A copy of `value` with every sensitive field, at any depth, set to
+    /// [`REDACTED`].
+    #[must_use]
+    pub fn redact(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(name, field)| {
+                        let field = if self.is_sensitive(name) {
+                            Value::String(REDACTED.to_string())
+                        } else {
+                            self.redact(field)
+                        };
+                        (name.clone(), field)
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => {
+                Value::Array(items.iter().map(|item| self.redact(item)).collect())
+            }
+            other => other.clone(),
+        }
+    }
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new(DEFAULT_PATTERNS)
+    }
+}
+
+/// Whether `text` matches `pattern`, where `*` matches any run of
+/// characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_patterns_match_case_insensitively_with_wildcards() {
+        let redactor = Redactor::default();
+
+        assert!(redactor.is_sensitive("token"));
+        assert!(redactor.is_sensitive("API_KEY"));
+        assert!(redactor.is_sensitive("refresh_token"));
+        assert!(!redactor.is_sensitive("keyboard"));
+        assert!(!redactor.is_sensitive("path"));
+
+        let custom = Redactor::new(&["x*y*z"]);
+        assert!(custom.is_sensitive("x1y2z"));
+        assert!(!custom.is_sensitive("xz"));
+    }
+
+    #[test]
+    fn test_nested_fields_are_redacted() {
+        let redacted = Redactor::default().redact(&json!({
+            "path": "src",
+            "options": [{ "password": "hunter2", "depth": 2 }],
+            "_meta": { "auth": "credential" },
+        }));
+
+        assert_eq!(
+            redacted,
+            json!({
+                "path": "src",
+                "options": [{ "password": "***", "depth": 2 }],
+                "_meta": { "auth": "***" },
+            })
+        );
+    }
+}
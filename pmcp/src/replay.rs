@@ -0,0 +1,399 @@
+use crate::clock::{Clock, SystemClock};
+use crate::transport::Transport;
+use crate::{Notification, PmcpError, Request, Response, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Request,
+    Response,
+    Notification,
+}
+
+/// One line of a session recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedMessage {
+    pub timestamp_ms: u64,
+    pub direction: Direction,
+    pub message: serde_json::Value,
+}
+
+/// Passes everything through to `inner` while appending each request,
+/// response and notification to a JSONL file, one [`RecordedMessage`] per
+/// line, for [`ReplayTransport`] to play back later. A received message is
+/// only queued for writing, so `receive` stays safe to cancel; the queue is
+/// written before the next message goes out and on close.
+pub struct SessionRecorder<T> {
+    inner: T,
+    file: tokio::fs::File,
+    clock: Arc<dyn Clock>,
+    unwritten: Vec<u8>,
+}
+
+impl<T: Transport> SessionRecorder<T> {
+    /// Records to `path`, replacing any previous recording there.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if the file cannot be created.
+    pub async fn create(inner: T, path: impl AsRef<Path>) -> Result<Self> {
+        let file = tokio::fs::File::create(path)
+            .await
+            .map_err(|e| PmcpError::Transport(e.to_string()))?;
+
+        Ok(Self {
+            inner,
+            file,
+            clock: Arc::new(SystemClock),
+            unwritten: Vec::new(),
+        })
+    }
+
+    /// Timestamps entries with `clock` instead of the system clock.
+    #[must_use]
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Queues an entry for `message`. Never awaits, so a message already
+    /// taken from `inner` cannot be lost to cancellation.
+    fn queue<M: Serialize>(&mut self, direction: Direction, message: &M) -> Result<()> {
+        let entry = RecordedMessage {
+            timestamp_ms: self.clock.now_ms(),
+            direction,
+            message: serde_json::to_value(message)
+                .map_err(|e| PmcpError::Protocol(e.to_string()))?,
+        };
+        serde_json::to_writer(&mut self.unwritten, &entry)
+            .map_err(|e| PmcpError::Protocol(e.to_string()))?;
+        self.unwritten.push(b'\n');
+        Ok(())
+    }
+
+    /// Writes every queued entry. Bytes leave the queue only once the file
+    /// has taken them, so a cancelled call loses nothing.
+    async fn write_queued(&mut self) -> Result<()> {
+        while !self.unwritten.is_empty() {
+            let written = self
+                .file
+                .write(&self.unwritten)
+                .await
+                .map_err(|e| PmcpError::Transport(e.to_string()))?;
+            self.unwritten.drain(..written);
+        }
+        // tokio writes files on a background thread; flush so each entry is
+        // on disk before the message it records moves on.
+        self.file
+            .flush()
+            .await
+            .map_err(|e| PmcpError::Transport(e.to_string()))
+    }
+
+    async fn record<M: Serialize + Sync>(
+        &mut self,
+        direction: Direction,
+        message: &M,
+    ) -> Result<()> {
+        self.queue(direction, message)?;
+        self.write_queued().await
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for SessionRecorder<T> {
+    async fn send(&mut self, response: Response) -> Result<()> {
+        self.record(Direction::Response, &response).await?;
+        self.inner.send(response).await
+    }
+
+    async fn receive(&mut self) -> Result<Request> {
+        self.write_queued().await?;
+        let request = self.inner.receive().await?;
+        self.queue(Direction::Request, &request)?;
+        Ok(request)
+    }
+
+    async fn send_notification(&mut self, notification: Notification) -> Result<()> {
+        self.record(Direction::Notification, &notification).await?;
+        self.inner.send_notification(notification).await
+    }
+
+    async fn send_request(&mut self, request: Request) -> Result<()> {
+        self.record(Direction::Request, &request).await?;
+        self.inner.send_request(request).await
+    }
+
+    async fn receive_response(&mut self) -> Result<Response> {
+        self.write_queued().await?;
+        let response = self.inner.receive_response().await?;
+        self.queue(Direction::Response, &response)?;
+        Ok(response)
+    }
+
+    async fn send_notification_batch(&mut self, notifications: Vec<Notification>) -> Result<()> {
+        for notification in &notifications {
+            self.record(Direction::Notification, notification).await?;
+        }
+        self.inner.send_notification_batch(notifications).await
+    }
+
+    async fn send_batch(&mut self, responses: Vec<Response>) -> Result<()> {
+        for response in &responses {
+            self.record(Direction::Response, response).await?;
+        }
+        self.inner.send_batch(responses).await
+    }
+
+    fn flush_deadline(&self) -> Option<tokio::time::Instant> {
+        self.inner.flush_deadline()
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.write_queued().await?;
+        self.inner.close().await
+    }
+}
+
+/// Responses a [`ReplayTransport`] has been sent, readable after the
+/// transport itself has moved into [`crate::server::Server::serve`].
+#[derive(Debug, Clone, Default)]
+pub struct ReplayedResponses(Arc<Mutex<Vec<Response>>>);
+
+impl ReplayedResponses {
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<Response> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+/// Feeds the requests of a [`SessionRecorder`] file to a server in their
+/// recorded order, then reports the connection closed. Each request is
+/// released only once the one before it has been answered, so it runs
+/// alone and at its own recorded time. Build the server with
+/// [`crate::server::ServerBuilder::with_clock`] set to
+/// [`ReplayTransport::clock`] to reproduce the original responses exactly.
+pub struct ReplayTransport {
+    requests: VecDeque<(u64, Request)>,
+    recorded: Vec<Response>,
+    replayed: ReplayedResponses,
+    now_ms: Arc<AtomicU64>,
+    /// The id of the replayed request still waiting for its response.
+    awaiting: Option<serde_json::Value>,
+}
+
+impl ReplayTransport {
+    /// # Errors
+    ///
+    /// Returns a transport error if `path` cannot be read, or a protocol
+    /// error naming the first line that is not a valid recording entry.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let jsonl =
+            std::fs::read_to_string(path).map_err(|e| PmcpError::Transport(e.to_string()))?;
+        let entries = jsonl
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line)
+                    .map_err(|e| PmcpError::Protocol(format!("Recording line {}: {e}", index + 1)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Self::from_entries(entries)
+    }
+
+    /// # Errors
+    ///
+    /// Returns a protocol error if a request or response entry does not hold
+    /// a valid message.
+    pub fn from_entries(entries: Vec<RecordedMessage>) -> Result<Self> {
+        let mut requests = VecDeque::new();
+        let mut recorded = Vec::new();
+
+        for entry in entries {
+            let invalid = |e: serde_json::Error| PmcpError::Protocol(e.to_string());
+            match entry.direction {
+                Direction::Request => requests.push_back((
+                    entry.timestamp_ms,
+                    serde_json::from_value(entry.message).map_err(invalid)?,
+                )),
+                Direction::Response => {
+                    recorded.push(serde_json::from_value(entry.message).map_err(invalid)?);
+                }
+                Direction::Notification => {}
+            }
+        }
+
+        let now_ms = requests.front().map_or(0, |(timestamp, _)| *timestamp);
+        Ok(Self {
+            requests,
+            recorded,
+            replayed: ReplayedResponses::default(),
+            now_ms: Arc::new(AtomicU64::new(now_ms)),
+            awaiting: None,
+        })
+    }
+
+    /// Reads as the timestamp of the request being replayed.
+    #[must_use]
+    pub fn clock(&self) -> ReplayClock {
+        ReplayClock {
+            start: Instant::now(),
+            start_ms: self.now_ms.load(Ordering::SeqCst),
+            now_ms: Arc::clone(&self.now_ms),
+        }
+    }
+
+    /// The responses captured in the recording, in recorded order.
+    #[must_use]
+    pub fn recorded_responses(&self) -> &[Response] {
+        &self.recorded
+    }
+
+    #[must_use]
+    pub fn replayed(&self) -> ReplayedResponses {
+        self.replayed.clone()
+    }
+}
+
+/// The [`ReplayTransport::clock`]: time moves only as recorded requests
+/// are replayed, and sleeping returns at once.
+#[derive(Debug, Clone)]
+pub struct ReplayClock {
+    start: Instant,
+    start_ms: u64,
+    now_ms: Arc<AtomicU64>,
+}
+
+#[async_trait]
+impl Clock for ReplayClock {
+    fn now(&self) -> Instant {
+        self.start + Duration::from_millis(self.now_ms().saturating_sub(self.start_ms))
+    }
+
+    fn now_ms(&self) -> u64 {
+        self.now_ms.load(Ordering::SeqCst)
+    }
+
+    async fn sleep(&self, _duration: Duration) {}
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn send(&mut self, response: Response) -> Result<()> {
+        if self.awaiting == response.id {
+            self.awaiting = None;
+        }
+        self.replayed
+            .0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(response);
+        Ok(())
+    }
+
+    /// Waits while a replayed request is unanswered: the server's `select!`
+    /// drops this call to send the response, and the next call moves on.
+    async fn receive(&mut self) -> Result<Request> {
+        if self.awaiting.is_some() {
+            std::future::pending::<()>().await;
+        }
+        let (timestamp_ms, request) = self.requests.pop_front().ok_or(PmcpError::Closed)?;
+        self.now_ms.store(timestamp_ms, Ordering::SeqCst);
+        self.awaiting.clone_from(&request.id);
+        Ok(request)
+    }
+
+    async fn send_notification(&mut self, _notification: Notification) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+    use crate::clock::MockClock;
+    use crate::server::{ServerBuilder, ToolHandler};
+    use crate::transport::InMemoryTransport;
+    use serde_json::json;
+
+    const RECORDED_AT: u64 = 1_700_000_000_000;
+
+    struct AddHandler;
+
+    #[async_trait]
+    impl ToolHandler for AddHandler {
+        async fn handle(&self, params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+            let params = params.unwrap_or_default();
+            let a = params["a"].as_f64().unwrap_or_default();
+            let b = params["b"].as_f64().unwrap_or_default();
+            Ok(json!(a + b))
+        }
+    }
+
+    fn builder() -> ServerBuilder {
+        ServerBuilder::new().with_handler(crate::tools::calculator_tool(), AddHandler)
+    }
+
+    #[tokio::test]
+    async fn test_recorded_session_replays_to_identical_responses() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+
+        let clock = MockClock::at(RECORDED_AT);
+        let server = builder().with_clock(clock.clone()).build().unwrap();
+        let (client_end, server_end) = InMemoryTransport::pair();
+        let recorder = SessionRecorder::create(server_end, &path)
+            .await
+            .unwrap()
+            .with_clock(clock.clone());
+        let serving = tokio::spawn(async move { server.serve(recorder).await });
+
+        let client = Client::new(client_end);
+        // Each only meets its deadline if replay runs it at its own
+        // recorded time, not the time of the request after it.
+        let deadline = |ms: u64| json!({"a": 1, "b": 1, "_meta": {"deadlineMs": ms}});
+        client
+            .call("calculator", Some(deadline(RECORDED_AT + 1_000)))
+            .await
+            .unwrap();
+        clock.advance(Duration::from_secs(5));
+        client
+            .call("calculator", Some(deadline(RECORDED_AT + 6_000)))
+            .await
+            .unwrap();
+        drop(client);
+        serving.await.unwrap().unwrap();
+
+        let replay = ReplayTransport::open(&path).unwrap();
+        let expected = replay.recorded_responses().to_vec();
+        let replayed = replay.replayed();
+        let server = builder().with_clock(replay.clock()).build().unwrap();
+        server.serve(replay).await.unwrap();
+
+        assert_eq!(expected.len(), 2);
+        assert_eq!(replayed.snapshot(), expected);
+    }
+}
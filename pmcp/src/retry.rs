@@ -0,0 +1,223 @@
+use crate::clock::{Clock, SystemClock};
+use crate::{PmcpError, Result};
+use rand::Rng;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+/// How much randomness to mix into each backoff delay so clients that failed
+/// together do not all retry together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Jitter {
+    /// Exactly the capped exponential delay.
+    #[default]
+    None,
+    /// Anywhere between zero and the capped delay.
+    Full,
+    /// Half the capped delay plus up to another half at random.
+    Equal,
+}
+
+type RetryPredicate = Arc<dyn Fn(&PmcpError) -> bool + Send + Sync>;
+
+/// Exponential backoff starting at `base_delay`, doubling per attempt and
+/// capped at `max_delay`. Errors the predicate marks as terminal are
+/// returned immediately.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: Jitter,
+    retryable: RetryPredicate,
+    clock: Arc<dyn Clock>,
+}
+
+impl RetryPolicy {
+    /// Retries every error, with a 30s cap and no jitter. `max_attempts`
+    /// counts the first call; zero is treated as one.
+    #[must_use]
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay,
+            max_delay: Duration::from_secs(30),
+            jitter: Jitter::None,
+            retryable: Arc::new(|_| true),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    #[must_use]
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    #[must_use]
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Only errors for which `predicate` returns `true` are retried.
+    #[must_use]
+    pub fn with_retryable(
+        mut self,
+        predicate: impl Fn(&PmcpError) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retryable = Arc::new(predicate);
+        self
+    }
+
+    /// Waits out backoff delays on `clock` instead of the system clock.
+    #[must_use]
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    #[must_use]
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// The capped delay after the `attempt`th failure (1-based), before jitter.
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        2u32.checked_pow(attempt.saturating_sub(1))
+            .and_then(|factor| self.base_delay.checked_mul(factor))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+
+    /// The delay to wait after the `attempt`th failure, jitter included.
+    #[must_use]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.jittered(self.backoff(attempt), rand::thread_rng().gen())
+    }
+
+    fn jittered(&self, backoff: Duration, sample: f64) -> Duration {
+        match self.jitter {
+            Jitter::None => backoff,
+            Jitter::Full => backoff.mul_f64(sample),
+            Jitter::Equal => backoff / 2 + (backoff / 2).mul_f64(sample),
+        }
+    }
+
+    /// Calls `f` until it succeeds, fails with a terminal error, or runs out
+    /// of attempts.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error from `f`.
+    pub async fn execute<F, Fut, T>(&self, mut f: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+
+            match f().await {
+                Ok(result) => return Ok(result),
+                Err(e) if attempt >= self.max_attempts || !(self.retryable)(&e) => return Err(e),
+                Err(e) => {
+                    let delay = self.delay(attempt);
+                    warn!("Attempt {} failed: {}. Retrying in {:?}", attempt, e, delay);
+                    self.clock.sleep(delay).await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_delay_is_capped() {
+        let policy =
+            RetryPolicy::new(20, Duration::from_millis(100)).with_max_delay(Duration::from_secs(1));
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(4), Duration::from_millis(800));
+        assert_eq!(policy.backoff(5), Duration::from_secs(1));
+        assert_eq!(policy.backoff(40), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let backoff = Duration::from_millis(800);
+        let full = RetryPolicy::new(5, backoff).with_jitter(Jitter::Full);
+        let equal = RetryPolicy::new(5, backoff).with_jitter(Jitter::Equal);
+
+        assert_eq!(full.jittered(backoff, 0.0), Duration::ZERO);
+        assert_eq!(equal.jittered(backoff, 0.0), backoff / 2);
+
+        for _ in 0..100 {
+            assert!(full.delay(1) <= backoff);
+            let delay = equal.delay(1);
+            assert!(delay >= backoff / 2 && delay <= backoff);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_terminal_error_short_circuits() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new(5, Duration::from_millis(10))
+            .with_retryable(|e| matches!(e, PmcpError::Transport(_)));
+
+        let outcome: Result<()> = policy
+            .execute(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(PmcpError::Protocol("malformed".to_string()))
+            })
+            .await;
+
+        assert!(matches!(outcome, Err(PmcpError::Protocol(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retryable_error_is_retried_until_success() {
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new(5, Duration::from_millis(10));
+
+        let outcome = policy
+            .execute(|| async {
+                if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(PmcpError::Transport("reset".to_string()))
+                } else {
+                    Ok("done")
+                }
+            })
+            .await;
+
+        assert_eq!(outcome.unwrap(), "done");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_backoff_waits_on_injected_clock() {
+        let clock = MockClock::new();
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy::new(3, Duration::from_secs(10)).with_clock(clock.clone());
+
+        let outcome: Result<()> = policy
+            .execute(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(PmcpError::Transport("reset".to_string()))
+            })
+            .await;
+
+        assert!(outcome.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(clock.elapsed(), Duration::from_secs(30));
+    }
+}
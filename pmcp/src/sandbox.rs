@@ -0,0 +1,115 @@
+use crate::protocol::ERROR_INVALID_PARAMS;
+use crate::{PmcpError, Result};
+use std::path::{Path, PathBuf};
+
+/// Confines user-supplied paths to a single directory tree. Tool handlers
+/// that touch the filesystem resolve every path through this before any IO.
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    root: PathBuf,
+}
+
+impl Sandbox {
+    /// # Errors
+    ///
+    /// Returns an error if `root` does not exist or cannot be canonicalized.
+    pub fn new(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root
+            .as_ref()
+            .canonicalize()
+            .map_err(|e| PmcpError::Server(format!("Invalid sandbox root: {e}")))?;
+        Ok(Self { root })
+    }
+
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolves `user_path` relative to the root, following symlinks, and
+    /// returns the canonical path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an invalid-params error if the path does not exist or resolves
+    /// outside the root.
+    pub fn resolve(&self, user_path: &str) -> Result<PathBuf> {
+        let resolved = self
+            .root
+            .join(user_path)
+            .canonicalize()
+            .map_err(|e| invalid_params(format!("{user_path}: {e}")))?;
+
+        if resolved.starts_with(&self.root) {
+            Ok(resolved)
+        } else {
+            Err(invalid_params(format!(
+                "Path escapes sandbox root: {user_path}"
+            )))
+        }
+    }
+}
+
+fn invalid_params(message: String) -> PmcpError {
+    PmcpError::JsonRpc {
+        code: ERROR_INVALID_PARAMS,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> (tempfile::TempDir, Sandbox) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("root/src")).unwrap();
+        std::fs::write(dir.path().join("root/src/lib.rs"), "").unwrap();
+        std::fs::write(dir.path().join("secret.txt"), "").unwrap();
+        let sandbox = Sandbox::new(dir.path().join("root")).unwrap();
+        (dir, sandbox)
+    }
+
+    fn is_rejected(result: &Result<PathBuf>) -> bool {
+        matches!(
+            result,
+            Err(PmcpError::JsonRpc {
+                code: ERROR_INVALID_PARAMS,
+                ..
+            })
+        )
+    }
+
+    #[test]
+    fn test_resolves_paths_inside_root() {
+        let (_dir, sandbox) = setup();
+
+        let resolved = sandbox.resolve("src/../src/lib.rs").unwrap();
+        assert_eq!(resolved, sandbox.root().join("src/lib.rs"));
+    }
+
+    #[test]
+    fn test_rejects_parent_traversal() {
+        let (_dir, sandbox) = setup();
+
+        assert!(is_rejected(&sandbox.resolve("../secret.txt")));
+        assert!(is_rejected(&sandbox.resolve("src/../../secret.txt")));
+    }
+
+    #[test]
+    fn test_rejects_absolute_path_outside_root() {
+        let (dir, sandbox) = setup();
+        let outside = dir.path().join("secret.txt");
+
+        assert!(is_rejected(&sandbox.resolve(outside.to_str().unwrap())));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rejects_symlink_escape() {
+        let (dir, sandbox) = setup();
+        std::os::unix::fs::symlink(dir.path(), sandbox.root().join("escape")).unwrap();
+
+        assert!(is_rejected(&sandbox.resolve("escape/secret.txt")));
+    }
+}
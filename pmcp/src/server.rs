@@ -1,17 +1,351 @@
-use crate::{Request, Response, Result, ServerCapabilities, Tool};
+use crate::audit::{AuditEntry, AuditOutcome, AuditSink};
+use crate::auth::{Scope, ScopePolicy};
+use crate::clock::{Clock, SystemClock};
+use crate::degradation::{DegradationThresholds, ServiceLevel, ServiceLevelTracker};
+use crate::health::{HealthState, HealthStatus};
+use crate::idempotency::{IdempotencyCache, IdempotencyKey};
+use crate::metrics::Metrics;
+use crate::protocol::{
+    check_json_limits, check_value_limits, is_valid_id, validate_response,
+    DEADLINE_EXCEEDED_MESSAGE, ERROR_DEADLINE_EXCEEDED, ERROR_INSUFFICIENT_SCOPE, ERROR_INTERNAL,
+    ERROR_INVALID_PARAMS, ERROR_INVALID_REQUEST, ERROR_METHOD_NOT_FOUND, ERROR_PARSE,
+    ERROR_RATE_LIMITED, ERROR_RESPONSE_TOO_LARGE, ERROR_SERVER_BUSY, ERROR_SERVICE_DEGRADED,
+    INSUFFICIENT_SCOPE_MESSAGE, MCP_PROTOCOL_VERSION, RESPONSE_TOO_LARGE_MESSAGE,
+    SERVER_BUSY_MESSAGE, SERVICE_DEGRADED_MESSAGE,
+};
+use crate::rate_limit::RateLimiter;
+use crate::redact::Redactor;
+use crate::transport::{TcpTransport, TlsTcpTransport, Transport};
+use crate::{Notification, Request, Response, Result, ServerCapabilities, Tool};
 use async_trait::async_trait;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use futures::FutureExt;
+use std::collections::{HashMap, HashSet};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, watch, RwLock, Semaphore};
+use tokio::task::JoinSet;
+use tokio_rustls::TlsAcceptor;
+use tracing::{error, field, info, warn, Instrument, Span};
+
+const DEFAULT_TOOLS_PAGE_SIZE: usize = 100;
+/// Suggested wait before retrying a call turned away by a full queue.
+const BUSY_RETRY_AFTER: Duration = Duration::from_millis(100);
 
 #[async_trait]
 pub trait ToolHandler: Send + Sync {
     async fn handle(&self, params: Option<serde_json::Value>) -> Result<serde_json::Value>;
+
+    /// Like [`ToolHandler::handle`], but with a sender for reporting incremental
+    /// progress. Handlers that never report progress can rely on the default.
+    async fn handle_with_progress(
+        &self,
+        params: Option<serde_json::Value>,
+        _progress: ProgressSender,
+    ) -> Result<serde_json::Value> {
+        self.handle(params).await
+    }
+
+    /// What the server calls. `dry_run` is set when the client passed
+    /// `params._meta.dryRun`: the handler should validate and authorize the
+    /// call, then return without side effects. Handlers opt in by
+    /// overriding this; the default refuses dry runs rather than risk
+    /// running the real thing.
+    async fn handle_call(
+        &self,
+        params: Option<serde_json::Value>,
+        progress: ProgressSender,
+        dry_run: bool,
+    ) -> Result<serde_json::Value> {
+        if dry_run {
+            return Err(crate::PmcpError::JsonRpc {
+                code: ERROR_INVALID_PARAMS,
+                message: "This tool does not support dry runs".to_string(),
+            });
+        }
+        self.handle_with_progress(params, progress).await
+    }
+}
+
+/// Handles a client notification. Notifications never produce a response,
+/// so errors are only logged.
+#[async_trait]
+pub trait NotificationHandler: Send + Sync {
+    async fn handle(&self, params: Option<serde_json::Value>) -> Result<()>;
+}
+
+/// Emits `notifications/progress` for the request's `params._meta.progressToken`.
+/// Reports are dropped when the client did not supply a token.
+#[derive(Clone, Default)]
+pub struct ProgressSender {
+    token: Option<serde_json::Value>,
+    sink: Option<mpsc::UnboundedSender<Notification>>,
+}
+
+impl ProgressSender {
+    #[must_use]
+    pub fn new(
+        token: Option<serde_json::Value>,
+        sink: mpsc::UnboundedSender<Notification>,
+    ) -> Self {
+        Self {
+            token,
+            sink: Some(sink),
+        }
+    }
+
+    #[must_use]
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    pub fn report(&self, progress: u64, total: Option<u64>) {
+        let (Some(token), Some(sink)) = (&self.token, &self.sink) else {
+            return;
+        };
+
+        let mut params = serde_json::json!({
+            "progressToken": token,
+            "progress": progress,
+        });
+        if let Some(total) = total {
+            params["total"] = total.into();
+        }
+
+        let _ = sink.send(Notification {
+            jsonrpc: "2.0".to_string(),
+            method: "notifications/progress".to_string(),
+            params: Some(params),
+        });
+    }
+}
+
+/// Reads the client's absolute `params._meta.deadlineMs`, in epoch millis.
+fn deadline_ms(request: &Request) -> Option<u64> {
+    request
+        .params
+        .as_ref()?
+        .get("_meta")?
+        .get("deadlineMs")?
+        .as_u64()
+}
+
+/// Whether the client asked, via `params._meta.dryRun`, for validation only.
+fn is_dry_run(request: &Request) -> bool {
+    request
+        .params
+        .as_ref()
+        .and_then(|params| params.get("_meta")?.get("dryRun")?.as_bool())
+        .unwrap_or(false)
+}
+
+/// Time left before `deadline_ms`, or `None` once it has passed.
+fn remaining_until(deadline_ms: u64, now_ms: u64) -> Option<Duration> {
+    deadline_ms
+        .checked_sub(now_ms)
+        .filter(|&left| left > 0)
+        .map(Duration::from_millis)
+}
+
+/// How the server routes one tool method, as reported by
+/// [`Server::routes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteInfo {
+    pub method: String,
+    /// Whether calls reach a handler; an advertised tool without one
+    /// answers method-not-found.
+    pub has_handler: bool,
+    /// Whether the tool appears in `tools/list`.
+    pub advertised: bool,
+    pub required_scopes: Vec<Scope>,
+    /// Calls allowed per second, if the method is rate limited.
+    pub rate_limit: Option<u32>,
+    /// For an alias, the tool calls are forwarded to.
+    pub alias_for: Option<String>,
+}
+
+/// An old tool name kept working after a rename.
+#[derive(Debug, Clone)]
+struct ToolAlias {
+    old_name: String,
+    target: String,
+    deprecated: bool,
+}
+
+impl ToolAlias {
+    fn warning(&self) -> Option<String> {
+        self.deprecated.then(|| {
+            format!(
+                "Tool '{}' is deprecated; call '{}' instead",
+                self.old_name, self.target
+            )
+        })
+    }
+}
+
+/// Adds `warning` under `deprecation` in the error's `data`, or in the
+/// result's `_meta` when the result is an object. Other results are left
+/// untouched so existing clients still decode them.
+fn add_deprecation_warning(response: &mut Response, warning: &str) {
+    let fields = match (&mut response.error, &mut response.result) {
+        (Some(error), _) => error
+            .data
+            .get_or_insert_with(|| serde_json::json!({}))
+            .as_object_mut(),
+        (None, Some(serde_json::Value::Object(result))) => result
+            .entry("_meta")
+            .or_insert_with(|| serde_json::json!({}))
+            .as_object_mut(),
+        _ => None,
+    };
+    if let Some(fields) = fields {
+        fields.insert("deprecation".to_string(), serde_json::json!(warning));
+    }
+}
+
+/// Sends `response`, checking it is well-formed JSON-RPC first in debug
+/// builds.
+async fn send_response<T: Transport>(transport: &mut T, response: Response) -> Result<()> {
+    validate_response(&response);
+    transport.send(response).await
+}
+
+fn server_busy(id: Option<serde_json::Value>) -> Response {
+    let retry_after_ms = u64::try_from(BUSY_RETRY_AFTER.as_millis()).unwrap_or(u64::MAX);
+    Response {
+        jsonrpc: "2.0".to_string(),
+        result: None,
+        error: Some(crate::ErrorObject {
+            code: ERROR_SERVER_BUSY,
+            message: SERVER_BUSY_MESSAGE.to_string(),
+            data: Some(serde_json::json!({ "retryAfterMs": retry_after_ms })),
+        }),
+        id,
+    }
+}
+
+/// Whether `request` can be answered at all: an object or array id could
+/// not be echoed back in a valid response.
+fn has_valid_id(request: &Request) -> bool {
+    request.id.as_ref().is_none_or(is_valid_id)
+}
+
+fn invalid_id() -> Response {
+    invalid_request(None, "Request id must be a number, string or null")
+}
+
+fn invalid_request(id: Option<serde_json::Value>, detail: &str) -> Response {
+    Response {
+        jsonrpc: "2.0".to_string(),
+        result: None,
+        error: Some(crate::ErrorObject {
+            code: ERROR_INVALID_REQUEST,
+            message: "Invalid Request".to_string(),
+            data: Some(serde_json::json!({ "reason": detail })),
+        }),
+        id,
+    }
+}
+
+fn deadline_exceeded() -> crate::PmcpError {
+    crate::PmcpError::JsonRpc {
+        code: ERROR_DEADLINE_EXCEEDED,
+        message: DEADLINE_EXCEEDED_MESSAGE.to_string(),
+    }
+}
+
+/// Turns a caught handler panic into an internal error. Only effective when
+/// panics unwind; builds with `panic = "abort"` still terminate.
+fn handler_panicked(payload: &(dyn std::any::Any + Send)) -> crate::ErrorObject {
+    let detail = payload
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_default();
+    error!(panic = %detail, "Tool handler panicked");
+
+    crate::ErrorObject {
+        code: ERROR_INTERNAL,
+        message: "Internal error".to_string(),
+        data: Some(serde_json::json!({ "reason": "handler panicked", "panic": detail })),
+    }
+}
+
+async fn stopped(shutdown: &mut watch::Receiver<bool>) {
+    if shutdown.wait_for(|stopping| *stopping).await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+/// Resolves once `deadline` passes; never, if there is none.
+async fn flush_due(deadline: Option<tokio::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+fn progress_token(request: &Request) -> Option<serde_json::Value> {
+    request
+        .params
+        .as_ref()?
+        .get("_meta")?
+        .get("progressToken")
+        .cloned()
+}
+
+/// The optional protocol features both sides support, settled by the
+/// client's `initialize`. Everything is off until then, so a client that
+/// never advertises a feature is never sent it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NegotiatedCapabilities {
+    pub batching: bool,
+}
+
+/// One connection's share of server state. Requests handled outside
+/// [`Server::serve`] all belong to a single detached session.
+#[derive(Debug, Default)]
+struct Session {
+    id: u64,
+    negotiated: Mutex<NegotiatedCapabilities>,
+}
+
+impl Session {
+    fn negotiated(&self) -> NegotiatedCapabilities {
+        *self
+            .negotiated
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 #[derive(Clone)]
 pub struct Server {
     capabilities: ServerCapabilities,
     handlers: Arc<RwLock<std::collections::HashMap<String, Box<dyn ToolHandler>>>>,
+    notification_handlers:
+        Arc<RwLock<std::collections::HashMap<String, Box<dyn NotificationHandler>>>>,
+    shutdown: Arc<watch::Sender<bool>>,
+    health: Arc<watch::Sender<HealthState>>,
+    drain_timeout: Duration,
+    max_concurrency: usize,
+    rate_limiter: Arc<RateLimiter>,
+    idempotency: Option<Arc<IdempotencyCache>>,
+    metrics: Arc<Metrics>,
+    tls: Option<TlsAcceptor>,
+    service_level: Option<Arc<ServiceLevelTracker>>,
+    expensive_tools: Arc<HashSet<String>>,
+    aliases: Arc<HashMap<String, ToolAlias>>,
+    scopes: Arc<ScopePolicy>,
+    queue: Option<Arc<Semaphore>>,
+    tools_page_size: usize,
+    clock: Arc<dyn Clock>,
+    audit: Option<Arc<dyn AuditSink>>,
+    redactor: Arc<Redactor>,
+    sessions: Arc<AtomicU64>,
+    detached: Arc<Session>,
 }
 
 impl Server {
@@ -20,7 +354,299 @@ impl Server {
         Self {
             capabilities,
             handlers: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            notification_handlers: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            shutdown: Arc::new(watch::channel(false).0),
+            health: Arc::new(watch::channel(HealthState::Starting).0),
+            drain_timeout: Duration::from_secs(30),
+            max_concurrency: 64,
+            rate_limiter: Arc::new(RateLimiter::new()),
+            idempotency: None,
+            metrics: Arc::new(Metrics::new()),
+            tls: None,
+            service_level: None,
+            expensive_tools: Arc::new(HashSet::new()),
+            aliases: Arc::new(HashMap::new()),
+            scopes: Arc::new(ScopePolicy::new()),
+            queue: None,
+            tools_page_size: DEFAULT_TOOLS_PAGE_SIZE,
+            clock: Arc::new(SystemClock),
+            audit: None,
+            redactor: Arc::default(),
+            sessions: Arc::default(),
+            detached: Arc::default(),
+        }
+    }
+
+    /// Signals every running [`Server::serve`] and [`Server::serve_tcp`] loop
+    /// to stop accepting work and drain.
+    pub fn shutdown(&self) {
+        self.health.send_replace(HealthState::Draining);
+        self.shutdown.send_replace(true);
+    }
+
+    /// [`ServiceLevel::Full`] unless degradation was enabled with
+    /// [`ServerBuilder::with_degradation`].
+    #[must_use]
+    pub fn service_level(&self) -> ServiceLevel {
+        self.service_level
+            .as_ref()
+            .map_or(ServiceLevel::Full, |tracker| tracker.level())
+    }
+
+    #[must_use]
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    #[must_use]
+    pub fn health_state(&self) -> HealthState {
+        *self.health.borrow()
+    }
+
+    #[must_use]
+    pub fn liveness(&self) -> HealthStatus {
+        self.health_state().liveness()
+    }
+
+    /// Fails until a client completes `initialize`, and again once
+    /// [`Server::shutdown`] starts draining.
+    #[must_use]
+    pub fn readiness(&self) -> HealthStatus {
+        self.health_state().readiness()
+    }
+
+    #[must_use]
+    pub fn startup(&self) -> HealthStatus {
+        self.health_state().startup()
+    }
+
+    /// Passes a message without an id to [`Server::handle_notification`].
+    async fn handle_unanswered(&self, request: Request) {
+        let notification = Notification {
+            jsonrpc: request.jsonrpc,
+            method: request.method,
+            params: request.params,
+        };
+        if let Err(e) = self.handle_notification(notification).await {
+            warn!(error = %e, "Notification handling failed");
+        }
+    }
+
+    /// Reads requests from `transport` until it closes or [`Server::shutdown`]
+    /// is called. Messages without an id are routed to
+    /// [`Server::handle_notification`] and never answered. Each request runs
+    /// on its own task, up to the configured
+    /// concurrency limit, and this loop is the only writer to the transport so
+    /// responses never interleave. Responses are written as they complete, not
+    /// in request order. Output the transport holds back is flushed from this
+    /// loop once its [`Transport::flush_deadline`] passes, and on close.
+    /// Requests still in flight when the loop stops get up to the drain
+    /// timeout to finish.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a response or notification cannot be sent.
+    pub async fn serve<T: Transport>(&self, mut transport: T) -> Result<()> {
+        let mut shutdown = self.shutdown.subscribe();
+        let limit = Arc::new(Semaphore::new(self.max_concurrency));
+        let (notification_tx, mut notification_rx) = mpsc::unbounded_channel();
+        let (response_tx, mut response_rx) = mpsc::unbounded_channel();
+        let mut in_flight = JoinSet::new();
+        let session = Arc::new(Session {
+            id: self.sessions.fetch_add(1, Ordering::Relaxed) + 1,
+            negotiated: Mutex::default(),
+        });
+
+        loop {
+            let flush_at = transport.flush_deadline();
+            tokio::select! {
+                biased;
+                () = stopped(&mut shutdown) => break,
+                Some(notification) = notification_rx.recv() => {
+                    transport.send_notification(notification).await?;
+                }
+                Some(response) = response_rx.recv() => self.send_responses(&mut transport, response, &mut response_rx, &session).await?,
+                Some(_) = in_flight.join_next(), if !in_flight.is_empty() => {}
+                () = flush_due(flush_at) => transport.flush().await?,
+                received = transport.receive() => match received {
+                    Ok(request) if !has_valid_id(&request) => {
+                        send_response(&mut transport, invalid_id()).await?;
+                    }
+                    // Turned away before parsing, so there is no id to echo.
+                    Err(crate::PmcpError::JsonRpc { code: ERROR_INVALID_REQUEST, message }) => {
+                        send_response(&mut transport, invalid_request(None, &message)).await?;
+                    }
+                    Ok(request) => {
+                        let permit = Arc::clone(&limit)
+                            .acquire_owned()
+                            .await
+                            .map_err(|e| crate::PmcpError::Server(e.to_string()))?;
+                        let server = self.clone();
+
+                        if request.id.is_none() {
+                            in_flight.spawn(async move {
+                                server.handle_unanswered(request).await;
+                                drop(permit);
+                            });
+                        } else {
+                            let notifications = notification_tx.clone();
+                            let responses = response_tx.clone();
+                            let id = request.id.clone();
+                            let session = Arc::clone(&session);
+
+                            in_flight.spawn(async move {
+                                let progress = ProgressSender::new(progress_token(&request), notifications);
+                                match server.dispatch(request, progress, &session).await {
+                                    Ok(response) => {
+                                        debug_assert_eq!(response.id, id, "Response id does not match its request");
+                                        let _ = responses.send(response);
+                                    }
+                                    Err(e) => error!(error = %e, "Request handling failed"),
+                                }
+                                drop(permit);
+                            });
+                        }
+                    }
+                    Err(crate::PmcpError::Closed) => {
+                        info!("Connection closed");
+                        break;
+                    }
+                    Err(e) => {
+                        error!(error = %e, "Transport error");
+                        break;
+                    }
+                },
+            }
+        }
+
+        let drain = tokio::time::sleep(self.drain_timeout);
+        tokio::pin!(drain);
+
+        while !in_flight.is_empty() {
+            let flush_at = transport.flush_deadline();
+            tokio::select! {
+                biased;
+                Some(notification) = notification_rx.recv() => {
+                    transport.send_notification(notification).await?;
+                }
+                Some(response) = response_rx.recv() => self.send_responses(&mut transport, response, &mut response_rx, &session).await?,
+                Some(_) = in_flight.join_next() => {}
+                () = flush_due(flush_at) => transport.flush().await?,
+                () = &mut drain => {
+                    warn!(
+                        drain_timeout = ?self.drain_timeout,
+                        abandoned = in_flight.len(),
+                        "Drain timeout elapsed; abandoning requests"
+                    );
+                    in_flight.abort_all();
+                    break;
+                }
+            }
         }
+
+        while let Ok(notification) = notification_rx.try_recv() {
+            transport.send_notification(notification).await?;
+        }
+        while let Ok(response) = response_rx.try_recv() {
+            self.send_responses(&mut transport, response, &mut response_rx, &session)
+                .await?;
+        }
+
+        transport.close().await
+    }
+
+    /// Accepts connections until [`Server::shutdown`] is called, then closes
+    /// the listener and waits for every active connection to drain. With
+    /// [`ServerBuilder::with_tls`], each connection is a TLS session.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if accepting a connection fails.
+    pub async fn serve_tcp(&self, listener: TcpListener) -> Result<()> {
+        let mut shutdown = self.shutdown.subscribe();
+        let mut connections = JoinSet::new();
+
+        loop {
+            tokio::select! {
+                biased;
+                () = stopped(&mut shutdown) => break,
+                Some(_) = connections.join_next(), if !connections.is_empty() => {}
+                accepted = listener.accept() => {
+                    let (socket, addr) =
+                        accepted.map_err(|e| crate::PmcpError::Transport(e.to_string()))?;
+                    info!(peer = %addr, "New connection");
+
+                    let server = self.clone();
+                    let connection = self.metrics.connection_opened();
+                    connections.spawn(async move {
+                        let limits = &server.capabilities;
+                        let (max_frame_size, max_depth, max_elements) =
+                            (limits.max_request_size, limits.max_json_depth, limits.max_json_elements);
+                        let outcome = match &server.tls {
+                            None => {
+                                let transport = TcpTransport::new(socket)
+                                    .with_max_frame_size(max_frame_size)
+                                    .with_json_limits(max_depth, max_elements);
+                                server.serve(transport).await
+                            }
+                            Some(acceptor) => match TlsTcpTransport::accept(acceptor, socket).await {
+                                Ok(transport) => {
+                                    let transport = transport
+                                        .with_max_frame_size(max_frame_size)
+                                        .with_json_limits(max_depth, max_elements);
+                                    server.serve(transport).await
+                                }
+                                Err(e) => Err(e),
+                            },
+                        };
+                        if let Err(e) = outcome {
+                            warn!(peer = %addr, error = %e, "Connection ended with error");
+                        }
+                        drop(connection);
+                    });
+                }
+            }
+        }
+
+        drop(listener);
+        while connections.join_next().await.is_some() {}
+
+        Ok(())
+    }
+
+    /// Every tool method the server routes, sorted by name: advertised
+    /// tools, tools with a registered handler, and aliases. Scopes and rate
+    /// limits of an alias are those of the tool it forwards to.
+    pub async fn routes(&self) -> Vec<RouteInfo> {
+        let handlers = self.handlers.read().await;
+        let advertised: HashSet<&str> = self
+            .capabilities
+            .tools
+            .iter()
+            .map(|tool| tool.name.as_str())
+            .collect();
+        let methods: std::collections::BTreeSet<&str> = advertised
+            .iter()
+            .copied()
+            .chain(handlers.keys().map(String::as_str))
+            .chain(self.aliases.keys().map(String::as_str))
+            .collect();
+
+        methods
+            .into_iter()
+            .map(|method| {
+                let alias_for = self.aliases.get(method).map(|alias| alias.target.clone());
+                let target = alias_for.as_deref().unwrap_or(method);
+                RouteInfo {
+                    method: method.to_string(),
+                    has_handler: handlers.contains_key(target),
+                    advertised: advertised.contains(method),
+                    required_scopes: self.scopes.required(target),
+                    rate_limit: self.rate_limiter.limit(target),
+                    alias_for,
+                }
+            })
+            .collect()
     }
 
     pub async fn register_tool(&self, tool: Tool, handler: Box<dyn ToolHandler>) {
@@ -28,33 +654,221 @@ impl Server {
         handlers.insert(tool.name.clone(), handler);
     }
 
+    pub async fn register_notification(&self, method: &str, handler: Box<dyn NotificationHandler>) {
+        let mut handlers = self.notification_handlers.write().await;
+        handlers.insert(method.to_string(), handler);
+    }
+
+    /// Routes a notification to its registered handler. Notifications with no
+    /// handler are ignored, as JSON-RPC forbids replying to them.
+    ///
+    /// # Errors
+    ///
+    /// Returns the handler's error, if any.
+    pub async fn handle_notification(&self, notification: Notification) -> Result<()> {
+        let handlers = self.notification_handlers.read().await;
+        if let Some(handler) = handlers.get(&notification.method) {
+            handler.handle(notification.params).await
+        } else {
+            tracing::debug!(
+                method = notification.method.as_str(),
+                "Ignoring unhandled notification"
+            );
+            Ok(())
+        }
+    }
+
     /// Handles incoming requests and returns appropriate responses.
     ///
     /// # Errors
     ///
     /// Returns an error if the handler fails to process the request.
     pub async fn handle_request(&self, request: Request) -> Result<Response> {
-        let handlers = self.handlers.read().await;
+        self.dispatch(request, ProgressSender::disabled(), &self.detached)
+            .await
+    }
 
-        if let Some(handler) = handlers.get(&request.method) {
-            match handler.handle(request.params).await {
-                Ok(result) => Ok(Response {
-                    jsonrpc: "2.0".to_string(),
-                    result: Some(result),
-                    error: None,
-                    id: request.id,
+    /// Handles a request that has not been parsed yet. Oversized or too
+    /// deeply nested payloads are rejected with `-32600` before any JSON is
+    /// parsed; malformed ones with `-32700`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handler fails to process the request.
+    pub async fn handle_raw(&self, raw: &[u8]) -> Result<Response> {
+        let limits = &self.capabilities;
+        let checked = if raw.len() > limits.max_request_size {
+            Err(format!(
+                "Request of {} bytes exceeds {} bytes",
+                raw.len(),
+                limits.max_request_size
+            ))
+        } else {
+            check_json_limits(raw, limits.max_json_depth, limits.max_json_elements)
+        };
+        if let Err(detail) = checked {
+            return Ok(invalid_request(None, &detail));
+        }
+
+        match serde_json::from_slice(raw) {
+            Ok(request) => self.handle_request(request).await,
+            Err(e) => Ok(Response {
+                jsonrpc: "2.0".to_string(),
+                result: None,
+                error: Some(crate::ErrorObject {
+                    code: ERROR_PARSE,
+                    message: "Parse error".to_string(),
+                    data: Some(serde_json::json!({ "reason": e.to_string() })),
                 }),
-                Err(e) => Ok(Response {
-                    jsonrpc: "2.0".to_string(),
-                    result: None,
-                    error: Some(crate::ErrorObject {
-                        code: -32603,
-                        message: e.to_string(),
-                        data: None,
-                    }),
-                    id: request.id,
+                id: None,
+            }),
+        }
+    }
+
+    /// Handles a request, pushing any progress notifications the handler emits
+    /// into `notifications` so the caller can forward them over its transport.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the handler fails to process the request.
+    pub async fn handle_request_with_progress(
+        &self,
+        request: Request,
+        notifications: mpsc::UnboundedSender<Notification>,
+    ) -> Result<Response> {
+        let progress = ProgressSender::new(progress_token(&request), notifications);
+        self.dispatch(request, progress, &self.detached).await
+    }
+
+    #[tracing::instrument(
+        name = "request",
+        skip_all,
+        fields(
+            method = request.method.as_str(),
+            request_id = field::Empty,
+            status = field::Empty,
+            error_category = field::Empty,
+            latency_ms = field::Empty,
+        )
+    )]
+    async fn dispatch(
+        &self,
+        request: Request,
+        progress: ProgressSender,
+        session: &Session,
+    ) -> Result<Response> {
+        if !has_valid_id(&request) {
+            return Ok(invalid_id());
+        }
+        let started = Instant::now();
+        if let Some(id) = &request.id {
+            Span::current().record("request_id", id.to_string().as_str());
+        }
+        let mut request = request;
+        let called_as = request.method.clone();
+        let deprecation = self.resolve_alias(&mut request);
+        let mut response = match unwrap_tool_call(&mut request) {
+            Ok(()) => {
+                self.dispatch_untraced(request, &called_as, progress, session)
+                    .await
+            }
+            Err(error) => Ok(Response {
+                jsonrpc: "2.0".to_string(),
+                result: None,
+                error: Some(error),
+                id: request.id,
+            }),
+        };
+        if let Ok(response) = &mut response {
+            if let Some(warning) = deprecation {
+                add_deprecation_warning(response, &warning);
+            }
+            record_outcome(&Span::current(), started, response);
+        }
+        response
+    }
+
+    /// Points a request for an aliased tool, called directly or through
+    /// `tools/call`, at the tool it now names. Returns the deprecation
+    /// warning to attach to the response, if the alias carries one.
+    fn resolve_alias(&self, request: &mut Request) -> Option<String> {
+        let name = if request.method == "tools/call" {
+            request.params.as_mut()?.get_mut("name")?
+        } else {
+            return self.aliases.get(&request.method).and_then(|alias| {
+                request.method.clone_from(&alias.target);
+                alias.warning()
+            });
+        };
+
+        let alias = self.aliases.get(name.as_str()?)?;
+        *name = serde_json::json!(alias.target);
+        alias.warning()
+    }
+
+    /// Handles `request` once any alias and `tools/call` wrapper are
+    /// resolved; `called_as` is the method the client actually sent.
+    async fn dispatch_untraced(
+        &self,
+        request: Request,
+        called_as: &str,
+        progress: ProgressSender,
+        session: &Session,
+    ) -> Result<Response> {
+        if let Some(params) = &request.params {
+            let limits = &self.capabilities;
+            // Depth counts from the message, as on the wire; `params` sits
+            // one level inside it.
+            let max_depth = limits.max_json_depth.saturating_sub(1);
+            if let Err(detail) = check_value_limits(params, max_depth, limits.max_json_elements) {
+                return Ok(invalid_request(request.id, &detail));
+            }
+        }
+
+        if let Err(retry_after) = self.rate_limiter.check(&request.method) {
+            let retry_after_ms = u64::try_from(retry_after.as_millis()).unwrap_or(u64::MAX);
+            return Ok(Response {
+                jsonrpc: "2.0".to_string(),
+                result: None,
+                error: Some(crate::ErrorObject {
+                    code: ERROR_RATE_LIMITED,
+                    message: "Rate limit exceeded".to_string(),
+                    data: Some(serde_json::json!({ "retryAfterMs": retry_after_ms })),
                 }),
+                id: request.id,
+            });
+        }
+
+        if let Some(response) = self.answer_builtin(&request, session) {
+            return Ok(response);
+        }
+
+        let handlers = self.handlers.read().await;
+
+        if let Some(handler) = handlers.get(&request.method) {
+            if let Some(denied) = self.deny_if_unauthorized(&request) {
+                return Ok(denied);
+            }
+            let cache_key = self
+                .idempotency
+                .as_ref()
+                .filter(|_| !is_dry_run(&request))
+                .and_then(|_| IdempotencyKey::new(session.id, &request));
+            if let Some(cached) = self.cached_response(cache_key.as_ref()) {
+                return Ok(cached);
             }
+            if let Some(shed) = self.shed_if_degraded(&request) {
+                return Ok(shed);
+            }
+            // Held until the call finishes; `None` when no queue is configured.
+            let _slot = match self.queue.as_ref().map(|queue| queue.try_acquire()) {
+                Some(Err(_)) => return Ok(server_busy(request.id)),
+                Some(Ok(slot)) => Some(slot),
+                None => None,
+            };
+            Ok(self
+                .call_tool(handler.as_ref(), called_as, request, progress, cache_key)
+                .await)
         } else {
             Ok(Response {
                 jsonrpc: "2.0".to_string(),
@@ -69,44 +883,2155 @@ impl Server {
         }
     }
 
-    #[must_use]
-    pub fn capabilities(&self) -> &ServerCapabilities {
-        &self.capabilities
+    /// Runs `handler` under the request's deadline, turning panics and
+    /// errors into error responses. A dry run answers `{"dryRun": true,
+    /// "valid": true}` once the handler accepts the arguments, and is
+    /// neither cached nor counted towards the service level. Other calls
+    /// are cached under `cache_key`, if given. Every call,
+    /// dry or not, is reported to the audit sink if one is registered.
+    async fn call_tool(
+        &self,
+        handler: &dyn ToolHandler,
+        method: &str,
+        request: Request,
+        progress: ProgressSender,
+        cache_key: Option<IdempotencyKey>,
+    ) -> Response {
+        let dry_run = is_dry_run(&request);
+        // Secrets never reach the logs or the audit sink, not even hashed.
+        let arguments = request
+            .params
+            .as_ref()
+            .map(|params| self.redactor.redact(params));
+        let audit = self.audit.as_ref().map(|sink| {
+            let entry = AuditEntry {
+                timestamp_ms: self.clock.now_ms(),
+                method: method.to_string(),
+                tool: request.method.clone(),
+                arguments_hash: sink.hash_arguments(arguments.as_ref()),
+                outcome: AuditOutcome::Ok,
+                duration_ms: 0,
+                dry_run,
+            };
+            (sink, entry)
+        });
+        let span = tracing::info_span!(
+            "tool_execution",
+            tool_name = %request.method,
+            status = field::Empty,
+            error_category = field::Empty,
+            latency_ms = field::Empty,
+        );
+        if let Some(arguments) = &arguments {
+            tracing::debug!(parent: &span, %arguments, "calling tool");
+        }
+        let started = Instant::now();
+        let budget =
+            deadline_ms(&request).map(|deadline| remaining_until(deadline, self.clock.now_ms()));
+        let call = AssertUnwindSafe(handler.handle_call(request.params, progress, dry_run))
+            .catch_unwind()
+            .map(|caught| match caught {
+                Ok(result) => result.map_err(crate::ErrorObject::from),
+                Err(payload) => Err(handler_panicked(payload.as_ref())),
+            })
+            .instrument(span.clone());
+        let outcome = match budget {
+            None => call.await,
+            Some(None) => Err(deadline_exceeded().into()),
+            Some(Some(left)) => tokio::time::timeout(left, call)
+                .await
+                .unwrap_or_else(|_| Err(deadline_exceeded().into())),
+        };
+        let outcome = outcome.and_then(|result| self.check_response_size(result));
+
+        let response = match outcome {
+            Ok(_) if dry_run => Response {
+                jsonrpc: "2.0".to_string(),
+                result: Some(serde_json::json!({ "dryRun": true, "valid": true })),
+                error: None,
+                id: request.id,
+            },
+            Ok(result) => Response {
+                jsonrpc: "2.0".to_string(),
+                result: Some(result),
+                error: None,
+                id: request.id,
+            },
+            Err(error) => Response {
+                jsonrpc: "2.0".to_string(),
+                result: None,
+                error: Some(error),
+                id: request.id,
+            },
+        };
+        record_outcome(&span, started, &response);
+        if !dry_run {
+            self.remember_tool_response(&response, cache_key);
+        }
+        if let Some((sink, mut entry)) = audit {
+            entry.duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+            if let Some(error) = &response.error {
+                entry.outcome = AuditOutcome::Error { code: error.code };
+            }
+            sink.record(entry).await;
+        }
+        response
     }
-}
 
-pub struct ServerBuilder {
-    capabilities: ServerCapabilities,
-}
+    /// Passes `result` through unless its serialized size exceeds
+    /// [`ServerCapabilities::max_response_size`], so an oversized frame is
+    /// never attempted.
+    fn check_response_size(
+        &self,
+        result: serde_json::Value,
+    ) -> std::result::Result<serde_json::Value, crate::ErrorObject> {
+        let size = serde_json::to_vec(&result).map_or(0, |bytes| bytes.len());
+        let limit = self.capabilities.max_response_size;
+        if size <= limit {
+            return Ok(result);
+        }
 
-impl ServerBuilder {
-    #[must_use]
-    pub fn new() -> Self {
-        Self {
-            capabilities: ServerCapabilities::default(),
+        warn!(size, limit, "Tool result exceeds the response size limit");
+        Err(crate::ErrorObject {
+            code: ERROR_RESPONSE_TOO_LARGE,
+            message: RESPONSE_TOO_LARGE_MESSAGE.to_string(),
+            data: Some(serde_json::json!({ "size": size, "limit": limit })),
+        })
+    }
+
+    /// Records the outcome for idempotent replay and the service level.
+    /// Invalid params are the caller's fault and do not count as failures.
+    fn remember_tool_response(&self, response: &Response, cache_key: Option<IdempotencyKey>) {
+        if let (Some(cache), Some(key)) = (&self.idempotency, cache_key) {
+            cache.insert(key, response.clone());
+        }
+        if let Some(tracker) = &self.service_level {
+            let failed = response
+                .error
+                .as_ref()
+                .is_some_and(|error| error.code != ERROR_INVALID_PARAMS);
+            tracker.record(failed);
         }
     }
 
-    #[must_use]
-    pub fn with_tool(mut self, tool: Tool) -> Self {
-        self.capabilities.tools.push(tool);
-        self
+    fn deny_if_unauthorized(&self, request: &Request) -> Option<Response> {
+        let missing = self
+            .scopes
+            .missing(&request.method, request.params.as_ref());
+        if missing.is_empty() {
+            return None;
+        }
+
+        let missing: Vec<&str> = missing.iter().map(Scope::as_str).collect();
+        warn!(tool_name = request.method.as_str(), missing = ?missing, "Insufficient scope");
+        Some(Response {
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(crate::ErrorObject {
+                code: ERROR_INSUFFICIENT_SCOPE,
+                message: INSUFFICIENT_SCOPE_MESSAGE.to_string(),
+                data: Some(serde_json::json!({ "missingScopes": missing })),
+            }),
+            id: request.id.clone(),
+        })
     }
 
-    #[must_use]
-    pub fn with_max_request_size(mut self, size: usize) -> Self {
-        self.capabilities.max_request_size = size;
-        self
+    fn shed_if_degraded(&self, request: &Request) -> Option<Response> {
+        let level = self.service_level();
+        let shed = match level {
+            ServiceLevel::Full => false,
+            ServiceLevel::Degraded => self.expensive_tools.contains(&request.method),
+            ServiceLevel::Minimal => true,
+        };
+        if !shed {
+            return None;
+        }
+
+        warn!(tool_name = request.method.as_str(), level = ?level, "Shedding tool call");
+        Some(Response {
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(crate::ErrorObject {
+                code: ERROR_SERVICE_DEGRADED,
+                message: SERVICE_DEGRADED_MESSAGE.to_string(),
+                data: Some(serde_json::json!({ "level": format!("{level:?}") })),
+            }),
+            id: request.id.clone(),
+        })
     }
 
-    #[must_use]
-    pub fn build(self) -> Server {
-        Server::new(self.capabilities)
+    fn cached_response(&self, key: Option<&IdempotencyKey>) -> Option<Response> {
+        let response = self.idempotency.as_ref()?.get(key?)?;
+        tracing::debug!("Replaying cached response for duplicate request id");
+        Some(response)
     }
-}
 
-impl Default for ServerBuilder {
-    fn default() -> Self {
-        Self::new()
+    /// Answers the protocol's own methods, leaving anything else for the
+    /// handler lookup.
+    fn answer_builtin(&self, request: &Request, session: &Session) -> Option<Response> {
+        match request.method.as_str() {
+            "initialize" => Some(self.initialize(request, session)),
+            "tools/list" => Some(self.list_tools(request)),
+            _ => None,
+        }
+    }
+
+    /// One page of tools, ordered by name. The cursor is the last name on
+    /// the previous page, so it stays valid when tools are registered in a
+    /// different order or added between calls.
+    fn list_tools(&self, request: &Request) -> Response {
+        let cursor = match request.params.as_ref().and_then(|p| p.get("cursor")) {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::String(cursor)) => Some(cursor.as_str()),
+            Some(_) => {
+                return Response {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(crate::ErrorObject {
+                        code: ERROR_INVALID_PARAMS,
+                        message: "tools/list 'cursor' must be a string".to_string(),
+                        data: None,
+                    }),
+                    id: request.id.clone(),
+                }
+            }
+        };
+
+        let mut remaining: Vec<&Tool> = self
+            .capabilities
+            .tools
+            .iter()
+            .filter(|tool| cursor.is_none_or(|after| tool.name.as_str() > after))
+            .collect();
+        remaining.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let page = &remaining[..remaining.len().min(self.tools_page_size)];
+        let tools: Vec<_> = page
+            .iter()
+            .map(|tool| {
+                serde_json::json!({
+                    "name": tool.name,
+                    "description": tool.description,
+                    "inputSchema": tool.input_schema,
+                })
+            })
+            .collect();
+
+        let mut result = serde_json::json!({ "tools": tools });
+        if remaining.len() > page.len() {
+            if let Some(last) = page.last() {
+                result["nextCursor"] = serde_json::json!(last.name);
+            }
+        }
+
+        Response {
+            jsonrpc: "2.0".to_string(),
+            result: Some(result),
+            error: None,
+            id: request.id.clone(),
+        }
+    }
+
+    /// Answers `initialize` and marks the server ready, unless it is
+    /// already draining. Features the client's `capabilities` do not
+    /// advertise are switched off for `session`, and the response reports
+    /// what was settled.
+    fn initialize(&self, request: &Request, session: &Session) -> Response {
+        let offered = |feature: &str| {
+            request
+                .params
+                .as_ref()
+                .and_then(|params| params.get("capabilities"))
+                .and_then(|capabilities| capabilities.get(feature))
+                .and_then(serde_json::Value::as_bool)
+                .unwrap_or(false)
+        };
+        let negotiated = NegotiatedCapabilities {
+            batching: self.capabilities.supports_batching && offered("batching"),
+        };
+        *session
+            .negotiated
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = negotiated;
+
+        self.health.send_if_modified(|state| {
+            let starting = *state == HealthState::Starting;
+            if starting {
+                *state = HealthState::Ready;
+            }
+            starting
+        });
+
+        Response {
+            jsonrpc: "2.0".to_string(),
+            result: Some(serde_json::json!({
+                "protocolVersion": MCP_PROTOCOL_VERSION,
+                "serverInfo": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+                "capabilities": {
+                    "tools": {},
+                    "batching": negotiated.batching,
+                    "compression": self.capabilities.supports_compression,
+                },
+            })),
+            error: None,
+            id: request.id.clone(),
+        }
+    }
+
+    #[must_use]
+    pub fn capabilities(&self) -> &ServerCapabilities {
+        &self.capabilities
+    }
+
+    /// What `initialize` settled for requests handled outside
+    /// [`Server::serve`]. Each served connection negotiates its own.
+    #[must_use]
+    pub fn negotiated(&self) -> NegotiatedCapabilities {
+        self.detached.negotiated()
+    }
+
+    /// Sends `first`, together with any other responses already waiting as
+    /// one batch if the client negotiated batching.
+    async fn send_responses<T: Transport>(
+        &self,
+        transport: &mut T,
+        first: Response,
+        waiting: &mut mpsc::UnboundedReceiver<Response>,
+        session: &Session,
+    ) -> Result<()> {
+        if !session.negotiated().batching {
+            return send_response(transport, first).await;
+        }
+
+        let mut batch = vec![first];
+        while let Ok(response) = waiting.try_recv() {
+            batch.push(response);
+        }
+        if batch.len() == 1 {
+            return send_response(transport, batch.remove(0)).await;
+        }
+
+        batch.iter().for_each(validate_response);
+        transport.send_batch(batch).await
+    }
+}
+
+/// Rewrites a `tools/call` into a direct call of the named tool, carrying
+/// `_meta` along so deadlines and progress tokens still apply. Other
+/// requests are left as they are.
+fn unwrap_tool_call(request: &mut Request) -> std::result::Result<(), crate::ErrorObject> {
+    if request.method != "tools/call" {
+        return Ok(());
+    }
+    let mut params = request.params.take().unwrap_or_default();
+    let Some(name) = params.get("name").and_then(serde_json::Value::as_str) else {
+        return Err(crate::ErrorObject {
+            code: ERROR_INVALID_PARAMS,
+            message: "tools/call requires a string 'name'".to_string(),
+            data: None,
+        });
+    };
+    request.method = name.to_string();
+
+    let mut arguments = params.get_mut("arguments").map(serde_json::Value::take);
+    if let Some(meta) = params.get_mut("_meta").map(serde_json::Value::take) {
+        if let serde_json::Value::Object(fields) =
+            arguments.get_or_insert_with(|| serde_json::json!({}))
+        {
+            fields.insert("_meta".to_string(), meta);
+        }
+    }
+    request.params = arguments;
+
+    Ok(())
+}
+
+/// Records `status`, `error_category` and `latency_ms` on a `request` or
+/// `tool_execution` span and emits a closing event so subscribers see them.
+fn record_outcome(span: &Span, started: Instant, response: &Response) {
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    span.record("latency_ms", latency_ms);
+    match &response.error {
+        None => {
+            span.record("status", "ok");
+        }
+        Some(error) => {
+            span.record("status", "error");
+            span.record("error_category", error_category(error));
+        }
+    }
+    tracing::debug!(parent: span, latency_ms, "completed");
+}
+
+fn error_category(error: &crate::ErrorObject) -> &'static str {
+    match error.code {
+        ERROR_PARSE => "parse_error",
+        ERROR_INVALID_REQUEST => "invalid_request",
+        ERROR_METHOD_NOT_FOUND => "method_not_found",
+        ERROR_INVALID_PARAMS => "invalid_params",
+        ERROR_INTERNAL => "internal_error",
+        ERROR_DEADLINE_EXCEEDED if error.message == DEADLINE_EXCEEDED_MESSAGE => {
+            "deadline_exceeded"
+        }
+        ERROR_SERVICE_DEGRADED if error.message == SERVICE_DEGRADED_MESSAGE => "service_degraded",
+        ERROR_SERVER_BUSY if error.message == SERVER_BUSY_MESSAGE => "server_busy",
+        ERROR_INSUFFICIENT_SCOPE if error.message == INSUFFICIENT_SCOPE_MESSAGE => {
+            "insufficient_scope"
+        }
+        ERROR_RATE_LIMITED => "rate_limited",
+        _ => "application_error",
+    }
+}
+
+pub struct ServerBuilder {
+    capabilities: ServerCapabilities,
+    handlers: std::collections::HashMap<String, Box<dyn ToolHandler>>,
+    drain_timeout: Duration,
+    max_concurrency: usize,
+    rate_limits: Vec<(String, u32)>,
+    idempotency_ttl: Option<Duration>,
+    tls: Option<Arc<tokio_rustls::rustls::ServerConfig>>,
+    degradation: Option<DegradationThresholds>,
+    expensive_tools: HashSet<String>,
+    aliases: HashMap<String, ToolAlias>,
+    scopes: ScopePolicy,
+    queue_capacity: Option<usize>,
+    tools_page_size: usize,
+    clock: Arc<dyn Clock>,
+    audit: Option<Arc<dyn AuditSink>>,
+    redactor: Redactor,
+}
+
+impl ServerBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            capabilities: ServerCapabilities::default(),
+            handlers: std::collections::HashMap::new(),
+            drain_timeout: Duration::from_secs(30),
+            max_concurrency: 64,
+            rate_limits: Vec::new(),
+            idempotency_ttl: None,
+            tls: None,
+            degradation: None,
+            expensive_tools: HashSet::new(),
+            aliases: HashMap::new(),
+            scopes: ScopePolicy::new(),
+            queue_capacity: None,
+            tools_page_size: DEFAULT_TOOLS_PAGE_SIZE,
+            clock: Arc::new(SystemClock),
+            audit: None,
+            redactor: Redactor::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_tool(mut self, tool: Tool) -> Self {
+        self.capabilities.tools.push(tool);
+        self
+    }
+
+    /// Advertises `tool` and registers `handler` to serve it.
+    #[must_use]
+    pub fn with_handler(mut self, tool: Tool, handler: impl ToolHandler + 'static) -> Self {
+        self.handlers.insert(tool.name.clone(), Box::new(handler));
+        self.capabilities.tools.push(tool);
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_request_size(mut self, size: usize) -> Self {
+        self.capabilities.max_request_size = size;
+        self
+    }
+
+    /// Caps the serialized size of a tool result, independently of the
+    /// request size limit.
+    #[must_use]
+    pub fn with_max_response_size(mut self, size: usize) -> Self {
+        self.capabilities.max_response_size = size;
+        self
+    }
+
+    /// Rejects requests nested deeper than `max_depth`, counting the
+    /// message itself as the first level, or with more than `max_elements`
+    /// entries in any array or object. Over TCP both are checked before the
+    /// request is parsed.
+    #[must_use]
+    pub fn with_json_limits(mut self, max_depth: usize, max_elements: usize) -> Self {
+        self.capabilities.max_json_depth = max_depth;
+        self.capabilities.max_json_elements = max_elements;
+        self
+    }
+
+    #[must_use]
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.capabilities.supports_compression = enabled;
+        self
+    }
+
+    #[must_use]
+    pub fn with_drain_timeout(mut self, timeout: Duration) -> Self {
+        self.drain_timeout = timeout;
+        self
+    }
+
+    /// Caps how many requests a single [`Server::serve`] loop runs at once.
+    #[must_use]
+    pub fn with_max_concurrency(mut self, limit: usize) -> Self {
+        self.max_concurrency = limit.max(1);
+        self
+    }
+
+    /// Admits at most `capacity` tool calls at once, across every
+    /// connection. Calls beyond that are answered immediately with
+    /// [`SERVER_BUSY_MESSAGE`] and a `retryAfterMs` hint instead of waiting.
+    #[must_use]
+    pub fn with_queue_capacity(mut self, capacity: usize) -> Self {
+        self.queue_capacity = Some(capacity.max(1));
+        self
+    }
+
+    /// Limits `method` to `per_second` calls, allowing bursts of the same size.
+    #[must_use]
+    pub fn with_rate_limit(mut self, method: &str, per_second: u32) -> Self {
+        self.rate_limits.push((method.to_string(), per_second));
+        self
+    }
+
+    /// Replays a tool's response for `ttl` when the same connection, or any
+    /// connection presenting the same `params._meta.idempotencyKey`, repeats
+    /// the call with the same id and arguments, instead of re-running the
+    /// handler. See [`IdempotencyKey`].
+    #[must_use]
+    pub fn with_idempotency(mut self, ttl: Duration) -> Self {
+        self.idempotency_ttl = Some(ttl);
+        self
+    }
+
+    /// Tracks the error rate of tool calls and sheds load when it climbs:
+    /// expensive tools at [`ServiceLevel::Degraded`], every tool at
+    /// [`ServiceLevel::Minimal`].
+    #[must_use]
+    pub fn with_degradation(mut self, thresholds: DegradationThresholds) -> Self {
+        self.degradation = Some(thresholds);
+        self
+    }
+
+    /// Caps how many tools one `tools/list` response carries; clients follow
+    /// `nextCursor` for the rest.
+    #[must_use]
+    pub fn with_tools_page_size(mut self, size: usize) -> Self {
+        self.tools_page_size = size.max(1);
+        self
+    }
+
+    /// Serves requests for `old_name` with `new_name`'s handler. The alias is
+    /// resolved before rate limiting and validation, so both apply under the
+    /// new name.
+    #[must_use]
+    pub fn with_alias(self, old_name: &str, new_name: &str) -> Self {
+        self.alias(old_name, new_name, false)
+    }
+
+    /// Like [`ServerBuilder::with_alias`], but each response to the old name
+    /// carries a `deprecation` warning telling the client to switch.
+    #[must_use]
+    pub fn with_deprecated_alias(self, old_name: &str, new_name: &str) -> Self {
+        self.alias(old_name, new_name, true)
+    }
+
+    fn alias(mut self, old_name: &str, new_name: &str, deprecated: bool) -> Self {
+        self.aliases.insert(
+            old_name.to_string(),
+            ToolAlias {
+                old_name: old_name.to_string(),
+                target: new_name.to_string(),
+                deprecated,
+            },
+        );
+        self
+    }
+
+    /// Only callers whose `params._meta.auth` credential has been granted
+    /// `scope` may call `tool`; others get [`INSUFFICIENT_SCOPE_MESSAGE`].
+    #[must_use]
+    pub fn with_required_scope(mut self, tool: &str, scope: &str) -> Self {
+        self.scopes.require(tool, Scope::new(scope));
+        self
+    }
+
+    /// Grants `scopes` to callers presenting `credential`.
+    #[must_use]
+    pub fn with_grant(mut self, credential: &str, scopes: &[&str]) -> Self {
+        for scope in scopes {
+            self.scopes.grant(credential, Scope::new(scope));
+        }
+        self
+    }
+
+    /// Marks `tool` as the first to be shed when the server is degraded.
+    #[must_use]
+    pub fn with_expensive_tool(mut self, tool: &str) -> Self {
+        self.expensive_tools.insert(tool.to_string());
+        self
+    }
+
+    /// Wraps every connection accepted by [`Server::serve_tcp`] in TLS. See
+    /// [`crate::tls::server_config`] for loading PEM files.
+    #[must_use]
+    pub fn with_tls(mut self, config: Arc<tokio_rustls::rustls::ServerConfig>) -> Self {
+        self.tls = Some(config);
+        self
+    }
+
+    /// Reads time from `clock` instead of the system clock: when checking
+    /// request deadlines, refilling rate limits and timestamping audit
+    /// entries. Replays pass [`crate::replay::ReplayTransport::clock`] so
+    /// deadlines resolve as they did when the session was recorded.
+    #[must_use]
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Redacts tool arguments with `redactor` instead of
+    /// [`Redactor::default`] before they are logged or audited.
+    #[must_use]
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Reports every tool invocation to `sink`.
+    #[must_use]
+    pub fn with_audit_sink(mut self, sink: impl AuditSink + 'static) -> Self {
+        self.audit = Some(Arc::new(sink));
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Returns a server error naming every advertised tool that has no
+    /// handler registered through [`ServerBuilder::with_handler`].
+    pub fn build(self) -> Result<Server> {
+        let unhandled: Vec<&str> = self
+            .capabilities
+            .tools
+            .iter()
+            .map(|tool| tool.name.as_str())
+            .filter(|name| !self.handlers.contains_key(*name))
+            .collect();
+        if !unhandled.is_empty() {
+            return Err(crate::PmcpError::Server(format!(
+                "Tools advertised without a handler: {}",
+                unhandled.join(", ")
+            )));
+        }
+
+        let mut rate_limiter = RateLimiter::new().with_clock(Arc::clone(&self.clock));
+        for (method, per_second) in &self.rate_limits {
+            rate_limiter.set_limit(method, *per_second);
+        }
+
+        Ok(Server {
+            handlers: Arc::new(RwLock::new(self.handlers)),
+            drain_timeout: self.drain_timeout,
+            max_concurrency: self.max_concurrency,
+            rate_limiter: Arc::new(rate_limiter),
+            idempotency: self
+                .idempotency_ttl
+                .map(|ttl| Arc::new(IdempotencyCache::new(ttl))),
+            tls: self.tls.map(TlsAcceptor::from),
+            service_level: self
+                .degradation
+                .map(|thresholds| Arc::new(ServiceLevelTracker::new(thresholds))),
+            expensive_tools: Arc::new(self.expensive_tools),
+            aliases: Arc::new(self.aliases),
+            scopes: Arc::new(self.scopes),
+            queue: self
+                .queue_capacity
+                .map(|capacity| Arc::new(Semaphore::new(capacity))),
+            tools_page_size: self.tools_page_size,
+            clock: self.clock,
+            audit: self.audit,
+            redactor: Arc::new(self.redactor),
+            ..Server::new(self.capabilities)
+        })
+    }
+}
+
+impl Default for ServerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct TickingHandler;
+
+    #[async_trait]
+    impl ToolHandler for TickingHandler {
+        async fn handle(&self, params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+            self.handle_with_progress(params, ProgressSender::disabled())
+                .await
+        }
+
+        async fn handle_with_progress(
+            &self,
+            _params: Option<serde_json::Value>,
+            progress: ProgressSender,
+        ) -> Result<serde_json::Value> {
+            for tick in 1..=3 {
+                progress.report(tick, Some(3));
+            }
+            Ok(json!("done"))
+        }
+    }
+
+    fn request(params: serde_json::Value) -> Request {
+        Request {
+            jsonrpc: "2.0".to_string(),
+            method: "deep_analysis".to_string(),
+            params: Some(params),
+            id: Some(json!(1)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_progress_notifications_in_order() {
+        let server = ServerBuilder::new().build().unwrap();
+        server
+            .register_tool(crate::tools::deep_analysis_tool(), Box::new(TickingHandler))
+            .await;
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let response = server
+            .handle_request_with_progress(request(json!({"_meta": {"progressToken": "abc"}})), tx)
+            .await
+            .unwrap();
+        assert_eq!(response.result, Some(json!("done")));
+
+        let mut ticks = Vec::new();
+        while let Some(notification) = rx.recv().await {
+            assert_eq!(notification.method, "notifications/progress");
+            ticks.push(notification.params.unwrap());
+        }
+
+        assert_eq!(
+            ticks,
+            vec![
+                json!({"progressToken": "abc", "progress": 1, "total": 3}),
+                json!({"progressToken": "abc", "progress": 2, "total": 3}),
+                json!({"progressToken": "abc", "progress": 3, "total": 3}),
+            ]
+        );
+    }
+
+    struct SlowHandler {
+        delay: Duration,
+        completed: Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    #[async_trait]
+    impl ToolHandler for SlowHandler {
+        async fn handle(&self, _params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+            tokio::time::sleep(self.delay).await;
+            self.completed
+                .store(true, std::sync::atomic::Ordering::SeqCst);
+            Ok(json!("slow"))
+        }
+    }
+
+    async fn serve_slow_request(
+        delay: Duration,
+        drain_timeout: Duration,
+    ) -> (bool, Option<Response>) {
+        let completed = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let server = ServerBuilder::new()
+            .with_drain_timeout(drain_timeout)
+            .build()
+            .unwrap();
+        server
+            .register_tool(
+                crate::tools::deep_analysis_tool(),
+                Box::new(SlowHandler {
+                    delay,
+                    completed: completed.clone(),
+                }),
+            )
+            .await;
+
+        let (request_tx, request_rx) = mpsc::channel(8);
+        let (response_tx, mut response_rx) = mpsc::channel(8);
+        let transport = crate::transport::WebSocketTransport::new(response_tx, request_rx);
+
+        let serving = tokio::spawn({
+            let server = server.clone();
+            async move { server.serve(transport).await }
+        });
+
+        request_tx.send(request(json!({}))).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        server.shutdown();
+        serving.await.unwrap().unwrap();
+
+        (
+            completed.load(std::sync::atomic::Ordering::SeqCst),
+            response_rx.try_recv().ok(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_in_flight_request() {
+        let (completed, response) =
+            serve_slow_request(Duration::from_millis(100), Duration::from_secs(5)).await;
+
+        assert!(completed);
+        assert_eq!(response.unwrap().result, Some(json!("slow")));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_abandons_request_after_drain_timeout() {
+        let (completed, response) =
+            serve_slow_request(Duration::from_secs(5), Duration::from_millis(50)).await;
+
+        assert!(!completed);
+        assert!(response.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fast_response_overtakes_slow_one() {
+        let server = ServerBuilder::new().build().unwrap();
+        server
+            .register_tool(
+                crate::tools::deep_analysis_tool(),
+                Box::new(SlowHandler {
+                    delay: Duration::from_millis(200),
+                    completed: Arc::default(),
+                }),
+            )
+            .await;
+        server
+            .register_tool(crate::tools::calculator_tool(), Box::new(TickingHandler))
+            .await;
+
+        let (request_tx, request_rx) = mpsc::channel(8);
+        let (response_tx, mut response_rx) = mpsc::channel(8);
+        let transport = crate::transport::WebSocketTransport::new(response_tx, request_rx);
+        tokio::spawn({
+            let server = server.clone();
+            async move { server.serve(transport).await }
+        });
+
+        request_tx.send(request(json!({}))).await.unwrap();
+        request_tx
+            .send(Request {
+                method: "calculator".to_string(),
+                id: Some(json!(2)),
+                ..request(json!({}))
+            })
+            .await
+            .unwrap();
+
+        let first = response_rx.recv().await.unwrap();
+        let second = response_rx.recv().await.unwrap();
+        assert_eq!(first.id, Some(json!(2)));
+        assert_eq!(second.id, Some(json!(1)));
+
+        server.shutdown();
+    }
+
+    /// Reports one tick, then takes a while to answer.
+    struct ReportThenWaitHandler;
+
+    #[async_trait]
+    impl ToolHandler for ReportThenWaitHandler {
+        async fn handle(&self, params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+            self.handle_with_progress(params, ProgressSender::disabled())
+                .await
+        }
+
+        async fn handle_with_progress(
+            &self,
+            _params: Option<serde_json::Value>,
+            progress: ProgressSender,
+        ) -> Result<serde_json::Value> {
+            progress.report(1, Some(1));
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok(json!("done"))
+        }
+    }
+
+    /// Logs every write in order, taking 50ms over each notification batch.
+    struct SlowNotificationTransport {
+        requests: mpsc::Receiver<Request>,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Transport for SlowNotificationTransport {
+        async fn send(&mut self, response: Response) -> Result<()> {
+            let id = response.id.unwrap_or_default();
+            self.log.lock().unwrap().push(format!("response {id}"));
+            Ok(())
+        }
+
+        async fn receive(&mut self) -> Result<Request> {
+            self.requests.recv().await.ok_or(crate::PmcpError::Closed)
+        }
+
+        async fn send_notification_batch(
+            &mut self,
+            notifications: Vec<Notification>,
+        ) -> Result<()> {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let count = notifications.len();
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("notifications {count}"));
+            Ok(())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_response_during_timed_flush_waits_for_the_batch() {
+        let server = ServerBuilder::new().build().unwrap();
+        server
+            .register_tool(
+                crate::tools::deep_analysis_tool(),
+                Box::new(ReportThenWaitHandler),
+            )
+            .await;
+
+        let (request_tx, requests) = mpsc::channel(8);
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let transport = crate::transport::NotificationBatcher::new(SlowNotificationTransport {
+            requests,
+            log: log.clone(),
+        })
+        .with_interval(Duration::from_millis(10));
+        let serving = tokio::spawn({
+            let server = server.clone();
+            async move { server.serve(transport).await }
+        });
+
+        // The flush starts at 10ms and is still writing when the response
+        // is ready at 20ms.
+        request_tx
+            .send(request(json!({"_meta": {"progressToken": "abc"}})))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        drop(request_tx);
+        serving.await.unwrap().unwrap();
+
+        let log = log.lock().unwrap().clone();
+        assert_eq!(log, vec!["notifications 1", "response 1"]);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_throttles_third_call() {
+        let server = ServerBuilder::new()
+            .with_rate_limit("calculator", 2)
+            .build()
+            .unwrap();
+        server
+            .register_tool(crate::tools::calculator_tool(), Box::new(TickingHandler))
+            .await;
+
+        let calculator = Request {
+            method: "calculator".to_string(),
+            ..request(json!({}))
+        };
+
+        for _ in 0..2 {
+            let response = server.handle_request(calculator.clone()).await.unwrap();
+            assert!(response.error.is_none());
+        }
+
+        let throttled = server.handle_request(calculator).await.unwrap();
+        let error = throttled.error.unwrap();
+        assert_eq!(error.code, ERROR_RATE_LIMITED);
+        assert!(error.data.unwrap()["retryAfterMs"].as_u64().unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_applies_to_tools_call() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), TickingHandler)
+            .with_rate_limit("calculator", 2)
+            .build()
+            .unwrap();
+        let call = Request {
+            method: "tools/call".to_string(),
+            ..request(json!({ "name": "calculator", "arguments": {} }))
+        };
+
+        for _ in 0..2 {
+            let response = server.handle_request(call.clone()).await.unwrap();
+            assert!(response.error.is_none());
+        }
+
+        let throttled = server.handle_request(call).await.unwrap();
+        assert_eq!(throttled.error.unwrap().code, ERROR_RATE_LIMITED);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_refills_by_injected_clock() {
+        let clock = crate::clock::MockClock::new();
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), TickingHandler)
+            .with_rate_limit("calculator", 1)
+            .with_clock(clock.clone())
+            .build()
+            .unwrap();
+        let calculator = Request {
+            method: "calculator".to_string(),
+            ..request(json!({}))
+        };
+        let throttled = |response: Response| {
+            response
+                .error
+                .is_some_and(|error| error.code == ERROR_RATE_LIMITED)
+        };
+
+        assert!(!throttled(
+            server.handle_request(calculator.clone()).await.unwrap()
+        ));
+        assert!(throttled(
+            server.handle_request(calculator.clone()).await.unwrap()
+        ));
+
+        clock.advance(Duration::from_secs(1));
+        assert!(!throttled(server.handle_request(calculator).await.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_progress_without_token_is_silent() {
+        let server = ServerBuilder::new().build().unwrap();
+        server
+            .register_tool(crate::tools::deep_analysis_tool(), Box::new(TickingHandler))
+            .await;
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        server
+            .handle_request_with_progress(request(json!({})), tx)
+            .await
+            .unwrap();
+
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_tool_execution_span_records_tool_and_status() {
+        let server = ServerBuilder::new().build().unwrap();
+        server
+            .register_tool(crate::tools::calculator_tool(), Box::new(TickingHandler))
+            .await;
+
+        let response = server
+            .handle_request(Request {
+                method: "calculator".to_string(),
+                ..request(json!({}))
+            })
+            .await
+            .unwrap();
+
+        assert!(response.error.is_none());
+        assert!(logs_contain("tool_execution{tool_name=calculator"));
+        assert!(logs_contain("status=\"ok\""));
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_logged_arguments_are_redacted() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), TickingHandler)
+            .build()
+            .unwrap();
+
+        server
+            .handle_request(Request {
+                method: "calculator".to_string(),
+                ..request(json!({ "token": "s3cret", "path": "src" }))
+            })
+            .await
+            .unwrap();
+
+        assert!(logs_contain(r#""token":"***""#));
+        assert!(logs_contain(r#""path":"src""#));
+        assert!(!logs_contain("s3cret"));
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_request_span_carries_structured_fields() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), TickingHandler)
+            .build()
+            .unwrap();
+
+        server
+            .handle_request(Request {
+                method: "calculator".to_string(),
+                id: Some(json!(42)),
+                ..request(json!({}))
+            })
+            .await
+            .unwrap();
+
+        assert!(logs_contain("method=\"calculator\""));
+        assert!(logs_contain("request_id=\"42\""));
+        assert!(logs_contain("latency_ms="));
+    }
+
+    struct RecordingNotificationHandler {
+        received: mpsc::UnboundedSender<Option<serde_json::Value>>,
+    }
+
+    #[async_trait]
+    impl NotificationHandler for RecordingNotificationHandler {
+        async fn handle(&self, params: Option<serde_json::Value>) -> Result<()> {
+            let _ = self.received.send(params);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notification_is_routed_without_response() {
+        let server = ServerBuilder::new().build().unwrap();
+        let (received_tx, mut received_rx) = mpsc::unbounded_channel();
+        server
+            .register_notification(
+                "notifications/initialized",
+                Box::new(RecordingNotificationHandler {
+                    received: received_tx,
+                }),
+            )
+            .await;
+
+        let (request_tx, request_rx) = mpsc::channel(8);
+        let (response_tx, mut response_rx) = mpsc::channel(8);
+        let transport = crate::transport::WebSocketTransport::new(response_tx, request_rx);
+        let serving = tokio::spawn({
+            let server = server.clone();
+            async move { server.serve(transport).await }
+        });
+
+        request_tx
+            .send(Request {
+                jsonrpc: "2.0".to_string(),
+                method: "notifications/initialized".to_string(),
+                params: Some(json!({ "ready": true })),
+                id: None,
+            })
+            .await
+            .unwrap();
+
+        let params = received_rx.recv().await.unwrap();
+        assert_eq!(params, Some(json!({ "ready": true })));
+
+        server.shutdown();
+        serving.await.unwrap().unwrap();
+        assert!(response_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_with_handler_serves_advertised_tool() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), TickingHandler)
+            .build()
+            .unwrap();
+
+        let response = server
+            .handle_request(Request {
+                method: "calculator".to_string(),
+                ..request(json!({}))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(server.capabilities().tools.len(), 1);
+        assert_eq!(response.result, Some(json!("done")));
+    }
+
+    #[test]
+    fn test_build_rejects_tool_without_handler() {
+        let result = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), TickingHandler)
+            .with_tool(crate::tools::deep_analysis_tool())
+            .build();
+
+        let Err(crate::PmcpError::Server(message)) = result else {
+            panic!("expected a server error");
+        };
+        assert!(message.contains("deep_analysis"));
+        assert!(!message.contains("calculator"));
+    }
+
+    #[tokio::test]
+    async fn test_readiness_follows_initialize_and_shutdown() {
+        let server = ServerBuilder::new().build().unwrap();
+        assert!(server.liveness().ok);
+        assert!(!server.readiness().ok);
+        assert!(!server.startup().ok);
+
+        let response = server
+            .handle_request(Request {
+                method: "initialize".to_string(),
+                ..request(json!({}))
+            })
+            .await
+            .unwrap();
+        assert!(response.error.is_none());
+        assert_eq!(server.health_state(), HealthState::Ready);
+        assert!(server.readiness().ok);
+        assert!(server.startup().ok);
+
+        server.shutdown();
+        let readiness = server.readiness();
+        assert!(!readiness.ok);
+        assert_eq!(readiness.http_status(), 503);
+        assert_eq!(readiness.detail.as_deref(), Some("draining"));
+        assert!(server.liveness().ok);
+    }
+
+    fn epoch_ms_from_now(offset: Duration, past: bool) -> u64 {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap();
+        let at = if past {
+            now.checked_sub(offset).unwrap()
+        } else {
+            now + offset
+        };
+        u64::try_from(at.as_millis()).unwrap()
+    }
+
+    async fn call_with_deadline(delay: Duration, deadline_ms: u64) -> (bool, Response) {
+        let completed = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let server = ServerBuilder::new()
+            .with_handler(
+                crate::tools::deep_analysis_tool(),
+                SlowHandler {
+                    delay,
+                    completed: completed.clone(),
+                },
+            )
+            .build()
+            .unwrap();
+
+        let response = server
+            .handle_request(request(json!({ "_meta": { "deadlineMs": deadline_ms } })))
+            .await
+            .unwrap();
+        (
+            completed.load(std::sync::atomic::Ordering::SeqCst),
+            response,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_expired_deadline_skips_handler() {
+        let deadline = epoch_ms_from_now(Duration::from_secs(1), true);
+        let (completed, response) = call_with_deadline(Duration::ZERO, deadline).await;
+
+        assert!(!completed);
+        let error = response.error.unwrap();
+        assert_eq!(error.code, ERROR_DEADLINE_EXCEEDED);
+        assert_eq!(error.message, DEADLINE_EXCEEDED_MESSAGE);
+    }
+
+    #[tokio::test]
+    async fn test_future_deadline_lets_handler_finish() {
+        let deadline = epoch_ms_from_now(Duration::from_secs(30), false);
+        let (completed, response) = call_with_deadline(Duration::from_millis(10), deadline).await;
+
+        assert!(completed);
+        assert_eq!(response.result, Some(json!("slow")));
+    }
+
+    #[tokio::test]
+    async fn test_deadline_bounds_handler_runtime() {
+        let deadline = epoch_ms_from_now(Duration::from_millis(50), false);
+        let (completed, response) = call_with_deadline(Duration::from_secs(5), deadline).await;
+
+        assert!(!completed);
+        assert_eq!(response.error.unwrap().code, ERROR_DEADLINE_EXCEEDED);
+    }
+
+    struct CountingHandler {
+        calls: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl ToolHandler for CountingHandler {
+        async fn handle(&self, _params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+            let calls = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            Ok(json!(calls))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_request_id_runs_handler_once() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let server = ServerBuilder::new()
+            .with_handler(
+                crate::tools::deep_analysis_tool(),
+                CountingHandler {
+                    calls: calls.clone(),
+                },
+            )
+            .with_idempotency(Duration::from_secs(30))
+            .build()
+            .unwrap();
+
+        let first = server.handle_request(request(json!({}))).await.unwrap();
+        let second = server.handle_request(request(json!({}))).await.unwrap();
+
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(first, second);
+        assert_eq!(second.result, Some(json!(1)));
+    }
+
+    #[tokio::test]
+    async fn test_reused_id_for_another_method_is_not_replayed() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counting = || CountingHandler {
+            calls: calls.clone(),
+        };
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::deep_analysis_tool(), counting())
+            .with_handler(crate::tools::calculator_tool(), counting())
+            .with_idempotency(Duration::from_secs(30))
+            .build()
+            .unwrap();
+
+        server.handle_request(request(json!({}))).await.unwrap();
+        let calculator = server
+            .handle_request(Request {
+                method: "calculator".to_string(),
+                ..request(json!({}))
+            })
+            .await
+            .unwrap();
+        let initialize = server
+            .handle_request(Request {
+                method: "initialize".to_string(),
+                ..request(json!({}))
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(calculator.result, Some(json!(2)));
+        assert!(initialize.result.unwrap().get("protocolVersion").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_same_id_on_another_connection_is_not_replayed() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let server = ServerBuilder::new()
+            .with_handler(
+                crate::tools::deep_analysis_tool(),
+                CountingHandler {
+                    calls: calls.clone(),
+                },
+            )
+            .with_idempotency(Duration::from_secs(30))
+            .build()
+            .unwrap();
+
+        let mut results = Vec::new();
+        for _ in 0..2 {
+            let (client_end, server_end) = crate::transport::InMemoryTransport::pair();
+            let serving = tokio::spawn({
+                let server = server.clone();
+                async move { server.serve(server_end).await }
+            });
+            let client = crate::client::Client::new(client_end);
+            results.push(client.call("deep_analysis", Some(json!({}))).await.unwrap());
+            drop(client);
+            serving.await.unwrap().unwrap();
+        }
+
+        assert_eq!(results, [json!(1), json!(2)]);
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_token_on_another_connection_is_replayed() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let server = ServerBuilder::new()
+            .with_handler(
+                crate::tools::deep_analysis_tool(),
+                CountingHandler {
+                    calls: calls.clone(),
+                },
+            )
+            .with_idempotency(Duration::from_secs(30))
+            .build()
+            .unwrap();
+        let params = json!({ "_meta": { "idempotencyKey": "upload-5c1e" } });
+
+        let mut results = Vec::new();
+        for _ in 0..2 {
+            let (client_end, server_end) = crate::transport::InMemoryTransport::pair();
+            let serving = tokio::spawn({
+                let server = server.clone();
+                async move { server.serve(server_end).await }
+            });
+            let client = crate::client::Client::new(client_end);
+            results.push(
+                client
+                    .call("deep_analysis", Some(params.clone()))
+                    .await
+                    .unwrap(),
+            );
+            drop(client);
+            serving.await.unwrap().unwrap();
+        }
+
+        assert_eq!(results, [json!(1), json!(1)]);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    async fn wait_for_connections(server: &Server, expected: usize) {
+        for _ in 0..200 {
+            if server.metrics().active_connections() == expected {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        panic!(
+            "expected {expected} active connections, saw {}",
+            server.metrics().active_connections()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_active_connections_gauge() {
+        let server = ServerBuilder::new().build().unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let serving = tokio::spawn({
+            let server = server.clone();
+            async move { server.serve_tcp(listener).await }
+        });
+
+        let first = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let second = tokio::net::TcpStream::connect(addr).await.unwrap();
+        wait_for_connections(&server, 2).await;
+
+        drop(first);
+        wait_for_connections(&server, 1).await;
+
+        drop(second);
+        wait_for_connections(&server, 0).await;
+
+        server.shutdown();
+        serving.await.unwrap().unwrap();
+    }
+
+    struct PanickingHandler;
+
+    #[async_trait]
+    impl ToolHandler for PanickingHandler {
+        async fn handle(&self, params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+            assert!(!params.is_some_and(|p| p["explode"] == true), "boom");
+            Ok(json!("fine"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handler_panic_becomes_internal_error() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), PanickingHandler)
+            .build()
+            .unwrap();
+        let call = |explode: bool, id: i64| Request {
+            jsonrpc: "2.0".to_string(),
+            method: "calculator".to_string(),
+            params: Some(json!({ "explode": explode })),
+            id: Some(json!(id)),
+        };
+
+        let response = server.handle_request(call(true, 1)).await.unwrap();
+        let error = response.error.unwrap();
+        assert_eq!(error.code, ERROR_INTERNAL);
+        assert_eq!(error.data.unwrap()["reason"], "handler panicked");
+
+        let response = server.handle_request(call(false, 2)).await.unwrap();
+        assert_eq!(response.result, Some(json!("fine")));
+    }
+
+    #[tokio::test]
+    async fn test_routes_distinguish_handled_and_unhandled_tools() {
+        let mut server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), FlakyHandler)
+            .with_handler(crate::tools::deep_analysis_tool(), FlakyHandler)
+            .with_required_scope("deep_analysis", "analysis")
+            .with_rate_limit("calculator", 5)
+            .with_alias("calc", "calculator")
+            .build()
+            .unwrap();
+        // `build` refuses unhandled tools, so advertise one afterwards.
+        server
+            .capabilities
+            .tools
+            .push(crate::tools::extract_files_tool());
+
+        let routes = server.routes().await;
+
+        let route = |method: &str| routes.iter().find(|route| route.method == method).unwrap();
+        assert_eq!(
+            routes
+                .iter()
+                .map(|route| route.method.as_str())
+                .collect::<Vec<_>>(),
+            ["calc", "calculator", "deep_analysis", "extract_files"]
+        );
+        assert!(route("calculator").has_handler);
+        assert_eq!(route("calculator").rate_limit, Some(5));
+        assert_eq!(
+            route("deep_analysis").required_scopes,
+            [Scope::new("analysis")]
+        );
+        assert!(!route("extract_files").has_handler);
+        assert!(route("extract_files").advertised);
+        assert_eq!(route("calc").alias_for.as_deref(), Some("calculator"));
+        assert!(route("calc").has_handler && !route("calc").advertised);
+    }
+
+    struct LargeResultHandler;
+
+    #[async_trait]
+    impl ToolHandler for LargeResultHandler {
+        async fn handle(&self, params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+            let len = params
+                .and_then(|p| p["len"].as_u64())
+                .and_then(|len| usize::try_from(len).ok())
+                .unwrap_or_default();
+            Ok(json!("x".repeat(len)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_oversized_result_is_replaced_by_error() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), LargeResultHandler)
+            .with_max_response_size(1_024)
+            .build()
+            .unwrap();
+        let call = |len: usize| Request {
+            jsonrpc: "2.0".to_string(),
+            method: "calculator".to_string(),
+            params: Some(json!({ "len": len })),
+            id: Some(json!(1)),
+        };
+
+        let small = server.handle_request(call(100)).await.unwrap();
+        assert!(small.result.is_some());
+
+        let large = server.handle_request(call(1_000_000)).await.unwrap();
+        assert!(large.result.is_none());
+        let error = large.error.unwrap();
+        assert_eq!(error.code, ERROR_RESPONSE_TOO_LARGE);
+        assert_eq!(error.message, RESPONSE_TOO_LARGE_MESSAGE);
+        assert_eq!(
+            error.data,
+            Some(json!({ "size": 1_000_002, "limit": 1_024 }))
+        );
+    }
+
+    struct ReservedCodeHandler;
+
+    #[async_trait]
+    impl ToolHandler for ReservedCodeHandler {
+        async fn handle(&self, _params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+            Err(crate::PmcpError::JsonRpc {
+                code: ERROR_METHOD_NOT_FOUND,
+                message: "no such operation".to_string(),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reserved_handler_error_code_is_remapped() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), ReservedCodeHandler)
+            .build()
+            .unwrap();
+
+        let response = server
+            .handle_request(Request {
+                jsonrpc: "2.0".to_string(),
+                method: "calculator".to_string(),
+                params: None,
+                id: Some(json!(1)),
+            })
+            .await
+            .unwrap();
+
+        let error = response.error.unwrap();
+        assert!(crate::protocol::is_server_error_code(error.code));
+        assert_eq!(error.message, "no such operation");
+        assert_eq!(
+            error.data,
+            Some(json!({ "originalCode": ERROR_METHOD_NOT_FOUND }))
+        );
+    }
+
+    struct FlakyHandler;
+
+    #[async_trait]
+    impl ToolHandler for FlakyHandler {
+        async fn handle(&self, params: Option<serde_json::Value>) -> Result<serde_json::Value> {
+            if params.is_some_and(|p| p["fail"] == true) {
+                return Err(crate::PmcpError::Tool("backend down".to_string()));
+            }
+            Ok(json!("ok"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_degraded_server_sheds_expensive_tools() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), FlakyHandler)
+            .with_handler(crate::tools::deep_analysis_tool(), FlakyHandler)
+            .with_expensive_tool("deep_analysis")
+            .with_degradation(DegradationThresholds {
+                min_samples: 4,
+                degraded_error_rate: 0.25,
+                minimal_error_rate: 0.9,
+                ..DegradationThresholds::default()
+            })
+            .build()
+            .unwrap();
+        let call = |method: &str, fail: bool| Request {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params: Some(json!({ "fail": fail })),
+            id: Some(json!(1)),
+        };
+
+        for fail in [false, false, true, true] {
+            server
+                .handle_request(call("calculator", fail))
+                .await
+                .unwrap();
+        }
+        assert_eq!(server.service_level(), ServiceLevel::Degraded);
+
+        let shed = server
+            .handle_request(call("deep_analysis", false))
+            .await
+            .unwrap();
+        let error = shed.error.unwrap();
+        assert_eq!(error.code, ERROR_SERVICE_DEGRADED);
+        assert_eq!(error.message, SERVICE_DEGRADED_MESSAGE);
+
+        let cheap = server
+            .handle_request(call("calculator", false))
+            .await
+            .unwrap();
+        assert_eq!(cheap.result, Some(json!("ok")));
+    }
+
+    #[tokio::test]
+    async fn test_tools_list_pages_by_cursor() {
+        let server = ["echo", "add", "sort", "diff", "count"]
+            .into_iter()
+            .fold(
+                ServerBuilder::new().with_tools_page_size(2),
+                |builder, name| {
+                    let tool = Tool {
+                        name: name.to_string(),
+                        ..crate::tools::calculator_tool()
+                    };
+                    builder.with_handler(tool, FlakyHandler)
+                },
+            )
+            .build()
+            .unwrap();
+
+        let mut pages = Vec::new();
+        let mut cursor = None;
+        loop {
+            let response = server
+                .handle_request(Request {
+                    jsonrpc: "2.0".to_string(),
+                    method: "tools/list".to_string(),
+                    params: cursor.map(|cursor| json!({ "cursor": cursor })),
+                    id: Some(json!(pages.len())),
+                })
+                .await
+                .unwrap();
+            let result = response.result.unwrap();
+            let names: Vec<String> = result["tools"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|tool| tool["name"].as_str().unwrap().to_string())
+                .collect();
+            pages.push(names);
+            match result.get("nextCursor") {
+                Some(next) => cursor = Some(next.clone()),
+                None => break,
+            }
+        }
+
+        assert_eq!(
+            pages,
+            [vec!["add", "count"], vec!["diff", "echo"], vec!["sort"],]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deeply_nested_payload_is_rejected_unparsed() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), FlakyHandler)
+            .build()
+            .unwrap();
+
+        let depth = 100_000;
+        let nested = format!("{}1{}", r#"{"a":"#.repeat(depth), "}".repeat(depth));
+        let raw = format!(r#"{{"jsonrpc":"2.0","method":"calculator","id":1,"params":{nested}}}"#);
+        let response = server.handle_raw(raw.as_bytes()).await.unwrap();
+        assert_eq!(response.error.unwrap().code, ERROR_INVALID_REQUEST);
+
+        let wide = format!("[{}0]", "0,".repeat(20_000));
+        let raw = format!(r#"{{"jsonrpc":"2.0","method":"calculator","id":1,"params":{wide}}}"#);
+        let response = server.handle_raw(raw.as_bytes()).await.unwrap();
+        assert_eq!(response.error.unwrap().code, ERROR_INVALID_REQUEST);
+
+        let within = server
+            .handle_raw(br#"{"jsonrpc":"2.0","method":"calculator","id":1,"params":{"a":[1,2]}}"#)
+            .await
+            .unwrap();
+        assert_eq!(within.result, Some(json!("ok")));
+    }
+
+    #[tokio::test]
+    async fn test_parsed_params_over_depth_limit_are_rejected() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), FlakyHandler)
+            .with_json_limits(8, 100)
+            .build()
+            .unwrap();
+        let params = (0..10).fold(json!(1), |inner, _| json!({ "a": inner }));
+
+        let response = server
+            .handle_request(Request {
+                jsonrpc: "2.0".to_string(),
+                method: "calculator".to_string(),
+                params: Some(params),
+                id: Some(json!(7)),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.id, Some(json!(7)));
+        assert_eq!(response.error.unwrap().code, ERROR_INVALID_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_depth_limit_is_the_same_raw_or_parsed() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), FlakyHandler)
+            .with_json_limits(3, 100)
+            .build()
+            .unwrap();
+        let raw = |params: &str| {
+            format!(r#"{{"jsonrpc":"2.0","method":"calculator","id":1,"params":{params}}}"#)
+        };
+
+        for (params, accepted) in [(r#"{"a":{"b":1}}"#, true), (r#"{"a":{"b":{}}}"#, false)] {
+            let unparsed = server.handle_raw(raw(params).as_bytes()).await.unwrap();
+            let parsed = server
+                .handle_request(serde_json::from_str(&raw(params)).unwrap())
+                .await
+                .unwrap();
+
+            assert_eq!(unparsed.error.is_none(), accepted, "{params} raw");
+            assert_eq!(parsed.error.is_none(), accepted, "{params} parsed");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tcp_frame_over_json_limits_is_rejected_before_parsing() {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), FlakyHandler)
+            .with_json_limits(8, 100)
+            .build()
+            .unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let serving = tokio::spawn({
+            let server = server.clone();
+            async move { server.serve_tcp(listener).await }
+        });
+
+        let (reader, mut writer) = tokio::net::TcpStream::connect(addr)
+            .await
+            .unwrap()
+            .into_split();
+        let nested = format!("{}1{}", "[".repeat(100), "]".repeat(100));
+        let deep = format!(
+            "{{\"jsonrpc\":\"2.0\",\"method\":\"calculator\",\"id\":1,\"params\":{nested}}}\n"
+        );
+        writer.write_all(deep.as_bytes()).await.unwrap();
+        writer
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"method\":\"calculator\",\"id\":2,\"params\":{}}\n")
+            .await
+            .unwrap();
+
+        let mut lines = BufReader::new(reader).lines();
+        let rejected: Response =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        let answered: Response =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(rejected.id, None);
+        assert_eq!(rejected.error.unwrap().code, ERROR_INVALID_REQUEST);
+        assert_eq!(answered.id, Some(json!(2)));
+
+        server.shutdown();
+        drop(writer);
+        serving.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_aliased_tool_dispatches_to_new_handler() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), FlakyHandler)
+            .with_alias("calc", "calculator")
+            .with_deprecated_alias("old_calc", "calculator")
+            .build()
+            .unwrap();
+        let call = |method: &str, params: serde_json::Value| Request {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params: Some(params),
+            id: Some(json!(1)),
+        };
+
+        let direct = server
+            .handle_request(call("calc", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(direct.result, Some(json!("ok")));
+
+        let wrapped = server
+            .handle_request(call("tools/call", json!({ "name": "calc" })))
+            .await
+            .unwrap();
+        assert_eq!(wrapped.result, Some(json!("ok")));
+
+        let failed = server
+            .handle_request(call("old_calc", json!({ "fail": true })))
+            .await
+            .unwrap();
+        let data = failed.error.unwrap().data.unwrap();
+        assert!(data["deprecation"]
+            .as_str()
+            .unwrap()
+            .contains("call 'calculator' instead"));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_is_refused_unless_handler_opts_in() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), FlakyHandler)
+            .build()
+            .unwrap();
+
+        let response = server
+            .handle_request(Request {
+                jsonrpc: "2.0".to_string(),
+                method: "calculator".to_string(),
+                params: Some(json!({ "_meta": { "dryRun": true } })),
+                id: Some(json!(1)),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response.error.unwrap().code, ERROR_INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_missing_scope_blocks_only_protected_tools() {
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), FlakyHandler)
+            .with_handler(crate::tools::deep_analysis_tool(), FlakyHandler)
+            .with_required_scope("deep_analysis", "analysis")
+            .with_grant("analyst-token", &["analysis"])
+            .with_grant("basic-token", &["read"])
+            .build()
+            .unwrap();
+        let call = |method: &str, credential: &str| Request {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params: Some(json!({ "_meta": { "auth": credential } })),
+            id: Some(json!(1)),
+        };
+
+        let denied = server
+            .handle_request(call("deep_analysis", "basic-token"))
+            .await
+            .unwrap();
+        let error = denied.error.unwrap();
+        assert_eq!(error.code, ERROR_INSUFFICIENT_SCOPE);
+        assert_eq!(error.message, INSUFFICIENT_SCOPE_MESSAGE);
+        assert_eq!(error.data.unwrap()["missingScopes"], json!(["analysis"]));
+
+        let calculator = server
+            .handle_request(call("calculator", "basic-token"))
+            .await
+            .unwrap();
+        assert_eq!(calculator.result, Some(json!("ok")));
+
+        let allowed = server
+            .handle_request(call("deep_analysis", "analyst-token"))
+            .await
+            .unwrap();
+        assert_eq!(allowed.result, Some(json!("ok")));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_full_queue_answers_busy() {
+        let completed = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let handler = SlowHandler {
+            delay: Duration::from_secs(30),
+            completed: Arc::clone(&completed),
+        };
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::deep_analysis_tool(), handler)
+            .with_queue_capacity(1)
+            .build()
+            .unwrap();
+        let call = |id: u64| Request {
+            jsonrpc: "2.0".to_string(),
+            method: "deep_analysis".to_string(),
+            params: None,
+            id: Some(json!(id)),
+        };
+
+        let first = tokio::spawn({
+            let server = server.clone();
+            async move { server.handle_request(call(1)).await }
+        });
+        tokio::task::yield_now().await;
+
+        let overflow = server.handle_request(call(2)).await.unwrap();
+        let error = overflow.error.unwrap();
+        assert_eq!(error.code, ERROR_SERVER_BUSY);
+        assert_eq!(error.message, SERVER_BUSY_MESSAGE);
+        assert!(error.data.unwrap()["retryAfterMs"].as_u64().unwrap() > 0);
+
+        let finished = first.await.unwrap().unwrap();
+        assert_eq!(finished.result, Some(json!("slow")));
+        assert!(completed.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    /// Replays `requests`, then reports the connection closed, recording
+    /// whether responses went out one by one or as batches.
+    #[derive(Default)]
+    struct ScriptedTransport {
+        requests: std::collections::VecDeque<Request>,
+        singles: Arc<Mutex<Vec<Response>>>,
+        batches: Arc<Mutex<Vec<Vec<Response>>>>,
+    }
+
+    #[async_trait]
+    impl Transport for ScriptedTransport {
+        async fn send(&mut self, response: Response) -> Result<()> {
+            self.singles.lock().unwrap().push(response);
+            Ok(())
+        }
+
+        async fn receive(&mut self) -> Result<Request> {
+            self.requests
+                .pop_front()
+                .ok_or_else(|| crate::PmcpError::Transport("Closed".to_string()))
+        }
+
+        async fn send_batch(&mut self, responses: Vec<Response>) -> Result<()> {
+            self.batches.lock().unwrap().push(responses);
+            Ok(())
+        }
+    }
+
+    fn initialize(batching: bool) -> Request {
+        Request {
+            jsonrpc: "2.0".to_string(),
+            method: "initialize".to_string(),
+            params: Some(json!({ "capabilities": { "batching": batching } })),
+            id: Some(json!(0)),
+        }
+    }
+
+    /// Serves `initialize` (if given) followed by three `tools/list` calls
+    /// on one connection.
+    async fn serve_after(server: &Server, initialize: Option<Request>) -> ScriptedTransport {
+        let lists = (1..=3).map(|id| Request {
+            jsonrpc: "2.0".to_string(),
+            method: "tools/list".to_string(),
+            params: None,
+            id: Some(json!(id)),
+        });
+        let transport = ScriptedTransport {
+            requests: initialize.into_iter().chain(lists).collect(),
+            ..ScriptedTransport::default()
+        };
+        let recorded = ScriptedTransport {
+            singles: Arc::clone(&transport.singles),
+            batches: Arc::clone(&transport.batches),
+            ..ScriptedTransport::default()
+        };
+        server.serve(transport).await.unwrap();
+        recorded
+    }
+
+    #[tokio::test]
+    async fn test_client_without_batching_gets_individual_responses() {
+        let server = ServerBuilder::new().build().unwrap();
+        let recorded = serve_after(&server, Some(initialize(false))).await;
+
+        assert!(recorded.batches.lock().unwrap().is_empty());
+        assert_eq!(recorded.singles.lock().unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_client_with_batching_gets_waiting_responses_batched() {
+        let server = ServerBuilder::new().build().unwrap();
+        let recorded = serve_after(&server, Some(initialize(true))).await;
+
+        let batches = recorded.batches.lock().unwrap();
+        let singles = recorded.singles.lock().unwrap();
+        assert!(!batches.is_empty());
+        assert_eq!(
+            batches.iter().map(Vec::len).sum::<usize>() + singles.len(),
+            4
+        );
+    }
+
+    #[tokio::test]
+    async fn test_negotiation_does_not_leak_between_connections() {
+        let server = ServerBuilder::new().build().unwrap();
+        serve_after(&server, Some(initialize(true))).await;
+
+        let recorded = serve_after(&server, None).await;
+
+        assert!(recorded.batches.lock().unwrap().is_empty());
+        assert_eq!(recorded.singles.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_object_or_array_id_is_rejected_before_dispatch() {
+        let server = ServerBuilder::new().build().unwrap();
+        let with_id = |id: serde_json::Value| Request {
+            jsonrpc: "2.0".to_string(),
+            method: "tools/list".to_string(),
+            params: None,
+            id: Some(id),
+        };
+        let transport = ScriptedTransport {
+            requests: [with_id(json!({})), with_id(json!([])), with_id(json!(1))].into(),
+            ..ScriptedTransport::default()
+        };
+        let singles = Arc::clone(&transport.singles);
+
+        server.serve(transport).await.unwrap();
+
+        let singles = singles.lock().unwrap().clone();
+        let rejected = singles
+            .iter()
+            .filter(|r| r.id.is_none() && r.error.as_ref().unwrap().code == ERROR_INVALID_REQUEST)
+            .count();
+        assert_eq!(rejected, 2);
+        assert!(singles.iter().any(|r| r.id == Some(json!(1))));
+        let direct = server.handle_request(with_id(json!({}))).await.unwrap();
+        assert_eq!(direct.error.unwrap().code, ERROR_INVALID_REQUEST);
+        assert_eq!(direct.id, None);
+    }
+
+    #[tokio::test]
+    async fn test_features_are_off_until_negotiated() {
+        let server = ServerBuilder::new().build().unwrap();
+        assert_eq!(server.negotiated(), NegotiatedCapabilities::default());
+
+        let response = server.handle_request(initialize(true)).await.unwrap();
+
+        assert_eq!(
+            server.negotiated(),
+            NegotiatedCapabilities { batching: true }
+        );
+        assert_eq!(response.result.unwrap()["capabilities"]["batching"], true);
+    }
+
+    #[derive(Clone, Default)]
+    struct MemoryAuditSink(Arc<Mutex<Vec<AuditEntry>>>);
+
+    #[async_trait]
+    impl AuditSink for MemoryAuditSink {
+        fn hash_arguments(&self, arguments: Option<&serde_json::Value>) -> String {
+            crate::audit::AuditKey::new(b"test").hash_arguments(arguments)
+        }
+
+        async fn record(&self, entry: AuditEntry) {
+            self.0.lock().unwrap().push(entry);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_records_one_entry_per_tool_call() {
+        let sink = MemoryAuditSink::default();
+        let server = ServerBuilder::new()
+            .with_handler(crate::tools::calculator_tool(), TickingHandler)
+            .with_clock(crate::clock::MockClock::at(1_700_000_000_000))
+            .with_audit_sink(sink.clone())
+            .build()
+            .unwrap();
+        let call = |method: &str, params: serde_json::Value| Request {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params: Some(params),
+            id: Some(json!(1)),
+        };
+
+        server
+            .handle_request(call(
+                "tools/call",
+                json!({ "name": "calculator", "arguments": { "a": 1, "b": 2 } }),
+            ))
+            .await
+            .unwrap();
+        server
+            .handle_request(call("calculator", json!({ "a": 1, "b": 2 })))
+            .await
+            .unwrap();
+        server
+            .handle_request(call("tools/list", json!({})))
+            .await
+            .unwrap();
+
+        let entries = sink.0.lock().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].method, "tools/call");
+        assert_eq!(entries[1].method, "calculator");
+        assert!(entries.iter().all(|entry| entry.tool == "calculator"
+            && entry.outcome == AuditOutcome::Ok
+            && entry.timestamp_ms == 1_700_000_000_000));
+        assert_eq!(entries[0].arguments_hash, entries[1].arguments_hash);
     }
 }
@@ -0,0 +1,84 @@
+use crate::{PmcpError, Result};
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+use tokio_rustls::rustls::crypto::ring;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::{version, ClientConfig, RootCertStore, ServerConfig};
+
+fn open(path: &Path) -> Result<BufReader<std::fs::File>> {
+    std::fs::File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| PmcpError::Transport(format!("{}: {e}", path.display())))
+}
+
+/// Reads every certificate from a PEM file, leaf first.
+///
+/// # Errors
+///
+/// Returns a transport error if the file cannot be read or holds no
+/// certificates.
+pub fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = rustls_pemfile::certs(&mut open(path)?)
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(|e| PmcpError::Transport(format!("{}: {e}", path.display())))?;
+
+    if certs.is_empty() {
+        return Err(PmcpError::Transport(format!(
+            "{}: no certificates found",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+/// Reads the first PKCS#8, PKCS#1 or SEC1 private key from a PEM file.
+///
+/// # Errors
+///
+/// Returns a transport error if the file cannot be read or holds no key.
+pub fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    rustls_pemfile::private_key(&mut open(path)?)
+        .map_err(|e| PmcpError::Transport(format!("{}: {e}", path.display())))?
+        .ok_or_else(|| PmcpError::Transport(format!("{}: no private key found", path.display())))
+}
+
+/// Builds a TLS 1.3 server configuration from PEM certificate and key files.
+///
+/// # Errors
+///
+/// Returns a transport error if either file is unusable or the key does not
+/// match the certificate.
+pub fn server_config(cert_path: &Path, key_path: &Path) -> Result<Arc<ServerConfig>> {
+    let config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_protocol_versions(&[&version::TLS13])
+        .map_err(|e| PmcpError::Transport(e.to_string()))?
+        .with_no_client_auth()
+        .with_single_cert(load_certs(cert_path)?, load_private_key(key_path)?)
+        .map_err(|e| PmcpError::Transport(e.to_string()))?;
+
+    Ok(Arc::new(config))
+}
+
+/// Builds a TLS 1.3 client configuration that trusts only the certificates
+/// in `ca_path`, e.g. a private CA or a self-signed server certificate.
+///
+/// # Errors
+///
+/// Returns a transport error if the file is unusable.
+pub fn client_config(ca_path: &Path) -> Result<Arc<ClientConfig>> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(ca_path)? {
+        roots
+            .add(cert)
+            .map_err(|e| PmcpError::Transport(format!("{}: {e}", ca_path.display())))?;
+    }
+
+    let config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_protocol_versions(&[&version::TLS13])
+        .map_err(|e| PmcpError::Transport(e.to_string()))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    Ok(Arc::new(config))
+}
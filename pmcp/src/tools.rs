@@ -1,6 +1,103 @@
-use crate::Tool;
+use crate::protocol::ERROR_INVALID_PARAMS;
+use crate::{PmcpError, Result, Tool};
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_json::json;
 
+/// Deserializes a handler's `params` into `T`, the counterpart to
+/// [`tool_from_params`] on the handler side.
+///
+/// # Errors
+///
+/// Returns an invalid-params JSON-RPC error when `params` is absent or does
+/// not match `T`, carrying serde's description of the mismatch.
+pub fn parse_params<T: DeserializeOwned>(params: Option<serde_json::Value>) -> Result<T> {
+    let invalid = |message: String| PmcpError::JsonRpc {
+        code: ERROR_INVALID_PARAMS,
+        message,
+    };
+    let params = params.ok_or_else(|| invalid("Missing params".to_string()))?;
+    serde_json::from_value(params).map_err(|e| invalid(format!("Invalid params: {e}")))
+}
+
+/// One block of a [`ToolResult`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Content {
+    Text { text: String },
+}
+
+/// The MCP tool result envelope,
+/// `{ "content": [{ "type": "text", "text": ... }], "isError": bool }`.
+/// Chain [`ToolResult::text`], [`ToolResult::json`] and
+/// [`ToolResult::error`] to add content blocks, then return
+/// [`ToolResult::into_value`] from the handler.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolResult {
+    pub content: Vec<Content>,
+    pub is_error: bool,
+}
+
+impl ToolResult {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.content.push(Content::Text { text: text.into() });
+        self
+    }
+
+    /// Adds `value` as a text block holding its JSON serialization.
+    #[must_use]
+    pub fn json(self, value: &serde_json::Value) -> Self {
+        self.text(value.to_string())
+    }
+
+    /// Adds `message` as a text block and marks the result as an error.
+    #[must_use]
+    pub fn error(mut self, message: impl Into<String>) -> Self {
+        self.is_error = true;
+        self.text(message)
+    }
+
+    #[must_use]
+    pub fn into_value(self) -> serde_json::Value {
+        json!(self)
+    }
+}
+
+impl From<ToolResult> for serde_json::Value {
+    fn from(result: ToolResult) -> Self {
+        result.into_value()
+    }
+}
+
+/// Builds a tool whose `input_schema` is generated from `T`, so the schema
+/// advertised to clients and the type the handler deserializes its
+/// arguments into cannot drift apart. Derive both `Deserialize` and
+/// `JsonSchema` on `T`; fields that are not `Option` become required.
+#[must_use]
+pub fn tool_from_params<T: JsonSchema>(name: &str, description: &str) -> Tool {
+    let mut input_schema = schemars::schema_for!(T).to_value();
+    if let Some(fields) = input_schema.as_object_mut() {
+        // Clients read the schema inline; the dialect and Rust type name
+        // only add noise to tools/list.
+        fields.remove("$schema");
+        fields.remove("title");
+    }
+
+    Tool {
+        name: name.to_string(),
+        description: description.to_string(),
+        input_schema,
+    }
+}
+
 #[must_use]
 pub fn calculator_tool() -> Tool {
     Tool {
@@ -14,10 +111,10 @@ pub fn calculator_tool() -> Tool {
                     "enum": ["add", "subtract", "multiply", "divide"]
                 },
                 "a": {
-                    "type": "number"
+                    "type": "integer"
                 },
                 "b": {
-                    "type": "number"
+                    "type": "integer"
                 }
             },
             "required": ["operation", "a", "b"]
@@ -25,6 +122,39 @@ pub fn calculator_tool() -> Tool {
     }
 }
 
+#[must_use]
+pub fn calculator_batch_tool() -> Tool {
+    Tool {
+        name: "calculator_batch".to_string(),
+        description: "Perform several arithmetic calculations in one call".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "operations": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "op": {
+                                "type": "string",
+                                "enum": ["add", "subtract", "multiply", "divide"]
+                            },
+                            "a": {
+                                "type": "integer"
+                            },
+                            "b": {
+                                "type": "integer"
+                            }
+                        },
+                        "required": ["op", "a", "b"]
+                    }
+                }
+            },
+            "required": ["operations"]
+        }),
+    }
+}
+
 #[must_use]
 pub fn analyze_complexity_tool() -> Tool {
     Tool {
@@ -69,6 +199,31 @@ pub fn extract_files_tool() -> Tool {
     }
 }
 
+#[must_use]
+pub fn read_file_chunked_tool() -> Tool {
+    Tool {
+        name: "read_file_chunked".to_string(),
+        description: "Read part of a file, paging through large files".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string"
+                },
+                "offset": {
+                    "type": "integer",
+                    "minimum": 0
+                },
+                "length": {
+                    "type": "integer",
+                    "minimum": 1
+                }
+            },
+            "required": ["path", "length"]
+        }),
+    }
+}
+
 #[must_use]
 pub fn deep_analysis_tool() -> Tool {
     Tool {
@@ -89,3 +244,86 @@ pub fn deep_analysis_tool() -> Tool {
         }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct ExtractParams {
+        path: String,
+        pattern: Option<String>,
+    }
+
+    #[test]
+    fn test_schema_generated_from_params_type() {
+        let tool = tool_from_params::<ExtractParams>("extract", "Extract files");
+
+        assert_eq!(tool.name, "extract");
+        assert_eq!(tool.input_schema["type"], "object");
+        assert_eq!(tool.input_schema["required"], json!(["path"]));
+        assert_eq!(tool.input_schema["properties"]["path"]["type"], "string");
+        assert!(tool.input_schema["properties"]["pattern"].is_object());
+        assert!(tool.input_schema.get("$schema").is_none());
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct AddParams {
+        a: i64,
+        b: i64,
+    }
+
+    #[test]
+    fn test_parse_params_missing_field_is_invalid_params() {
+        let result = parse_params::<AddParams>(Some(json!({ "a": 1 })));
+
+        match result {
+            Err(PmcpError::JsonRpc { code, message }) => {
+                assert_eq!(code, ERROR_INVALID_PARAMS);
+                assert!(message.contains("missing field `b`"), "{message}");
+            }
+            other => panic!("expected invalid params, got {other:?}"),
+        }
+        assert!(matches!(
+            parse_params::<AddParams>(None),
+            Err(PmcpError::JsonRpc {
+                code: ERROR_INVALID_PARAMS,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_params_valid_payload() {
+        let params: AddParams = parse_params(Some(json!({ "a": 1, "b": 2 }))).unwrap();
+
+        assert_eq!(params, AddParams { a: 1, b: 2 });
+    }
+
+    #[test]
+    fn test_tool_result_envelope() {
+        let value = ToolResult::new()
+            .text("done")
+            .json(&json!({ "result": 8 }))
+            .into_value();
+
+        assert_eq!(value["isError"], json!(false));
+        assert_eq!(
+            value["content"],
+            json!([
+                { "type": "text", "text": "done" },
+                { "type": "text", "text": "{\"result\":8}" },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_tool_result_error_sets_flag() {
+        let value = ToolResult::new().error("boom").into_value();
+
+        assert_eq!(value["isError"], json!(true));
+        assert_eq!(value["content"][0]["text"], "boom");
+    }
+}
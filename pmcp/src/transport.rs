@@ -1,27 +1,158 @@
-use crate::{Request, Response, Result};
+use crate::protocol::{check_json_limits, ERROR_INVALID_REQUEST};
+use crate::retry::RetryPolicy;
+use crate::{Notification, Request, Response, Result};
 use async_trait::async_trait;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
 use tokio::sync::mpsc;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::ClientConfig;
+use tokio_rustls::{TlsAcceptor, TlsConnector, TlsStream};
+use tracing::warn;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const CONTENT_LENGTH_HEADER: &str = "Content-Length:";
+
+/// The largest frame a transport reads, before and after decompression,
+/// unless set with `with_max_frame_size`. Matches the server's default
+/// `max_request_size`.
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 10_485_760;
+
+/// The deepest nesting and widest array or object a transport parses,
+/// unless set with `with_json_limits`. Match the server's defaults.
+pub const DEFAULT_MAX_JSON_DEPTH: usize = 64;
+pub const DEFAULT_MAX_JSON_ELEMENTS: usize = 10_000;
 
 #[async_trait]
 pub trait Transport: Send + Sync {
     async fn send(&mut self, response: Response) -> Result<()>;
     async fn receive(&mut self) -> Result<Request>;
+
+    async fn send_notification(&mut self, notification: Notification) -> Result<()> {
+        Err(crate::PmcpError::Transport(format!(
+            "Notifications not supported by this transport: {}",
+            notification.method
+        )))
+    }
+
+    /// Client side: writes a request to the server.
+    async fn send_request(&mut self, request: Request) -> Result<()> {
+        Err(crate::PmcpError::Transport(format!(
+            "Requests not supported by this transport: {}",
+            request.method
+        )))
+    }
+
+    /// Client side: reads the next response from the server.
+    async fn receive_response(&mut self) -> Result<Response> {
+        Err(crate::PmcpError::Transport(
+            "Responses not supported by this transport".to_string(),
+        ))
+    }
+
+    /// Sends several notifications at once. Transports that own a byte
+    /// stream write them as a single JSON-RPC array; the default sends them
+    /// one at a time.
+    async fn send_notification_batch(&mut self, notifications: Vec<Notification>) -> Result<()> {
+        for notification in notifications {
+            self.send_notification(notification).await?;
+        }
+        Ok(())
+    }
+
+    /// Releases the connection once nothing more will be sent. The default
+    /// does nothing; dropping the transport closes it as well.
+    async fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// When output held back by this transport must next be written with
+    /// [`Transport::flush`], or `None` if nothing is held back. The server
+    /// checks this between messages and flushes once it passes.
+    fn flush_deadline(&self) -> Option<tokio::time::Instant> {
+        None
+    }
+
+    /// Writes any output held back by this transport. The default holds
+    /// nothing back.
+    async fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Sends several responses at once, as with
+    /// [`Transport::send_notification_batch`]. The server only calls this
+    /// once the client has negotiated batching.
+    async fn send_batch(&mut self, responses: Vec<Response>) -> Result<()> {
+        for response in responses {
+            self.send(response).await?;
+        }
+        Ok(())
+    }
 }
 
-pub struct StdioTransport {
-    stdin: BufReader<tokio::io::Stdin>,
-    stdout: tokio::io::Stdout,
+/// Newline-delimited or `Content-Length` framed JSON over a reader/writer
+/// pair: the process's stdin and stdout by default, or any pipe handed to
+/// [`StdioTransport::from_pipes`].
+pub struct StdioTransport<R = tokio::io::Stdin, W = tokio::io::Stdout> {
+    stdin: FrameReader<R>,
+    stdout: W,
+    compression: bool,
 }
 
 impl StdioTransport {
     #[must_use]
     pub fn new() -> Self {
+        Self::from_pipes(tokio::io::stdin(), tokio::io::stdout())
+    }
+}
+
+impl<R, W> StdioTransport<R, W>
+where
+    R: AsyncRead + Unpin + Send,
+{
+    /// Speaks the stdio framing over `reader` and `writer`, e.g. a child
+    /// process's pipes or an in-memory duplex stream.
+    #[must_use]
+    pub fn from_pipes(reader: R, writer: W) -> Self {
         Self {
-            stdin: BufReader::new(tokio::io::stdin()),
-            stdout: tokio::io::stdout(),
+            stdin: FrameReader::new(reader),
+            stdout: writer,
+            compression: false,
         }
     }
+
+    /// Gzip outgoing payloads inside a `Content-Length` frame. Incoming
+    /// messages are accepted in either form regardless of this setting.
+    #[must_use]
+    pub fn with_compression(mut self, enabled: bool) -> Self {
+        self.compression = enabled;
+        self
+    }
+
+    /// Rejects incoming frames larger than `size` bytes, measured after
+    /// decompression.
+    #[must_use]
+    pub fn with_max_frame_size(mut self, size: usize) -> Self {
+        self.stdin.max_frame_size = size;
+        self
+    }
+
+    /// Rejects incoming messages nested deeper than `max_depth`, counting
+    /// the message itself as the first level, or with more than
+    /// `max_elements` entries in any array or object, before parsing them.
+    #[must_use]
+    pub fn with_json_limits(mut self, max_depth: usize, max_elements: usize) -> Self {
+        self.stdin.max_json_depth = max_depth;
+        self.stdin.max_json_elements = max_elements;
+        self
+    }
 }
 
 impl Default for StdioTransport {
@@ -31,65 +162,1272 @@ impl Default for StdioTransport {
 }
 
 #[async_trait]
-impl Transport for StdioTransport {
+impl<R, W> Transport for StdioTransport<R, W>
+where
+    R: AsyncRead + Unpin + Send + Sync,
+    W: AsyncWrite + Unpin + Send + Sync,
+{
     async fn send(&mut self, response: Response) -> Result<()> {
-        let json = serde_json::to_string(&response)
+        let json =
+            serde_json::to_vec(&response).map_err(|e| crate::PmcpError::Protocol(e.to_string()))?;
+
+        write_frame(&mut self.stdout, &json, self.compression).await
+    }
+
+    async fn receive(&mut self) -> Result<Request> {
+        self.stdin.next_message().await
+    }
+
+    async fn send_notification(&mut self, notification: Notification) -> Result<()> {
+        let json = serde_json::to_vec(&notification)
             .map_err(|e| crate::PmcpError::Protocol(e.to_string()))?;
 
-        self.stdout
-            .write_all(json.as_bytes())
+        write_frame(&mut self.stdout, &json, self.compression).await
+    }
+
+    async fn send_notification_batch(&mut self, notifications: Vec<Notification>) -> Result<()> {
+        let json = serde_json::to_vec(&notifications)
+            .map_err(|e| crate::PmcpError::Protocol(e.to_string()))?;
+
+        write_frame(&mut self.stdout, &json, self.compression).await
+    }
+
+    async fn send_batch(&mut self, responses: Vec<Response>) -> Result<()> {
+        let json = serde_json::to_vec(&responses)
+            .map_err(|e| crate::PmcpError::Protocol(e.to_string()))?;
+
+        write_frame(&mut self.stdout, &json, self.compression).await
+    }
+
+    async fn send_request(&mut self, request: Request) -> Result<()> {
+        let json =
+            serde_json::to_vec(&request).map_err(|e| crate::PmcpError::Protocol(e.to_string()))?;
+
+        write_frame(&mut self.stdout, &json, self.compression).await
+    }
+
+    async fn receive_response(&mut self) -> Result<Response> {
+        self.stdin.next_response().await
+    }
+}
+
+async fn write_frame<W>(writer: &mut W, payload: &[u8], compress: bool) -> Result<()>
+where
+    W: AsyncWrite + Unpin + Send,
+{
+    if compress {
+        let compressed = compress_payload(payload)?;
+        let header = format!("{CONTENT_LENGTH_HEADER} {}\r\n\r\n", compressed.len());
+
+        writer
+            .write_all(header.as_bytes())
+            .await
+            .map_err(|e| crate::PmcpError::Transport(e.to_string()))?;
+
+        writer
+            .write_all(&compressed)
+            .await
+            .map_err(|e| crate::PmcpError::Transport(e.to_string()))?;
+    } else {
+        writer
+            .write_all(payload)
             .await
             .map_err(|e| crate::PmcpError::Transport(e.to_string()))?;
 
-        self.stdout
+        writer
             .write_all(b"\n")
             .await
             .map_err(|e| crate::PmcpError::Transport(e.to_string()))?;
+    }
+
+    writer
+        .flush()
+        .await
+        .map_err(|e| crate::PmcpError::Transport(e.to_string()))
+}
+
+/// Accumulates bytes from `reader` and splits them into frames. Bytes read by
+/// a cancelled [`FrameReader::next_frame`] stay buffered, so the call is safe
+/// to use as a `select!` branch.
+struct FrameReader<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    max_frame_size: usize,
+    max_json_depth: usize,
+    max_json_elements: usize,
+    /// The rest of a batch whose first response has been returned.
+    batched: VecDeque<Response>,
+}
+
+impl<R> FrameReader<R>
+where
+    R: AsyncRead + Unpin + Send,
+{
+    fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            max_json_depth: DEFAULT_MAX_JSON_DEPTH,
+            max_json_elements: DEFAULT_MAX_JSON_ELEMENTS,
+            batched: VecDeque::new(),
+        }
+    }
+
+    /// The next response, whether it arrived alone or in a batch array as
+    /// written by [`Transport::send_batch`].
+    async fn next_response(&mut self) -> Result<Response> {
+        if let Some(response) = self.batched.pop_front() {
+            return Ok(response);
+        }
+        let protocol = |e: serde_json::Error| crate::PmcpError::Protocol(e.to_string());
+
+        match self.next_message().await? {
+            serde_json::Value::Array(items) => {
+                self.batched = items
+                    .into_iter()
+                    .map(serde_json::from_value)
+                    .collect::<std::result::Result<_, _>>()
+                    .map_err(protocol)?;
+                self.batched
+                    .pop_front()
+                    .ok_or_else(|| crate::PmcpError::Protocol("Empty response batch".to_string()))
+            }
+            single => serde_json::from_value(single).map_err(protocol),
+        }
+    }
+
+    /// Reads the next frame and parses it, once it is known to be within
+    /// the JSON limits. A frame over them is reported as an invalid-request
+    /// JSON-RPC error; the stream stays usable.
+    async fn next_message<T: serde::de::DeserializeOwned>(&mut self) -> Result<T> {
+        let payload = self.next_frame().await?;
+        check_json_limits(&payload, self.max_json_depth, self.max_json_elements).map_err(
+            |message| crate::PmcpError::JsonRpc {
+                code: ERROR_INVALID_REQUEST,
+                message,
+            },
+        )?;
+
+        serde_json::from_slice(&payload).map_err(|e| crate::PmcpError::Protocol(e.to_string()))
+    }
+
+    async fn next_frame(&mut self) -> Result<Vec<u8>> {
+        loop {
+            if let Some(frame) = decode_frame(&mut self.buffer, self.max_frame_size)? {
+                return Ok(frame);
+            }
+
+            let read = self
+                .reader
+                .read_buf(&mut self.buffer)
+                .await
+                .map_err(|e| crate::PmcpError::Transport(e.to_string()))?;
+
+            if read == 0 {
+                return Err(if self.buffer.is_empty() {
+                    crate::PmcpError::Closed
+                } else {
+                    crate::PmcpError::Transport("Connection closed mid-frame".to_string())
+                });
+            }
+        }
+    }
+}
+
+/// Splits the next frame off `buffer`, or returns `None` until it has fully
+/// arrived. A frame larger than `max_size`, declared or decompressed, is an
+/// error; the connection cannot be resynchronised after one, so the buffer
+/// is discarded.
+fn decode_frame(buffer: &mut Vec<u8>, max_size: usize) -> Result<Option<Vec<u8>>> {
+    let too_large = |buffer: &mut Vec<u8>| {
+        buffer.clear();
+        Err(crate::PmcpError::Protocol(format!(
+            "Frame exceeds {max_size} bytes"
+        )))
+    };
+    let Some(header_end) = buffer.iter().position(|b| *b == b'\n') else {
+        // A newline-delimited message still waiting for its newline.
+        return if buffer.len() > max_size {
+            too_large(buffer)
+        } else {
+            Ok(None)
+        };
+    };
+    if header_end > max_size {
+        return too_large(buffer);
+    }
+
+    let line = String::from_utf8_lossy(&buffer[..header_end]);
+    let Some(length) = line.trim().strip_prefix(CONTENT_LENGTH_HEADER) else {
+        return Ok(Some(buffer.drain(..=header_end).collect()));
+    };
 
-        self.stdout
-            .flush()
+    let length: usize = length
+        .trim()
+        .parse()
+        .map_err(|_| crate::PmcpError::Protocol(format!("Invalid frame header: {line}")))?;
+    if length > max_size {
+        return too_large(buffer);
+    }
+
+    let Some(separator) = buffer[header_end + 1..].iter().position(|b| *b == b'\n') else {
+        return Ok(None);
+    };
+    let body_start = header_end + separator + 2;
+
+    if buffer.len() < body_start + length {
+        return Ok(None);
+    }
+
+    let body: Vec<u8> = buffer
+        .drain(..body_start + length)
+        .skip(body_start)
+        .collect();
+
+    if body.starts_with(&GZIP_MAGIC) {
+        decompress_payload(&body, max_size).map(Some)
+    } else {
+        Ok(Some(body))
+    }
+}
+
+fn compress_payload(payload: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(payload)
+        .map_err(|e| crate::PmcpError::Transport(e.to_string()))?;
+    encoder
+        .finish()
+        .map_err(|e| crate::PmcpError::Transport(e.to_string()))
+}
+
+/// Inflates `payload`, stopping as soon as the output passes `max_size` so
+/// a small compressed frame cannot expand without bound.
+fn decompress_payload(payload: &[u8], max_size: usize) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(payload)
+        .take(
+            u64::try_from(max_size)
+                .unwrap_or(u64::MAX)
+                .saturating_add(1),
+        )
+        .read_to_end(&mut decoded)
+        .map_err(|e| crate::PmcpError::Protocol(e.to_string()))?;
+    if decoded.len() > max_size {
+        return Err(crate::PmcpError::Protocol(format!(
+            "Decompressed frame exceeds {max_size} bytes"
+        )));
+    }
+    Ok(decoded)
+}
+
+pub struct TcpTransport {
+    reader: FrameReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+}
+
+impl TcpTransport {
+    #[must_use]
+    pub fn new(stream: TcpStream) -> Self {
+        let (reader, writer) = stream.into_split();
+        Self {
+            reader: FrameReader::new(reader),
+            writer,
+        }
+    }
+
+    /// Rejects incoming frames larger than `size` bytes, measured after
+    /// decompression.
+    #[must_use]
+    pub fn with_max_frame_size(mut self, size: usize) -> Self {
+        self.reader.max_frame_size = size;
+        self
+    }
+
+    /// Rejects incoming messages nested deeper than `max_depth`, counting
+    /// the message itself as the first level, or with more than
+    /// `max_elements` entries in any array or object, before parsing them.
+    #[must_use]
+    pub fn with_json_limits(mut self, max_depth: usize, max_elements: usize) -> Self {
+        self.reader.max_json_depth = max_depth;
+        self.reader.max_json_elements = max_elements;
+        self
+    }
+
+    /// Opens a connection to `addr`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if the connection cannot be established.
+    pub async fn connect(addr: &str) -> Result<Self> {
+        let stream = TcpStream::connect(addr)
             .await
             .map_err(|e| crate::PmcpError::Transport(e.to_string()))?;
+        Ok(Self::new(stream))
+    }
+}
 
-        Ok(())
+#[async_trait]
+impl Transport for TcpTransport {
+    async fn send(&mut self, response: Response) -> Result<()> {
+        let json =
+            serde_json::to_vec(&response).map_err(|e| crate::PmcpError::Protocol(e.to_string()))?;
+
+        write_frame(&mut self.writer, &json, false).await
     }
 
     async fn receive(&mut self) -> Result<Request> {
-        let mut line = String::new();
-        self.stdin
-            .read_line(&mut line)
+        self.reader.next_message().await
+    }
+
+    async fn send_notification(&mut self, notification: Notification) -> Result<()> {
+        let json = serde_json::to_vec(&notification)
+            .map_err(|e| crate::PmcpError::Protocol(e.to_string()))?;
+
+        write_frame(&mut self.writer, &json, false).await
+    }
+
+    async fn send_notification_batch(&mut self, notifications: Vec<Notification>) -> Result<()> {
+        let json = serde_json::to_vec(&notifications)
+            .map_err(|e| crate::PmcpError::Protocol(e.to_string()))?;
+
+        write_frame(&mut self.writer, &json, false).await
+    }
+
+    async fn send_batch(&mut self, responses: Vec<Response>) -> Result<()> {
+        let json = serde_json::to_vec(&responses)
+            .map_err(|e| crate::PmcpError::Protocol(e.to_string()))?;
+
+        write_frame(&mut self.writer, &json, false).await
+    }
+
+    async fn send_request(&mut self, request: Request) -> Result<()> {
+        let json =
+            serde_json::to_vec(&request).map_err(|e| crate::PmcpError::Protocol(e.to_string()))?;
+
+        write_frame(&mut self.writer, &json, false).await
+    }
+
+    async fn receive_response(&mut self) -> Result<Response> {
+        self.reader.next_response().await
+    }
+}
+
+type TlsHalves = StdioTransport<
+    tokio::io::ReadHalf<TlsStream<TcpStream>>,
+    tokio::io::WriteHalf<TlsStream<TcpStream>>,
+>;
+
+/// The same newline JSON framing as [`TcpTransport`], inside a TLS session.
+/// Build configurations with the helpers in [`crate::tls`].
+pub struct TlsTcpTransport {
+    inner: TlsHalves,
+}
+
+impl TlsTcpTransport {
+    /// Completes the server side of the handshake on an accepted socket.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if the handshake fails.
+    pub async fn accept(acceptor: &TlsAcceptor, socket: TcpStream) -> Result<Self> {
+        let stream = acceptor
+            .accept(socket)
+            .await
+            .map_err(|e| crate::PmcpError::Transport(format!("TLS handshake failed: {e}")))?;
+
+        Ok(Self::from_stream(TlsStream::Server(stream)))
+    }
+
+    /// Dials `addr` and verifies the server's certificate against
+    /// `server_name`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if the connection or handshake fails, or if
+    /// `server_name` is not a valid DNS name or IP address.
+    pub async fn connect(
+        config: std::sync::Arc<ClientConfig>,
+        addr: &str,
+        server_name: &str,
+    ) -> Result<Self> {
+        let name = ServerName::try_from(server_name.to_string())
+            .map_err(|e| crate::PmcpError::Transport(format!("{server_name}: {e}")))?;
+        let socket = TcpStream::connect(addr)
             .await
             .map_err(|e| crate::PmcpError::Transport(e.to_string()))?;
+        let stream = TlsConnector::from(config)
+            .connect(name, socket)
+            .await
+            .map_err(|e| crate::PmcpError::Transport(format!("TLS handshake failed: {e}")))?;
+
+        Ok(Self::from_stream(TlsStream::Client(stream)))
+    }
+
+    fn from_stream(stream: TlsStream<TcpStream>) -> Self {
+        let (reader, writer) = tokio::io::split(stream);
+        Self {
+            inner: StdioTransport::from_pipes(reader, writer),
+        }
+    }
+
+    /// Rejects incoming frames larger than `size` bytes, measured after
+    /// decompression.
+    #[must_use]
+    pub fn with_max_frame_size(mut self, size: usize) -> Self {
+        self.inner = self.inner.with_max_frame_size(size);
+        self
+    }
+
+    /// As [`TcpTransport::with_json_limits`].
+    #[must_use]
+    pub fn with_json_limits(mut self, max_depth: usize, max_elements: usize) -> Self {
+        self.inner = self.inner.with_json_limits(max_depth, max_elements);
+        self
+    }
+}
+
+#[async_trait]
+impl Transport for TlsTcpTransport {
+    async fn send(&mut self, response: Response) -> Result<()> {
+        self.inner.send(response).await
+    }
+
+    async fn receive(&mut self) -> Result<Request> {
+        self.inner.receive().await
+    }
+
+    async fn send_notification(&mut self, notification: Notification) -> Result<()> {
+        self.inner.send_notification(notification).await
+    }
+
+    async fn send_notification_batch(&mut self, notifications: Vec<Notification>) -> Result<()> {
+        self.inner.send_notification_batch(notifications).await
+    }
+
+    async fn send_batch(&mut self, responses: Vec<Response>) -> Result<()> {
+        self.inner.send_batch(responses).await
+    }
+
+    async fn send_request(&mut self, request: Request) -> Result<()> {
+        self.inner.send_request(request).await
+    }
+
+    async fn receive_response(&mut self) -> Result<Response> {
+        self.inner.receive_response().await
+    }
+}
+
+/// Wraps a [`TcpTransport`] and re-dials the stored address with the
+/// capped backoff of a [`RetryPolicy`] whenever the connection fails. The call that observed the failure
+/// returns [`crate::PmcpError::Reconnecting`] once the new connection is up, so
+/// callers know the in-flight message was lost and can retry it.
+pub struct ReconnectingTransport {
+    addr: String,
+    inner: TcpTransport,
+    policy: RetryPolicy,
+}
+
+impl ReconnectingTransport {
+    /// Connects to `addr` with the default policy of 5 attempts starting at 100ms.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if every connection attempt fails.
+    pub async fn connect(addr: &str) -> Result<Self> {
+        Self::connect_with_retry(addr, 5, Duration::from_millis(100)).await
+    }
+
+    /// Connects to `addr`, retrying up to `max_attempts` times with a delay
+    /// that starts at `base_delay` and doubles after each failure, up to
+    /// [`RetryPolicy::new`]'s cap.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if every connection attempt fails.
+    pub async fn connect_with_retry(
+        addr: &str,
+        max_attempts: u32,
+        base_delay: Duration,
+    ) -> Result<Self> {
+        Self::connect_with_policy(addr, RetryPolicy::new(max_attempts, base_delay)).await
+    }
+
+    /// Connects to `addr`, and later reconnects, following `policy`'s
+    /// attempts and delays.
+    ///
+    /// # Errors
+    ///
+    /// Returns a transport error if every connection attempt fails.
+    pub async fn connect_with_policy(addr: &str, policy: RetryPolicy) -> Result<Self> {
+        let inner = dial_with_backoff(addr, &policy).await?;
+        Ok(Self {
+            addr: addr.to_string(),
+            inner,
+            policy,
+        })
+    }
+
+    async fn reconnect(&mut self, cause: String) -> crate::PmcpError {
+        warn!("Connection to {} lost: {}. Reconnecting", self.addr, cause);
+
+        match dial_with_backoff(&self.addr, &self.policy).await {
+            Ok(inner) => {
+                self.inner = inner;
+                crate::PmcpError::Reconnecting(cause)
+            }
+            Err(e) => e,
+        }
+    }
+
+    /// Reconnects if `result` shows the connection failed or was hung up on.
+    async fn recover<T>(&mut self, result: Result<T>) -> Result<T> {
+        match result {
+            Err(crate::PmcpError::Transport(cause)) => Err(self.reconnect(cause).await),
+            Err(crate::PmcpError::Closed) => {
+                Err(self.reconnect("Connection closed".to_string()).await)
+            }
+            other => other,
+        }
+    }
+}
+
+async fn dial_with_backoff(addr: &str, policy: &RetryPolicy) -> Result<TcpTransport> {
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+
+        match TcpTransport::connect(addr).await {
+            Ok(transport) => return Ok(transport),
+            Err(e) if attempt >= policy.max_attempts() => {
+                return Err(crate::PmcpError::Transport(format!(
+                    "Giving up on {addr} after {attempt} attempts: {e}"
+                )))
+            }
+            Err(e) => {
+                let delay = policy.delay(attempt);
+                warn!("Attempt {} failed: {}. Retrying in {:?}", attempt, e, delay);
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for ReconnectingTransport {
+    async fn send(&mut self, response: Response) -> Result<()> {
+        let result = self.inner.send(response).await;
+        self.recover(result).await
+    }
+
+    async fn receive(&mut self) -> Result<Request> {
+        let result = self.inner.receive().await;
+        self.recover(result).await
+    }
+
+    async fn send_notification(&mut self, notification: Notification) -> Result<()> {
+        let result = self.inner.send_notification(notification).await;
+        self.recover(result).await
+    }
+
+    async fn send_notification_batch(&mut self, notifications: Vec<Notification>) -> Result<()> {
+        let result = self.inner.send_notification_batch(notifications).await;
+        self.recover(result).await
+    }
+
+    async fn send_batch(&mut self, responses: Vec<Response>) -> Result<()> {
+        let result = self.inner.send_batch(responses).await;
+        self.recover(result).await
+    }
 
-        serde_json::from_str(&line).map_err(|e| crate::PmcpError::Protocol(e.to_string()))
+    async fn send_request(&mut self, request: Request) -> Result<()> {
+        let result = self.inner.send_request(request).await;
+        self.recover(result).await
     }
+
+    async fn receive_response(&mut self) -> Result<Response> {
+        let result = self.inner.receive_response().await;
+        self.recover(result).await
+    }
+}
+
+/// Buffers outgoing notifications and hands them to the wrapped transport as
+/// one batch once `max_batch` are pending or `interval` has passed since the
+/// oldest was queued. Pending notifications are flushed ahead of any
+/// response so progress never arrives after the result it describes.
+///
+/// The timed flush is left to the caller: the server watches
+/// [`Transport::flush_deadline`] and calls [`Transport::flush`] from its own
+/// loop, so a flush is never abandoned half-written. [`Transport::close`]
+/// flushes whatever is still pending.
+pub struct NotificationBatcher<T> {
+    inner: T,
+    pending: Vec<Notification>,
+    max_batch: usize,
+    interval: Duration,
+    deadline: Option<tokio::time::Instant>,
 }
 
+impl<T: Transport> NotificationBatcher<T> {
+    /// Wraps `inner`, flushing every 32 notifications or 50ms.
+    #[must_use]
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            pending: Vec::new(),
+            max_batch: 32,
+            interval: Duration::from_millis(50),
+            deadline: None,
+        }
+    }
+
+    /// Flush as soon as `max_batch` notifications are pending. Values below
+    /// one are treated as one.
+    #[must_use]
+    pub fn with_max_batch(mut self, max_batch: usize) -> Self {
+        self.max_batch = max_batch.max(1);
+        self
+    }
+
+    /// Flush a partial batch once its oldest notification has waited this long.
+    #[must_use]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for NotificationBatcher<T> {
+    async fn send(&mut self, response: Response) -> Result<()> {
+        self.flush().await?;
+        self.inner.send(response).await
+    }
+
+    async fn receive(&mut self) -> Result<Request> {
+        self.inner.receive().await
+    }
+
+    fn flush_deadline(&self) -> Option<tokio::time::Instant> {
+        self.deadline
+    }
+
+    /// Writes any pending notifications through the wrapped transport. They
+    /// stay pending until the write completes, so a failed or abandoned
+    /// flush loses nothing.
+    async fn flush(&mut self) -> Result<()> {
+        if !self.pending.is_empty() {
+            self.inner
+                .send_notification_batch(self.pending.clone())
+                .await?;
+            self.pending.clear();
+        }
+        self.deadline = None;
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<()> {
+        self.flush().await?;
+        self.inner.close().await
+    }
+
+    async fn send_notification(&mut self, notification: Notification) -> Result<()> {
+        self.pending.push(notification);
+        if self.pending.len() >= self.max_batch {
+            return self.flush().await;
+        }
+
+        if self.deadline.is_none() {
+            self.deadline = Some(tokio::time::Instant::now() + self.interval);
+        }
+        Ok(())
+    }
+
+    async fn send_notification_batch(&mut self, notifications: Vec<Notification>) -> Result<()> {
+        for notification in notifications {
+            self.send_notification(notification).await?;
+        }
+        Ok(())
+    }
+
+    async fn send_batch(&mut self, responses: Vec<Response>) -> Result<()> {
+        self.flush().await?;
+        self.inner.send_batch(responses).await
+    }
+
+    async fn send_request(&mut self, request: Request) -> Result<()> {
+        self.inner.send_request(request).await
+    }
+
+    async fn receive_response(&mut self) -> Result<Response> {
+        self.inner.receive_response().await
+    }
+}
+
+/// Carries messages over a pair of channels fed by a WebSocket connection.
+/// Once either side closes, sends and receives fail with
+/// [`crate::PmcpError::Closed`].
 pub struct WebSocketTransport {
-    tx: mpsc::Sender<Response>,
+    tx: Option<mpsc::Sender<Response>>,
     rx: mpsc::Receiver<Request>,
 }
 
 impl WebSocketTransport {
     #[must_use]
     pub fn new(tx: mpsc::Sender<Response>, rx: mpsc::Receiver<Request>) -> Self {
-        Self { tx, rx }
+        Self { tx: Some(tx), rx }
     }
 }
 
 #[async_trait]
 impl Transport for WebSocketTransport {
     async fn send(&mut self, response: Response) -> Result<()> {
-        self.tx
-            .send(response)
+        let tx = self.tx.as_ref().ok_or(crate::PmcpError::Closed)?;
+
+        tx.send(response)
             .await
-            .map_err(|e| crate::PmcpError::Transport(e.to_string()))
+            .map_err(|_| crate::PmcpError::Closed)
+    }
+
+    async fn receive(&mut self) -> Result<Request> {
+        self.rx.recv().await.ok_or(crate::PmcpError::Closed)
+    }
+
+    /// Stops sending and receiving without waiting on the peer. Requests
+    /// already queued are still returned by `receive` before it reports the
+    /// close.
+    async fn close(&mut self) -> Result<()> {
+        self.tx = None;
+        self.rx.close();
+        Ok(())
+    }
+}
+
+#[cfg(any(test, feature = "in-memory"))]
+const IN_MEMORY_CAPACITY: usize = 64;
+
+/// An in-process connection: each end of an [`InMemoryTransport::pair`]
+/// can act as either client or server, so a [`crate::client::Client`] and
+/// a [`crate::server::Server`] can talk with no IO. Built for tests and
+/// benchmarks; other crates enable it with the `in-memory` feature.
+#[cfg(any(test, feature = "in-memory"))]
+pub struct InMemoryTransport {
+    tx: Option<mpsc::Sender<serde_json::Value>>,
+    rx: mpsc::Receiver<serde_json::Value>,
+}
+
+#[cfg(any(test, feature = "in-memory"))]
+impl InMemoryTransport {
+    /// Creates the client and server ends of one connection.
+    #[must_use]
+    pub fn pair() -> (Self, Self) {
+        Self::pair_with_capacity(IN_MEMORY_CAPACITY)
+    }
+
+    /// As [`InMemoryTransport::pair`], each end buffering up to `capacity`
+    /// messages before sends wait.
+    #[must_use]
+    pub fn pair_with_capacity(capacity: usize) -> (Self, Self) {
+        let (client_tx, server_rx) = mpsc::channel(capacity);
+        let (server_tx, client_rx) = mpsc::channel(capacity);
+
+        (
+            Self {
+                tx: Some(client_tx),
+                rx: client_rx,
+            },
+            Self {
+                tx: Some(server_tx),
+                rx: server_rx,
+            },
+        )
+    }
+
+    async fn send_message<M: serde::Serialize + Sync>(&mut self, message: &M) -> Result<()> {
+        let value =
+            serde_json::to_value(message).map_err(|e| crate::PmcpError::Protocol(e.to_string()))?;
+        let tx = self.tx.as_ref().ok_or(crate::PmcpError::Closed)?;
+
+        tx.send(value).await.map_err(|_| crate::PmcpError::Closed)
+    }
+
+    async fn receive_message<M: serde::de::DeserializeOwned>(&mut self) -> Result<M> {
+        let value = self.rx.recv().await.ok_or(crate::PmcpError::Closed)?;
+
+        serde_json::from_value(value).map_err(|e| crate::PmcpError::Protocol(e.to_string()))
+    }
+}
+
+#[cfg(any(test, feature = "in-memory"))]
+#[async_trait]
+impl Transport for InMemoryTransport {
+    async fn send(&mut self, response: Response) -> Result<()> {
+        self.send_message(&response).await
     }
 
     async fn receive(&mut self) -> Result<Request> {
-        self.rx
-            .recv()
+        self.receive_message().await
+    }
+
+    async fn send_notification(&mut self, notification: Notification) -> Result<()> {
+        self.send_message(&notification).await
+    }
+
+    async fn send_request(&mut self, request: Request) -> Result<()> {
+        self.send_message(&request).await
+    }
+
+    async fn receive_response(&mut self) -> Result<Response> {
+        self.receive_message().await
+    }
+
+    /// Stops sending and receiving. Messages already sent are still
+    /// delivered; once the peer has read them its next receive fails.
+    async fn close(&mut self) -> Result<()> {
+        self.tx = None;
+        self.rx.close();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct RecordingTransport {
+        batches: Arc<Mutex<Vec<Vec<Notification>>>>,
+        write_delay: Duration,
+    }
+
+    #[async_trait]
+    impl Transport for RecordingTransport {
+        async fn send(&mut self, _response: Response) -> Result<()> {
+            Ok(())
+        }
+
+        async fn receive(&mut self) -> Result<Request> {
+            std::future::pending().await
+        }
+
+        async fn send_notification_batch(
+            &mut self,
+            notifications: Vec<Notification>,
+        ) -> Result<()> {
+            tokio::time::sleep(self.write_delay).await;
+            self.batches.lock().unwrap().push(notifications);
+            Ok(())
+        }
+    }
+
+    fn progress(step: u64) -> Notification {
+        Notification {
+            jsonrpc: "2.0".to_string(),
+            method: "notifications/progress".to_string(),
+            params: Some(json!({ "progress": step })),
+        }
+    }
+
+    fn large_response() -> Response {
+        let findings: Vec<_> = (0..2_000)
+            .map(|i| json!({"file": format!("src/module_{}.rs", i % 50), "severity": "warning"}))
+            .collect();
+
+        Response {
+            jsonrpc: "2.0".to_string(),
+            result: Some(json!({ "findings": findings })),
+            error: None,
+            id: Some(json!(1)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compressed_frame_round_trip() {
+        let json = serde_json::to_vec(&large_response()).unwrap();
+
+        let mut wire = Vec::new();
+        write_frame(&mut wire, &json, true).await.unwrap();
+        assert!(wire.len() < json.len());
+
+        let mut reader = FrameReader::new(&wire[..]);
+        let decoded = reader.next_frame().await.unwrap();
+        assert_eq!(decoded, json);
+    }
+
+    #[tokio::test]
+    async fn test_reads_uncompressed_frames() {
+        let json = serde_json::to_vec(&large_response()).unwrap();
+
+        let mut wire = Vec::new();
+        write_frame(&mut wire, &json, false).await.unwrap();
+        write_frame(&mut wire, &json, true).await.unwrap();
+
+        let mut reader = FrameReader::new(&wire[..]);
+        let plain: Response = serde_json::from_slice(&reader.next_frame().await.unwrap()).unwrap();
+        let packed: Response = serde_json::from_slice(&reader.next_frame().await.unwrap()).unwrap();
+        assert_eq!(plain.result, packed.result);
+    }
+
+    #[tokio::test]
+    async fn test_decompression_bomb_is_rejected() {
+        // 16 MB of zeros compresses to a few KB.
+        let bomb = vec![0u8; 16 << 20];
+        let mut wire = Vec::new();
+        write_frame(&mut wire, &bomb, true).await.unwrap();
+        assert!(wire.len() < DEFAULT_MAX_FRAME_SIZE);
+
+        let mut reader = FrameReader::new(&wire[..]);
+        assert!(matches!(
+            reader.next_frame().await,
+            Err(crate::PmcpError::Protocol(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_frames_are_rejected_before_buffering() {
+        let declared = format!("{CONTENT_LENGTH_HEADER} 1048576\r\n\r\n");
+        let mut reader = FrameReader::new(declared.as_bytes());
+        reader.max_frame_size = 1_024;
+        assert!(reader.next_frame().await.is_err());
+
+        let unterminated = vec![b'x'; 4_096];
+        let mut reader = FrameReader::new(&unterminated[..]);
+        reader.max_frame_size = 1_024;
+        assert!(matches!(
+            reader.next_frame().await,
+            Err(crate::PmcpError::Protocol(_))
+        ));
+    }
+
+    /// A server-side [`TcpTransport`] and the raw client socket feeding it.
+    async fn tcp_pair() -> (TcpTransport, TcpStream) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (server, _) = listener.accept().await.unwrap();
+        (TcpTransport::new(server), client)
+    }
+
+    fn ping_request(id: i64) -> Request {
+        Request {
+            jsonrpc: "2.0".to_string(),
+            method: "ping".to_string(),
+            params: None,
+            id: Some(json!(id)),
+        }
+    }
+
+    fn ping(id: i64) -> Vec<u8> {
+        let mut line = serde_json::to_vec(&ping_request(id)).unwrap();
+        line.push(b'\n');
+        line
+    }
+
+    #[tokio::test]
+    async fn test_tcp_message_split_across_reads_is_reassembled() {
+        let (mut transport, mut client) = tcp_pair().await;
+        let line = ping(1);
+        let (head, tail) = line.split_at(line.len() / 2);
+
+        client.write_all(head).await.unwrap();
+        let partial = tokio::time::timeout(Duration::from_millis(50), transport.receive()).await;
+        assert!(partial.is_err(), "decoded an incomplete message");
+
+        client.write_all(tail).await.unwrap();
+        let request = transport.receive().await.unwrap();
+        assert_eq!(request.id, Some(json!(1)));
+    }
+
+    #[tokio::test]
+    async fn test_tcp_messages_in_one_read_are_both_decoded() {
+        let (mut transport, mut client) = tcp_pair().await;
+
+        client
+            .write_all(&[ping(1), ping(2)].concat())
             .await
-            .ok_or_else(|| crate::PmcpError::Transport("Channel closed".to_string()))
+            .unwrap();
+
+        assert_eq!(transport.receive().await.unwrap().id, Some(json!(1)));
+        assert_eq!(transport.receive().await.unwrap().id, Some(json!(2)));
+    }
+
+    #[tokio::test]
+    async fn test_tcp_peer_hangup_is_reported_as_closed() {
+        let (mut transport, client) = tcp_pair().await;
+
+        drop(client);
+
+        assert!(matches!(
+            transport.receive().await,
+            Err(crate::PmcpError::Closed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_tcp_hangup_mid_frame_is_a_transport_error() {
+        let (mut transport, mut client) = tcp_pair().await;
+        let line = ping(1);
+
+        client.write_all(&line[..line.len() / 2]).await.unwrap();
+        drop(client);
+
+        assert!(matches!(
+            transport.receive().await,
+            Err(crate::PmcpError::Transport(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reconnecting_transport_recovers() {
+        let line = b"{\"jsonrpc\":\"2.0\",\"method\":\"ping\",\"params\":null,\"id\":1}\n";
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        let mut transport =
+            ReconnectingTransport::connect_with_retry(&addr, 8, Duration::from_millis(10))
+                .await
+                .unwrap();
+
+        let (mut socket, _) = listener.accept().await.unwrap();
+        socket.write_all(line).await.unwrap();
+        assert_eq!(transport.receive().await.unwrap().method, "ping");
+
+        drop(socket);
+        drop(listener);
+
+        let restart_addr = addr.clone();
+        let server = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let listener = tokio::net::TcpListener::bind(&restart_addr).await.unwrap();
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(line).await.unwrap();
+            socket
+        });
+
+        assert!(matches!(
+            transport.receive().await,
+            Err(crate::PmcpError::Reconnecting(_))
+        ));
+        assert_eq!(transport.receive().await.unwrap().method, "ping");
+
+        drop(server.await.unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_many_reconnect_attempts_back_off_without_overflow() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        drop(listener);
+
+        let started = tokio::time::Instant::now();
+        let Err(error) =
+            ReconnectingTransport::connect_with_retry(&addr, 40, Duration::from_secs(1)).await
+        else {
+            panic!("connected to a closed port");
+        };
+
+        assert!(error.to_string().contains("after 40 attempts"));
+        // Capped at 30s per wait, not 2^38 seconds.
+        assert!(started.elapsed() <= Duration::from_secs(30 * 39));
+    }
+
+    #[tokio::test]
+    async fn test_batched_responses_are_received_one_at_a_time() {
+        let (server_end, client_end) = tokio::io::duplex(1024);
+        let (server_read, server_write) = tokio::io::split(server_end);
+        let (client_read, client_write) = tokio::io::split(client_end);
+        let mut server = StdioTransport::from_pipes(server_read, server_write);
+        let mut client = StdioTransport::from_pipes(client_read, client_write);
+        let pong = |id: i64| Response {
+            jsonrpc: "2.0".to_string(),
+            result: Some(json!({})),
+            error: None,
+            id: Some(json!(id)),
+        };
+
+        server.send_batch(vec![pong(1), pong(2)]).await.unwrap();
+        server.send(pong(3)).await.unwrap();
+
+        for id in 1..=3 {
+            assert_eq!(client.receive_response().await.unwrap().id, Some(json!(id)));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_batcher_flushes_partial_batch_after_interval() {
+        let recorder = RecordingTransport::default();
+        let mut batcher = NotificationBatcher::new(recorder.clone())
+            .with_max_batch(10)
+            .with_interval(Duration::from_millis(100));
+        let started = tokio::time::Instant::now();
+
+        for step in 0..3 {
+            batcher.send_notification(progress(step)).await.unwrap();
+        }
+        assert!(recorder.batches.lock().unwrap().is_empty());
+        assert_eq!(
+            batcher.flush_deadline(),
+            Some(started + Duration::from_millis(100))
+        );
+
+        batcher.flush().await.unwrap();
+
+        let flushed = recorder.batches.lock().unwrap().clone();
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].len(), 3);
+        assert_eq!(batcher.pending(), 0);
+        assert_eq!(batcher.flush_deadline(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_batcher_keeps_abandoned_flush_and_writes_it_on_close() {
+        let recorder = RecordingTransport {
+            write_delay: Duration::from_millis(50),
+            ..RecordingTransport::default()
+        };
+        let mut batcher = NotificationBatcher::new(recorder.clone()).with_max_batch(10);
+
+        for step in 0..3 {
+            batcher.send_notification(progress(step)).await.unwrap();
+        }
+        let abandoned = tokio::time::timeout(Duration::from_millis(10), batcher.flush()).await;
+        assert!(abandoned.is_err());
+        assert_eq!(batcher.pending(), 3);
+
+        batcher.close().await.unwrap();
+
+        let flushed = recorder.batches.lock().unwrap().clone();
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].len(), 3);
+        assert_eq!(batcher.pending(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_batcher_writes_full_batch_as_json_array() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        let transport = TcpTransport::connect(&addr).await.unwrap();
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut batcher = NotificationBatcher::new(transport).with_max_batch(2);
+
+        batcher.send_notification(progress(1)).await.unwrap();
+        batcher.send_notification(progress(2)).await.unwrap();
+
+        let mut reader = FrameReader::new(socket);
+        let frame: serde_json::Value =
+            serde_json::from_slice(&reader.next_frame().await.unwrap()).unwrap();
+        assert_eq!(frame.as_array().map(Vec::len), Some(2));
+        assert_eq!(frame[1]["params"]["progress"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_pair_correlates_and_closes_cleanly() {
+        let (mut client, mut server) = InMemoryTransport::pair();
+        for id in 1..=3 {
+            client
+                .send_request(Request {
+                    jsonrpc: "2.0".to_string(),
+                    method: format!("method_{id}"),
+                    params: None,
+                    id: Some(json!(id)),
+                })
+                .await
+                .unwrap();
+        }
+
+        let mut received = Vec::new();
+        for _ in 0..3 {
+            received.push(server.receive().await.unwrap());
+        }
+        for request in received.iter().rev() {
+            server
+                .send(Response {
+                    jsonrpc: "2.0".to_string(),
+                    result: Some(json!(request.method)),
+                    error: None,
+                    id: request.id.clone(),
+                })
+                .await
+                .unwrap();
+        }
+        server.close().await.unwrap();
+
+        for id in (1..=3).rev() {
+            let response = client.receive_response().await.unwrap();
+            assert_eq!(response.id, Some(json!(id)));
+            assert_eq!(response.result, Some(json!(format!("method_{id}"))));
+        }
+        assert!(matches!(
+            client.receive_response().await,
+            Err(crate::PmcpError::Closed)
+        ));
+        assert!(matches!(
+            server
+                .send_notification(Notification {
+                    jsonrpc: "2.0".to_string(),
+                    method: "late".to_string(),
+                    params: None,
+                })
+                .await,
+            Err(crate::PmcpError::Closed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_websocket_close_is_reported_as_closed_not_failure() {
+        let (request_tx, request_rx) = mpsc::channel(8);
+        let (response_tx, mut response_rx) = mpsc::channel(8);
+        let mut transport = WebSocketTransport::new(response_tx, request_rx);
+        request_tx.send(ping_request(1)).await.unwrap();
+
+        transport.close().await.unwrap();
+
+        // The peer sees the close, and what it queued beforehand still drains.
+        assert!(response_rx.recv().await.is_none());
+        assert!(request_tx.send(ping_request(2)).await.is_err());
+        assert_eq!(transport.receive().await.unwrap().id, Some(json!(1)));
+        assert!(matches!(
+            transport.receive().await,
+            Err(crate::PmcpError::Closed)
+        ));
+        assert!(matches!(
+            transport.send(large_response()).await,
+            Err(crate::PmcpError::Closed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_websocket_peer_hangup_is_reported_as_closed() {
+        let (request_tx, request_rx) = mpsc::channel::<Request>(8);
+        let (response_tx, response_rx) = mpsc::channel(8);
+        let mut transport = WebSocketTransport::new(response_tx, request_rx);
+
+        drop((request_tx, response_rx));
+
+        assert!(matches!(
+            transport.receive().await,
+            Err(crate::PmcpError::Closed)
+        ));
+        assert!(matches!(
+            transport.send(large_response()).await,
+            Err(crate::PmcpError::Closed)
+        ));
     }
 }
@@ -0,0 +1,234 @@
+//! Canonical messages from the MCP 2024-11-05 specification, decoded into
+//! this crate's types and encoded again. Each must come back unchanged:
+//! same field names, same structure, nothing added.
+
+use pmcp::protocol::{Message, MCP_PROTOCOL_VERSION};
+use pmcp::server::ServerBuilder;
+use pmcp::tools::ToolResult;
+use pmcp::{Notification, Request, Response};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+fn assert_round_trips<T: Serialize + DeserializeOwned>(canonical: &Value) {
+    let decoded: T = serde_json::from_value(canonical.clone()).unwrap();
+    assert_eq!(&serde_json::to_value(decoded).unwrap(), canonical);
+
+    let message: Message = serde_json::from_value(canonical.clone()).unwrap();
+    assert_eq!(&serde_json::to_value(message).unwrap(), canonical);
+}
+
+fn keys(value: &Value) -> Vec<&str> {
+    let mut keys: Vec<&str> = value
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(String::as_str)
+        .collect();
+    keys.sort_unstable();
+    keys
+}
+
+#[test]
+fn test_initialize_request() {
+    let canonical = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2024-11-05",
+            "capabilities": { "roots": { "listChanged": true }, "sampling": {} },
+            "clientInfo": { "name": "ExampleClient", "version": "1.0.0" }
+        }
+    });
+
+    assert_round_trips::<Request>(&canonical);
+    assert!(matches!(
+        serde_json::from_value(canonical).unwrap(),
+        Message::Request(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_initialize_response() {
+    let canonical = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": {
+            "protocolVersion": "2024-11-05",
+            "capabilities": { "logging": {}, "tools": { "listChanged": true } },
+            "serverInfo": { "name": "ExampleServer", "version": "1.0.0" }
+        }
+    });
+    assert_round_trips::<Response>(&canonical);
+
+    let server = ServerBuilder::new().build().unwrap();
+    let response = server
+        .handle_request(
+            serde_json::from_value(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": { "protocolVersion": MCP_PROTOCOL_VERSION, "capabilities": {} }
+            }))
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+    let ours = serde_json::to_value(response).unwrap();
+    assert_eq!(keys(&ours), ["id", "jsonrpc", "result"]);
+    assert_eq!(
+        keys(&ours["result"]),
+        ["capabilities", "protocolVersion", "serverInfo"]
+    );
+    assert_eq!(keys(&ours["result"]["serverInfo"]), ["name", "version"]);
+}
+
+#[test]
+fn test_initialized_notification() {
+    let canonical = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+
+    assert_round_trips::<Notification>(&canonical);
+    assert!(matches!(
+        serde_json::from_value(canonical).unwrap(),
+        Message::Notification(_)
+    ));
+}
+
+#[test]
+fn test_progress_notification() {
+    assert_round_trips::<Notification>(&json!({
+        "jsonrpc": "2.0",
+        "method": "notifications/progress",
+        "params": { "progressToken": "abc123", "progress": 50, "total": 100 }
+    }));
+}
+
+#[test]
+fn test_tools_list_request() {
+    assert_round_trips::<Request>(&json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "tools/list",
+        "params": { "cursor": "optional-cursor-value" }
+    }));
+}
+
+#[tokio::test]
+async fn test_tools_list_response() {
+    let canonical = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": {
+            "tools": [{
+                "name": "get_weather",
+                "description": "Get current weather information for a location",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "location": { "type": "string", "description": "City name or zip code" }
+                    },
+                    "required": ["location"]
+                }
+            }],
+            "nextCursor": "next-page-cursor"
+        }
+    });
+    assert_round_trips::<Response>(&canonical);
+    assert!(matches!(
+        serde_json::from_value(canonical).unwrap(),
+        Message::Response(_)
+    ));
+
+    let server = ServerBuilder::new()
+        .with_handler(pmcp::tools::calculator_tool(), NoopHandler)
+        .with_handler(pmcp::tools::extract_files_tool(), NoopHandler)
+        .with_tools_page_size(1)
+        .build()
+        .unwrap();
+    let response = server
+        .handle_request(
+            serde_json::from_value(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/list"
+            }))
+            .unwrap(),
+        )
+        .await
+        .unwrap();
+    let ours = serde_json::to_value(response).unwrap();
+    assert_eq!(keys(&ours["result"]), ["nextCursor", "tools"]);
+    assert_eq!(
+        keys(&ours["result"]["tools"][0]),
+        ["description", "inputSchema", "name"]
+    );
+}
+
+#[test]
+fn test_tools_call_request() {
+    assert_round_trips::<Request>(&json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "tools/call",
+        "params": { "name": "get_weather", "arguments": { "location": "New York" } }
+    }));
+}
+
+#[test]
+fn test_tools_call_response() {
+    let canonical = json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "result": {
+            "content": [{
+                "type": "text",
+                "text": "Current weather in New York:\nTemperature: 72°F\nConditions: Partly cloudy"
+            }],
+            "isError": false
+        }
+    });
+    assert_round_trips::<Response>(&canonical);
+
+    let ours = ToolResult::new()
+        .text("Current weather in New York:\nTemperature: 72°F\nConditions: Partly cloudy")
+        .into_value();
+    assert_eq!(ours, canonical["result"]);
+}
+
+#[test]
+fn test_error_response() {
+    assert_round_trips::<Response>(&json!({
+        "jsonrpc": "2.0",
+        "id": 3,
+        "error": { "code": -32602, "message": "Unknown tool: invalid_tool_name" }
+    }));
+}
+
+#[test]
+fn test_error_response_with_data_and_null_id() {
+    assert_round_trips::<Response>(&json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": {
+            "code": -32700,
+            "message": "Parse error",
+            "data": { "reason": "unexpected end of input" }
+        }
+    }));
+}
+
+#[test]
+fn test_ping() {
+    assert_round_trips::<Request>(&json!({ "jsonrpc": "2.0", "id": "123", "method": "ping" }));
+    assert_round_trips::<Response>(&json!({ "jsonrpc": "2.0", "id": "123", "result": {} }));
+}
+
+struct NoopHandler;
+
+#[async_trait::async_trait]
+impl pmcp::server::ToolHandler for NoopHandler {
+    async fn handle(&self, _params: Option<Value>) -> pmcp::Result<Value> {
+        Ok(Value::Null)
+    }
+}
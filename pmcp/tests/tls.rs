@@ -0,0 +1,59 @@
+use pmcp::client::Client;
+use pmcp::server::ServerBuilder;
+use pmcp::tls;
+use pmcp::transport::TlsTcpTransport;
+use std::path::{Path, PathBuf};
+use tokio::net::TcpListener;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+#[tokio::test]
+async fn test_tls_handshake_and_request() {
+    let config = tls::server_config(&fixture("localhost.crt"), &fixture("localhost.key")).unwrap();
+    let server = ServerBuilder::new().with_tls(config).build().unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let serving = tokio::spawn({
+        let server = server.clone();
+        async move { server.serve_tcp(listener).await }
+    });
+
+    let client_config = tls::client_config(&fixture("localhost.crt")).unwrap();
+    let transport = TlsTcpTransport::connect(client_config, &addr, "localhost")
+        .await
+        .unwrap();
+    let client = Client::new(transport);
+
+    let init = client.call("initialize", None).await.unwrap();
+    assert_eq!(init["serverInfo"]["name"], "pmcp");
+
+    drop(client);
+    server.shutdown();
+    serving.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_tls_rejects_wrong_server_name() {
+    let config = tls::server_config(&fixture("localhost.crt"), &fixture("localhost.key")).unwrap();
+    let server = ServerBuilder::new().with_tls(config).build().unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let serving = tokio::spawn({
+        let server = server.clone();
+        async move { server.serve_tcp(listener).await }
+    });
+
+    let client_config = tls::client_config(&fixture("localhost.crt")).unwrap();
+    let connected = TlsTcpTransport::connect(client_config, &addr, "example.com").await;
+    assert!(connected.is_err());
+
+    server.shutdown();
+    serving.await.unwrap().unwrap();
+}